@@ -1,10 +1,5 @@
-use std::iter::once;
-
 use cgmath::Deg;
-use wgpu::{
-    CommandEncoderDescriptor, IndexFormat, LoadOp, MaintainBase, Operations,
-    RenderPassColorAttachment, RenderPassDepthStencilAttachment, RenderPassDescriptor,
-};
+use wgpu::MaintainBase;
 use winit::{
     dpi::{PhysicalSize, Size},
     event::{Event, WindowEvent},
@@ -13,8 +8,8 @@ use winit::{
 };
 
 use crate::engine::{
-    Camera, EngineError, EngineResult, Projection, TAmbientLight, TComputingEngine, TPointLight,
-    TRenderingEngine, TTexture, TextureHelper, WGPURenderingEngine,
+    Camera, EngineError, EngineResult, Projection, RenderFrame, TComputingEngine, TRenderingEngine,
+    WGPURenderingEngine,
 };
 
 mod input;
@@ -33,10 +28,6 @@ pub struct App {
     timer: Timer,
     input_handler: InputHandler,
     camera: Camera,
-    #[cfg(debug_assertions)]
-    last_draw_calls: u32,
-    #[cfg(debug_assertions)]
-    last_triangle_count: u32,
 }
 
 impl App {
@@ -59,7 +50,7 @@ impl App {
 
         let rendering_engine = WGPURenderingEngine::new(&window)?;
 
-        let world = world_builder.build(rendering_engine.logical_device());
+        let world = world_builder.build(rendering_engine.logical_device())?;
 
         let timer = Timer::new();
 
@@ -89,11 +80,10 @@ impl App {
             timer,
             input_handler,
             camera,
-            #[cfg(debug_assertions)]
-            last_draw_calls: 0,
-            #[cfg(debug_assertions)]
-            last_triangle_count: 0,
         };
+        app.apply_requested_msaa_sample_count();
+        app.apply_post_process_changes();
+        app.apply_render_graph_changes();
 
         event_loop.run(move |event, _, control_flow| {
             // Immediately start a new cycle once a loop is completed.
@@ -185,6 +175,14 @@ impl App {
             .set_surface_configuration(current_config);
         self.rendering_engine.reconfigure_surface();
 
+        // Recreate size dependent render targets in the new size
+        if let Err(e) = self
+            .rendering_engine
+            .resize_render_targets(new_size.width, new_size.height)
+        {
+            log::error!("Failed to resize render targets: {:?}", e);
+        }
+
         // Change projection
         let old_projection = self.camera.projection();
         let projection = Projection::new(
@@ -198,89 +196,32 @@ impl App {
     }
 
     fn handle_redraw(&mut self) -> EngineResult<()> {
-        #[cfg(debug_assertions)]
-        {
-            self.last_draw_calls = 0;
-            self.last_triangle_count = 0;
-        }
-
-        let surface_texture = self.rendering_engine.surface_texture()?;
-        let surface_texture_view = surface_texture.make_texture_view();
-
-        let mut command_encoder =
-            self.rendering_engine
-                .device()
-                .create_command_encoder(&CommandEncoderDescriptor {
-                    label: Some("Command Encoder"),
-                });
+        let clear_color = self.world.clear_color();
 
-        {
-            let mut render_pass = command_encoder.begin_render_pass(&RenderPassDescriptor {
-                label: Some("Render Pass"),
-                color_attachments: &[Some(RenderPassColorAttachment {
-                    view: &surface_texture_view,
-                    resolve_target: None,
-                    ops: Operations {
-                        load: LoadOp::Clear(self.world.clear_color()),
-                        store: true,
-                    },
-                })],
-                depth_stencil_attachment: Some(RenderPassDepthStencilAttachment {
-                    view: self
-                        .rendering_engine
-                        .depth_texture()
-                        .expect("Depth Texture gone missing!")
-                        .view(),
-                    depth_ops: Some(Operations {
-                        load: LoadOp::Clear(1.0),
-                        store: true,
-                    }),
-                    stencil_ops: None,
-                }),
-            });
-
-            render_pass.set_pipeline(self.rendering_engine.render_pipeline());
-
-            // Call entity renderables
-            let (meshes, ambient_light, point_lights) = self
-                .world
-                .prepare_render_and_collect_data(self.rendering_engine.logical_device());
-
-            meshes.iter().for_each(|x| {
-                #[cfg(debug_assertions)]
-                {
-                    self.last_draw_calls += 1;
-                    self.last_triangle_count += x.instance_count() / 3;
-                }
-
-                // Vertex & Instance Buffer
-                render_pass.set_vertex_buffer(0, x.vertex_buffer().slice(..));
-                render_pass.set_vertex_buffer(1, x.instance_buffer().slice(..));
-
-                // Index Buffer
-                render_pass.set_index_buffer(x.index_buffer().slice(..), IndexFormat::Uint32);
-
-                // Texture / Material
-                render_pass.set_bind_group(0, x.material().bind_group(), &[]);
-
-                // Camera
-                render_pass.set_bind_group(1, self.camera.bind_group(), &[]);
-
-                // Ambient Light
-                render_pass.set_bind_group(2, ambient_light.bind_group(), &[]);
-
-                // Point Light
-                render_pass.set_bind_group(3, point_lights[0].bind_group(), &[]);
-
-                render_pass.draw_indexed(0..x.index_count(), 0, 0..x.instance_count());
-            });
+        // Skybox follows the camera rotation
+        if let Some(skybox) = self.world.skybox() {
+            skybox.update_buffer(self.rendering_engine.logical_device(), &self.camera);
         }
 
-        let command_buffer = command_encoder.finish();
-        self.rendering_engine.queue().submit(once(command_buffer));
-        surface_texture.present();
+        // Call entity renderables
+        let (meshes, ambient_light, light_storage, skybox, environment_map) = self
+            .world
+            .prepare_render_and_collect_data(self.rendering_engine.logical_device());
+
+        // Culling, shadows, the scene, post-processing & whatever
+        // got added happens in the render graph
+        let frame = RenderFrame::new(
+            self.rendering_engine.logical_device(),
+            &self.camera,
+            clear_color,
+            meshes,
+            ambient_light,
+            light_storage,
+            skybox,
+            environment_map,
+        );
 
-        Ok(())
+        self.rendering_engine.render(&frame)
     }
 
     fn handle_main_events_cleared(&mut self, window: &mut Window, control_flow: &mut ControlFlow) {
@@ -312,6 +253,12 @@ impl App {
                 {
                     log::debug!("Draw Calls: {}", self.last_draw_calls());
                     log::debug!("Triangle Count: {}", self.last_triangle_count());
+                    log::debug!("Culled Meshes: {}", self.last_culled_mesh_count());
+                    log::debug!("Culled Instances: {}", self.last_culled_instance_count());
+                    log::debug!(
+                        "Indirect Draw Calls: {} (instances culled on GPU)",
+                        self.last_indirect_draw_calls()
+                    );
                 }
             }
 
@@ -325,19 +272,81 @@ impl App {
             );
         }
 
+        self.apply_requested_msaa_sample_count();
+        self.apply_post_process_changes();
+        self.apply_render_graph_changes();
+
+        #[cfg(debug_assertions)]
+        self.rendering_engine.reload_changed_shaders();
+
         let exit = self.input_handler.post_update(window);
         if exit {
             *control_flow = ControlFlow::Exit;
         }
     }
 
+    fn apply_requested_msaa_sample_count(&mut self) {
+        if let Some(sample_count) = self.world.take_requested_msaa_sample_count() {
+            match self.rendering_engine.set_sample_count(sample_count) {
+                Ok(()) => log::info!("MSAA sample count set to {}", sample_count),
+                Err(e) => log::error!(
+                    "Failed to set MSAA sample count to {} (supported: {:?}): {:?}",
+                    sample_count,
+                    self.rendering_engine.supported_sample_counts(),
+                    e
+                ),
+            }
+        }
+    }
+
+    fn apply_post_process_changes(&mut self) {
+        for post_process_change in self.world.take_post_process_changes() {
+            if let Err(e) = self
+                .rendering_engine
+                .apply_post_process_change(post_process_change)
+            {
+                log::error!("Failed to apply post-processing change: {:?}", e);
+            }
+        }
+    }
+
+    fn apply_render_graph_changes(&mut self) {
+        for render_graph_change in self.world.take_render_graph_changes() {
+            self.rendering_engine
+                .apply_render_graph_change(render_graph_change);
+        }
+    }
+
     #[cfg(debug_assertions)]
     pub fn last_draw_calls(&self) -> u32 {
-        self.last_draw_calls
+        self.rendering_engine.last_render_statistics().draw_calls
     }
 
     #[cfg(debug_assertions)]
     pub fn last_triangle_count(&self) -> u32 {
-        self.last_triangle_count
+        self.rendering_engine
+            .last_render_statistics()
+            .triangle_count
+    }
+
+    #[cfg(debug_assertions)]
+    pub fn last_culled_mesh_count(&self) -> u32 {
+        self.rendering_engine
+            .last_render_statistics()
+            .culled_mesh_count
+    }
+
+    #[cfg(debug_assertions)]
+    pub fn last_culled_instance_count(&self) -> u32 {
+        self.rendering_engine
+            .last_render_statistics()
+            .culled_instance_count
+    }
+
+    #[cfg(debug_assertions)]
+    pub fn last_indirect_draw_calls(&self) -> u32 {
+        self.rendering_engine
+            .last_render_statistics()
+            .indirect_draw_calls
     }
 }
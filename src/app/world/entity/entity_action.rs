@@ -2,7 +2,9 @@ use std::mem::discriminant;
 
 use wgpu::Color;
 
-use crate::engine::CameraChange;
+use crate::engine::{
+    CameraChange, LightChange, PostProcessChange, RenderGraphChange, SkyboxSource,
+};
 
 use super::BoxedEntity;
 
@@ -11,6 +13,16 @@ pub enum EntityAction {
     Spawn(Vec<BoxedEntity>),
     Remove(Vec<String>),
     CameraChange(CameraChange),
+    LightChange(LightChange),
+    /// Loads and shows a new skybox, `None` removes the skybox
+    SkyboxChange(Option<SkyboxSource>),
+    /// Bakes a new environment map for ambient lighting,
+    /// `None` falls back to the ambient light
+    EnvironmentMapChange(Option<SkyboxSource>),
+    MsaaSampleCountChange(u32),
+    PostProcessChange(PostProcessChange),
+    /// Adds or removes nodes of the render graph, e.g. UI or debug overlays
+    RenderGraphChange(RenderGraphChange),
     Keep,
 }
 
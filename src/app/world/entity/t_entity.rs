@@ -1,6 +1,6 @@
 use crate::{
     app::InputHandler,
-    engine::{EngineResult, LogicalDevice, TMesh},
+    engine::{EngineResult, Light, LogicalDevice, TMesh},
 };
 
 use super::{EntityAction, EntityConfiguration};
@@ -16,7 +16,21 @@ pub trait TEntity {
         Ok(())
     }
 
+    /// Entities with meshes have to implement [`TEntity::meshes_mut`] as well
     fn meshes(&self) -> Vec<&dyn TMesh> {
         vec![]
     }
+
+    /// Mutable access to the same meshes as [`TEntity::meshes`].
+    /// Used to upload instance changes before rendering,
+    /// debug builds check that both return the same amount of meshes.
+    fn meshes_mut(&mut self) -> Vec<&mut dyn TMesh> {
+        vec![]
+    }
+
+    /// Lights carried by the entity, e.g. the light of a torch.
+    /// Collected every frame, thus the lights can move or change freely.
+    fn lights(&self) -> Vec<Light> {
+        vec![]
+    }
 }
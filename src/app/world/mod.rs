@@ -1,6 +1,9 @@
 use wgpu::Color;
 
-use crate::engine::{Camera, LogicalDevice, StandardAmbientLight, StandardPointLight, TMesh};
+use crate::engine::{
+    Camera, DebugDraw, EngineResult, EnvironmentMap, LightStorage, LogicalDevice,
+    PostProcessChange, RenderGraphChange, Skybox, StandardAmbientLight, TMesh,
+};
 
 use super::InputHandler;
 
@@ -18,7 +21,13 @@ pub struct World {
     entity_tag_duplication_behaviour: EntityTagDuplicationBehaviour,
     entities: Vec<EntityContainer>,
     ambient_light: StandardAmbientLight,
-    point_lights: [StandardPointLight; 4],
+    light_storage: LightStorage,
+    skybox: Option<Skybox>,
+    environment_map: EnvironmentMap,
+    requested_msaa_sample_count: Option<u32>,
+    post_process_changes: Vec<PostProcessChange>,
+    render_graph_changes: Vec<RenderGraphChange>,
+    light_gizmos: bool,
 }
 
 impl World {
@@ -29,7 +38,10 @@ impl World {
         a: 1.0,
     };
 
-    pub fn from_builder(builder: WorldBuilder, logical_device: &LogicalDevice) -> Self {
+    pub fn from_builder(
+        builder: WorldBuilder,
+        logical_device: &LogicalDevice,
+    ) -> EngineResult<Self> {
         builder.build(logical_device)
     }
 
@@ -143,6 +155,16 @@ impl World {
             .collect()
     }
 
+    pub fn prepared_renderable_mut(&mut self) -> Vec<&mut BoxedEntity> {
+        self.entities
+            .iter_mut()
+            .filter(|container| {
+                container.is_prepared() && container.entity_configuration().do_render()
+            })
+            .map(|container| container.entity_mut())
+            .collect()
+    }
+
     pub fn unprepared_renderable(&mut self) -> Vec<&mut EntityContainer> {
         self.entities
             .iter_mut()
@@ -156,6 +178,48 @@ impl World {
         self.clear_color
     }
 
+    pub fn skybox(&self) -> Option<&Skybox> {
+        self.skybox.as_ref()
+    }
+
+    /// Replaces the skybox, `None` falls back to the clear color
+    pub fn set_skybox(&mut self, skybox: Option<Skybox>) {
+        self.skybox = skybox;
+    }
+
+    pub fn environment_map(&self) -> &EnvironmentMap {
+        &self.environment_map
+    }
+
+    /// Replaces the environment map used for ambient lighting,
+    /// [`EnvironmentMap::empty`] falls back to the ambient light
+    pub fn set_environment_map(&mut self, environment_map: EnvironmentMap) {
+        self.environment_map = environment_map;
+    }
+
+    pub fn light_storage(&self) -> &LightStorage {
+        &self.light_storage
+    }
+
+    pub fn light_storage_mut(&mut self) -> &mut LightStorage {
+        &mut self.light_storage
+    }
+
+    /// Returns the MSAA sample count requested since the last call, if any
+    pub fn take_requested_msaa_sample_count(&mut self) -> Option<u32> {
+        self.requested_msaa_sample_count.take()
+    }
+
+    /// Returns the post-processing changes requested since the last call
+    pub fn take_post_process_changes(&mut self) -> Vec<PostProcessChange> {
+        std::mem::take(&mut self.post_process_changes)
+    }
+
+    /// Returns the render graph changes requested since the last call
+    pub fn take_render_graph_changes(&mut self) -> Vec<RenderGraphChange> {
+        std::mem::take(&mut self.render_graph_changes)
+    }
+
     pub fn call_updateable(
         &mut self,
         frequency: UpdateFrequency,
@@ -190,6 +254,47 @@ impl World {
                     println!("{delta_time}");
                     camera.apply_camera_change(delta_time, logical_device, camera_change);
                 }
+                EntityAction::LightChange(light_change) => {
+                    self.light_storage.apply_light_change(light_change);
+                }
+                EntityAction::SkyboxChange(skybox_source) => match skybox_source {
+                    Some(skybox_source) => match Skybox::new(logical_device, &skybox_source) {
+                        Ok(skybox) => self.skybox = Some(skybox),
+                        Err(e) => {
+                            log::error!("Failed to load skybox from {:?}: {:?}", skybox_source, e)
+                        }
+                    },
+                    None => self.skybox = None,
+                },
+                EntityAction::EnvironmentMapChange(environment_source) => {
+                    let intensity = self.environment_map.intensity();
+                    let environment_map = match &environment_source {
+                        Some(environment_source) => EnvironmentMap::from_source(
+                            logical_device,
+                            environment_source,
+                            intensity,
+                        ),
+                        None => EnvironmentMap::empty(logical_device),
+                    };
+
+                    match environment_map {
+                        Ok(environment_map) => self.environment_map = environment_map,
+                        Err(e) => log::error!(
+                            "Failed to load environment map from {:?}: {:?}",
+                            environment_source,
+                            e
+                        ),
+                    }
+                }
+                EntityAction::MsaaSampleCountChange(sample_count) => {
+                    self.requested_msaa_sample_count = Some(sample_count);
+                }
+                EntityAction::PostProcessChange(post_process_change) => {
+                    self.post_process_changes.push(post_process_change);
+                }
+                EntityAction::RenderGraphChange(render_graph_change) => {
+                    self.render_graph_changes.push(render_graph_change);
+                }
                 EntityAction::Keep => (),
             }
         }
@@ -201,13 +306,42 @@ impl World {
     ) -> (
         Vec<&dyn TMesh>,
         &StandardAmbientLight,
-        &[StandardPointLight; 4],
+        &LightStorage,
+        Option<&Skybox>,
+        &EnvironmentMap,
     ) {
         // Prepare rendere where needed
         self.unprepared_renderable()
             .iter_mut()
             .for_each(|x| x.prepare_entity(logical_device));
 
+        // Upload changed instances
+        self.prepared_renderable_mut()
+            .into_iter()
+            .flat_map(|x| {
+                debug_assert_eq!(
+                    x.meshes().len(),
+                    x.meshes_mut().len(),
+                    "'{}' has to return the same meshes from meshes & meshes_mut",
+                    x.entity_configuration().tag()
+                );
+                x.meshes_mut()
+            })
+            .for_each(|x| x.update_instance_buffer(logical_device));
+
+        // Collect entity lights & upload changed lights
+        let entity_lights = self
+            .entities
+            .iter()
+            .flat_map(|x| x.entity().lights())
+            .collect();
+        self.light_storage.set_entity_lights(entity_lights);
+        self.light_storage.update_buffer(logical_device);
+
+        if self.light_gizmos {
+            DebugDraw::OVERLAY.lights(&self.light_storage);
+        }
+
         // Retrieve meshes
         (
             self.prepared_renderable()
@@ -215,7 +349,9 @@ impl World {
                 .flat_map(|x| x.meshes())
                 .collect::<Vec<_>>(),
             &self.ambient_light,
-            &self.point_lights,
+            &self.light_storage,
+            self.skybox.as_ref(),
+            &self.environment_map,
         )
     }
 }
@@ -1,7 +1,12 @@
-use cgmath::Vector3;
+use cgmath::{Rad, Vector3};
 use wgpu::Color;
 
-use crate::engine::{LogicalDevice, StandardAmbientLight, StandardPointLight};
+use crate::engine::{
+    BoxedRenderNode, EngineResult, EnvironmentMap, LightStorage, LogicalDevice, PostProcessChange,
+    PostProcessPassDescriptor, PostProcessSettings, RenderGraphChange, ShadowSettings, Skybox,
+    SkyboxSource, StandardAmbientLight, StandardDirectionalLight, StandardPointLight,
+    StandardSpotLight,
+};
 
 use super::{BoxedEntity, EntityTagDuplicationBehaviour, World};
 
@@ -92,12 +97,39 @@ impl From<((f32, f32, f32), (f32, f32, f32), f32)> for WPointLight {
     }
 }
 
+#[derive(Debug, Clone, Copy)]
+pub struct WDirectionalLight {
+    pub color: WColor,
+    pub direction: Vector3<f32>,
+    pub strength: f32,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct WSpotLight {
+    pub color: WColor,
+    pub position: WPosition,
+    pub direction: Vector3<f32>,
+    pub inner_cone_angle: Rad<f32>,
+    pub outer_cone_angle: Rad<f32>,
+    pub strength: f32,
+}
+
 pub struct WorldBuilder {
     clear_color: Option<Color>,
     entity_tag_duplication_behaviour: Option<EntityTagDuplicationBehaviour>,
     entities: Vec<BoxedEntity>,
     ambient_light: Option<WAmbientLight>,
-    point_light: [Option<WPointLight>; 4],
+    point_lights: Vec<WPointLight>,
+    directional_lights: Vec<WDirectionalLight>,
+    spot_lights: Vec<WSpotLight>,
+    shadow_settings: Option<ShadowSettings>,
+    skybox: Option<SkyboxSource>,
+    environment_map: Option<(SkyboxSource, f32)>,
+    msaa_sample_count: Option<u32>,
+    post_process_settings: Option<PostProcessSettings>,
+    post_process_passes: Vec<PostProcessPassDescriptor>,
+    render_nodes: Vec<BoxedRenderNode>,
+    light_gizmos: bool,
 }
 
 impl WorldBuilder {
@@ -107,11 +139,21 @@ impl WorldBuilder {
             entity_tag_duplication_behaviour: None,
             entities: vec![],
             ambient_light: None,
-            point_light: [None, None, None, None],
+            point_lights: vec![],
+            directional_lights: vec![],
+            spot_lights: vec![],
+            shadow_settings: None,
+            skybox: None,
+            environment_map: None,
+            msaa_sample_count: None,
+            post_process_settings: None,
+            post_process_passes: vec![],
+            render_nodes: vec![],
+            light_gizmos: false,
         }
     }
 
-    pub fn build(self, logical_device: &LogicalDevice) -> World {
+    pub fn build(self, logical_device: &LogicalDevice) -> EngineResult<World> {
         let ambient_light_raw = self.ambient_light.unwrap_or(((1.0, 1.0, 1.0), 0.1).into());
         let ambient_light = StandardAmbientLight::new(
             logical_device,
@@ -119,19 +161,73 @@ impl WorldBuilder {
             ambient_light_raw.strength,
         );
 
-        let mut point_lights = vec![];
-        for point_light in self.point_light {
-            let point_light_data = point_light.unwrap_or_default();
-
-            let point_light = StandardPointLight::new(
-                logical_device,
-                point_light_data.color.into(),
-                point_light_data.position.into(),
-                point_light_data.strength,
-                point_light.is_some(),
-            );
-            point_lights.push(point_light);
-        }
+        let point_lights = self
+            .point_lights
+            .into_iter()
+            .map(|point_light| {
+                StandardPointLight::new(
+                    point_light.color.into(),
+                    point_light.position.into(),
+                    point_light.strength,
+                    true,
+                )
+            })
+            .collect();
+        let directional_lights = self
+            .directional_lights
+            .into_iter()
+            .map(|directional_light| {
+                StandardDirectionalLight::new(
+                    directional_light.color.into(),
+                    directional_light.direction,
+                    directional_light.strength,
+                    true,
+                )
+            })
+            .collect();
+        let spot_lights = self
+            .spot_lights
+            .into_iter()
+            .map(|spot_light| {
+                StandardSpotLight::new(
+                    spot_light.color.into(),
+                    spot_light.position.into(),
+                    spot_light.direction,
+                    spot_light.inner_cone_angle,
+                    spot_light.outer_cone_angle,
+                    spot_light.strength,
+                    true,
+                )
+            })
+            .collect();
+        let light_storage = LightStorage::new(
+            logical_device,
+            point_lights,
+            directional_lights,
+            spot_lights,
+            self.shadow_settings.unwrap_or_default(),
+        )?;
+
+        let skybox = self
+            .skybox
+            .as_ref()
+            .map(|skybox_source| Skybox::new(logical_device, skybox_source))
+            .transpose()?;
+
+        // Reuses the already loaded skybox cubemap if both share their source
+        let environment_map = match (&self.environment_map, &skybox) {
+            (Some((source, intensity)), Some(skybox)) if self.skybox.as_ref() == Some(source) => {
+                EnvironmentMap::from_cube_texture(
+                    logical_device,
+                    skybox.cube_texture(),
+                    *intensity,
+                )?
+            }
+            (Some((source, intensity)), _) => {
+                EnvironmentMap::from_source(logical_device, source, *intensity)?
+            }
+            (None, _) => EnvironmentMap::empty(logical_device)?,
+        };
 
         let mut world = World {
             clear_color: self.clear_color.unwrap_or(Color::BLACK),
@@ -140,20 +236,33 @@ impl WorldBuilder {
                 .unwrap_or(EntityTagDuplicationBehaviour::WarnOnDuplication),
             entities: vec![],
             ambient_light,
-            point_lights: [
-                // Take 4x times the zero'th entry. Assumes there are 4 lights.
-                point_lights.remove(0),
-                point_lights.remove(0),
-                point_lights.remove(0),
-                point_lights.remove(0),
-            ],
+            light_storage,
+            skybox,
+            environment_map,
+            requested_msaa_sample_count: self.msaa_sample_count,
+            post_process_changes: self
+                .post_process_settings
+                .map(PostProcessChange::Settings)
+                .into_iter()
+                .chain(
+                    self.post_process_passes
+                        .into_iter()
+                        .map(PostProcessChange::AddPass),
+                )
+                .collect(),
+            render_graph_changes: self
+                .render_nodes
+                .into_iter()
+                .map(RenderGraphChange::AddNode)
+                .collect(),
+            light_gizmos: self.light_gizmos,
         };
 
         for entity in self.entities {
             world.add_entity(entity);
         }
 
-        world
+        Ok(world)
     }
 
     pub fn with_clear_color(mut self, color: Color) -> Self {
@@ -179,14 +288,119 @@ impl WorldBuilder {
         self
     }
 
+    /// Adds a point light to the world.
+    /// Can be called any amount of times.
     pub fn with_point_light(
         mut self,
-        slot: usize,
         color: Vector3<f32>,
         position: Vector3<f32>,
         strength: f32,
     ) -> Self {
-        self.point_light[slot] = Some((color.into(), position.into(), strength).into());
+        self.point_lights
+            .push((color.into(), position.into(), strength).into());
+        self
+    }
+
+    /// Adds a directional light, like the sun, to the world.
+    /// `direction` is the direction the light is travelling in.
+    /// Can be called any amount of times.
+    pub fn with_directional_light(
+        mut self,
+        color: Vector3<f32>,
+        direction: Vector3<f32>,
+        strength: f32,
+    ) -> Self {
+        let color: (f32, f32, f32) = color.into();
+        self.directional_lights.push(WDirectionalLight {
+            color: color.into(),
+            direction,
+            strength,
+        });
+        self
+    }
+
+    /// Adds a spot light to the world.
+    /// Inside the `inner_cone_angle` the light has full strength and fades
+    /// out until the `outer_cone_angle`.
+    /// Can be called any amount of times.
+    pub fn with_spot_light<I: Into<Rad<f32>>, O: Into<Rad<f32>>>(
+        mut self,
+        color: Vector3<f32>,
+        position: Vector3<f32>,
+        direction: Vector3<f32>,
+        inner_cone_angle: I,
+        outer_cone_angle: O,
+        strength: f32,
+    ) -> Self {
+        let color: (f32, f32, f32) = color.into();
+        let position: (f32, f32, f32) = position.into();
+        self.spot_lights.push(WSpotLight {
+            color: color.into(),
+            position: position.into(),
+            direction,
+            inner_cone_angle: inner_cone_angle.into(),
+            outer_cone_angle: outer_cone_angle.into(),
+            strength,
+        });
+        self
+    }
+
+    /// Shadow map resolution, layer count and biases.
+    /// See [`ShadowSettings`] for the defaults.
+    pub fn with_shadow_settings(mut self, shadow_settings: ShadowSettings) -> Self {
+        self.shadow_settings = Some(shadow_settings);
+        self
+    }
+
+    /// Renders the given cubemap behind all geometry instead of the clear color
+    pub fn with_skybox(mut self, skybox_source: SkyboxSource) -> Self {
+        self.skybox = Some(skybox_source);
+        self
+    }
+
+    /// Lights the world with image-based lighting baked from the given
+    /// HDR environment instead of the flat ambient light.
+    /// Usually the same source as the skybox, which then gets loaded only once.
+    pub fn with_environment_map(mut self, source: SkyboxSource, intensity: f32) -> Self {
+        self.environment_map = Some((source, intensity));
+        self
+    }
+
+    /// Sets the MSAA sample count (1 = no MSAA).
+    /// An unsupported sample count gets logged as an error at startup
+    /// and rendering continues without MSAA.
+    pub fn with_msaa_sample_count(mut self, sample_count: u32) -> Self {
+        self.msaa_sample_count = Some(sample_count);
+        self
+    }
+
+    /// Sets exposure, tonemapping and bloom
+    pub fn with_post_process_settings(
+        mut self,
+        post_process_settings: PostProcessSettings,
+    ) -> Self {
+        self.post_process_settings = Some(post_process_settings);
+        self
+    }
+
+    /// Adds a custom post-processing pass.
+    /// Can be called any amount of times, passes run in the order they were added.
+    pub fn with_post_process_pass(mut self, descriptor: PostProcessPassDescriptor) -> Self {
+        self.post_process_passes.push(descriptor);
+        self
+    }
+
+    /// Adds a node to the render graph, e.g. a UI or debug overlay.
+    /// It runs wherever the resources it declares require.
+    pub fn with_render_node(mut self, node: BoxedRenderNode) -> Self {
+        self.render_nodes.push(node);
+        self
+    }
+
+    /// Draws the position & range of every light each frame,
+    /// see [`crate::engine::DebugDraw::lights`]. Debug builds only.
+    pub fn with_light_gizmos(mut self, light_gizmos: bool) -> Self {
+        self.light_gizmos = light_gizmos;
         self
     }
 }
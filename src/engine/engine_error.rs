@@ -21,4 +21,9 @@ pub enum EngineError {
     GltfNoScene,
     GltfNoModel,
     GltfNoOrFailedMaterial,
+    UnsupportedSampleCount(u32),
+    ShaderError(String),
+    ShaderLayoutMismatch(String),
+    RenderGraphError(String),
+    CubeTextureFaceMismatch,
 }
@@ -19,3 +19,12 @@ pub fn hex_to_f32_color(hex: &str) -> Result<(f32, f32, f32), ParseIntError> {
 
     Ok(rgb_to_f32_color(r, g, b))
 }
+
+/// Converts a single sRGB encoded channel (0.0-1.0) into linear space
+pub fn srgb_to_linear(value: f32) -> f32 {
+    if value <= 0.04045 {
+        value / 12.92
+    } else {
+        ((value + 0.055) / 1.055).powf(2.4)
+    }
+}
@@ -0,0 +1,118 @@
+use cgmath::{InnerSpace, Matrix4, Vector3, Vector4};
+
+use crate::engine::TVertex;
+
+/// Axis-aligned bounding box
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BoundingBox {
+    min: Vector3<f32>,
+    max: Vector3<f32>,
+}
+
+impl BoundingBox {
+    pub fn new(min: Vector3<f32>, max: Vector3<f32>) -> Self {
+        Self { min, max }
+    }
+
+    /// An "inverted" box that contains nothing.
+    /// Extending it with any point results in a box around said point.
+    pub fn empty() -> Self {
+        Self {
+            min: Vector3::new(f32::MAX, f32::MAX, f32::MAX),
+            max: Vector3::new(f32::MIN, f32::MIN, f32::MIN),
+        }
+    }
+
+    pub fn from_vertices<V>(vertices: &[V]) -> Self
+    where
+        V: TVertex,
+    {
+        vertices.iter().fold(Self::empty(), |bounding_box, vertex| {
+            bounding_box.extended_by_point(vertex.position_coordinates().into())
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.min.x > self.max.x || self.min.y > self.max.y || self.min.z > self.max.z
+    }
+
+    pub fn extended_by_point(self, point: Vector3<f32>) -> Self {
+        Self {
+            min: Vector3::new(
+                self.min.x.min(point.x),
+                self.min.y.min(point.y),
+                self.min.z.min(point.z),
+            ),
+            max: Vector3::new(
+                self.max.x.max(point.x),
+                self.max.y.max(point.y),
+                self.max.z.max(point.z),
+            ),
+        }
+    }
+
+    pub fn extended_by_box(self, other: &BoundingBox) -> Self {
+        if other.is_empty() {
+            return self;
+        }
+
+        self.extended_by_point(other.min)
+            .extended_by_point(other.max)
+    }
+
+    /// Transforms all eight corners of the box and builds a new
+    /// axis-aligned box around them.
+    pub fn transformed(&self, matrix: &Matrix4<f32>) -> Self {
+        if self.is_empty() {
+            return *self;
+        }
+
+        self.corners()
+            .iter()
+            .fold(Self::empty(), |bounding_box, corner| {
+                let transformed = matrix * Vector4::new(corner.x, corner.y, corner.z, 1.0);
+                bounding_box.extended_by_point(transformed.truncate())
+            })
+    }
+
+    pub fn corners(&self) -> [Vector3<f32>; 8] {
+        [
+            Vector3::new(self.min.x, self.min.y, self.min.z),
+            Vector3::new(self.max.x, self.min.y, self.min.z),
+            Vector3::new(self.min.x, self.max.y, self.min.z),
+            Vector3::new(self.max.x, self.max.y, self.min.z),
+            Vector3::new(self.min.x, self.min.y, self.max.z),
+            Vector3::new(self.max.x, self.min.y, self.max.z),
+            Vector3::new(self.min.x, self.max.y, self.max.z),
+            Vector3::new(self.max.x, self.max.y, self.max.z),
+        ]
+    }
+
+    pub fn center(&self) -> Vector3<f32> {
+        (self.min + self.max) * 0.5
+    }
+
+    pub fn half_extents(&self) -> Vector3<f32> {
+        (self.max - self.min) * 0.5
+    }
+
+    /// Radius of the smallest sphere around [`Self::center`] that
+    /// contains the whole box.
+    pub fn radius(&self) -> f32 {
+        self.half_extents().magnitude()
+    }
+
+    pub fn min(&self) -> Vector3<f32> {
+        self.min
+    }
+
+    pub fn max(&self) -> Vector3<f32> {
+        self.max
+    }
+}
+
+impl Default for BoundingBox {
+    fn default() -> Self {
+        Self::empty()
+    }
+}
@@ -0,0 +1,49 @@
+use cgmath::{InnerSpace, Matrix4, Vector3};
+
+use super::BoundingBox;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BoundingSphere {
+    center: Vector3<f32>,
+    radius: f32,
+}
+
+impl BoundingSphere {
+    pub fn new(center: Vector3<f32>, radius: f32) -> Self {
+        Self { center, radius }
+    }
+
+    pub fn from_bounding_box(bounding_box: &BoundingBox) -> Self {
+        Self::new(bounding_box.center(), bounding_box.radius())
+    }
+
+    /// Moves the sphere into the space of the given matrix.
+    /// The radius is scaled by the largest axis scale of the matrix, so the
+    /// sphere stays conservative for non-uniform scaling.
+    pub fn transformed(&self, matrix: &Matrix4<f32>) -> Self {
+        let center = (matrix * self.center.extend(1.0)).truncate();
+
+        let max_scale = matrix
+            .x
+            .truncate()
+            .magnitude2()
+            .max(matrix.y.truncate().magnitude2())
+            .max(matrix.z.truncate().magnitude2())
+            .sqrt();
+
+        Self::new(center, self.radius * max_scale)
+    }
+
+    pub fn to_bounding_box(&self) -> BoundingBox {
+        let extent = Vector3::new(self.radius, self.radius, self.radius);
+        BoundingBox::new(self.center - extent, self.center + extent)
+    }
+
+    pub fn center(&self) -> Vector3<f32> {
+        self.center
+    }
+
+    pub fn radius(&self) -> f32 {
+        self.radius
+    }
+}
@@ -0,0 +1,5 @@
+mod bounding_box;
+pub use bounding_box::*;
+
+mod bounding_sphere;
+pub use bounding_sphere::*;
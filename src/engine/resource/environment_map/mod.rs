@@ -0,0 +1,276 @@
+use std::{mem::size_of, num::NonZeroU64};
+
+use wgpu::{
+    BindGroup, BindGroupDescriptor, BindGroupEntry, BindGroupLayout, BindGroupLayoutDescriptor,
+    BindGroupLayoutEntry, BindingResource, BindingType, Buffer, BufferAddress, BufferBindingType,
+    BufferDescriptor, BufferUsages, Extent3d, SamplerBindingType, ShaderStages, TextureDescriptor,
+    TextureDimension, TextureSampleType, TextureUsages, TextureViewDimension,
+};
+
+use crate::engine::{
+    AbstractTexture, CubeTexture, EngineResult, EnvironmentMapBaker, LogicalDevice, SkyboxSource,
+    TTexture,
+};
+
+mod u_environment;
+pub use u_environment::*;
+
+/// Image-based lighting maps of an HDR environment, replacing the flat
+/// ambient light:
+/// - Irradiance map for diffuse lighting
+/// - Prefiltered map for specular reflections, rougher surfaces sample
+///   higher mip levels
+/// - BRDF lookup table, scale & bias of the specular Fresnel term
+///
+/// An [`Self::empty`] environment map falls back to the ambient light.
+pub struct EnvironmentMap {
+    irradiance_texture: CubeTexture,
+    prefiltered_texture: CubeTexture,
+    brdf_lut: AbstractTexture,
+    buffer: Buffer,
+    bind_group: BindGroup,
+    intensity: f32,
+    enabled: bool,
+}
+
+impl EnvironmentMap {
+    pub const IRRADIANCE_SIZE: u32 = 32;
+    pub const PREFILTERED_SIZE: u32 = 128;
+    pub const PREFILTERED_MIP_LEVEL_COUNT: u32 = 5;
+    pub const BRDF_LUT_SIZE: u32 = 256;
+
+    pub const BIND_GROUP_LAYOUT_DESCRIPTOR: BindGroupLayoutDescriptor<'static> =
+        BindGroupLayoutDescriptor {
+            label: Some("Environment Map Bind Group Layout"),
+            entries: &[
+                // Irradiance
+                BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Texture {
+                        sample_type: TextureSampleType::Float { filterable: true },
+                        view_dimension: TextureViewDimension::Cube,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                // Prefiltered
+                BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Texture {
+                        sample_type: TextureSampleType::Float { filterable: true },
+                        view_dimension: TextureViewDimension::Cube,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                // BRDF LUT
+                BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Texture {
+                        sample_type: TextureSampleType::Float { filterable: true },
+                        view_dimension: TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                BindGroupLayoutEntry {
+                    binding: 3,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Sampler(SamplerBindingType::Filtering),
+                    count: None,
+                },
+                BindGroupLayoutEntry {
+                    binding: 4,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Buffer {
+                        ty: BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: NonZeroU64::new(size_of::<UEnvironment>() as u64),
+                    },
+                    count: None,
+                },
+            ],
+        };
+
+    /// Placeholder without any lighting, the ambient light is used instead
+    pub fn empty(logical_device: &LogicalDevice) -> EngineResult<Self> {
+        Self::new(logical_device, 1, 1, 1, 1.0, false)
+    }
+
+    /// Loads the environment cubemap and bakes the lighting maps from it
+    pub fn from_source(
+        logical_device: &LogicalDevice,
+        source: &SkyboxSource,
+        intensity: f32,
+    ) -> EngineResult<Self> {
+        let cube_texture = source.load(logical_device, Some("Environment"))?;
+        Self::from_cube_texture(logical_device, &cube_texture, intensity)
+    }
+
+    /// Bakes the lighting maps from an HDR environment cubemap
+    /// (e.g. the one of a [`crate::engine::Skybox`])
+    pub fn from_cube_texture(
+        logical_device: &LogicalDevice,
+        source: &CubeTexture,
+        intensity: f32,
+    ) -> EngineResult<Self> {
+        let environment_map = Self::new(
+            logical_device,
+            Self::IRRADIANCE_SIZE,
+            Self::PREFILTERED_SIZE,
+            Self::BRDF_LUT_SIZE,
+            intensity,
+            true,
+        )?;
+
+        EnvironmentMapBaker::new(logical_device)?.bake(logical_device, source, &environment_map);
+
+        Ok(environment_map)
+    }
+
+    fn new(
+        logical_device: &LogicalDevice,
+        irradiance_size: u32,
+        prefiltered_size: u32,
+        brdf_lut_size: u32,
+        intensity: f32,
+        enabled: bool,
+    ) -> EngineResult<Self> {
+        let usage = TextureUsages::TEXTURE_BINDING | TextureUsages::STORAGE_BINDING;
+
+        let irradiance_texture = CubeTexture::from_empty(
+            logical_device,
+            irradiance_size,
+            1,
+            usage,
+            Some("Irradiance Texture"),
+        )?;
+        let prefiltered_texture = CubeTexture::from_empty(
+            logical_device,
+            prefiltered_size,
+            Self::PREFILTERED_MIP_LEVEL_COUNT.min(prefiltered_size.ilog2() + 1),
+            usage,
+            Some("Prefiltered Texture"),
+        )?;
+        let brdf_lut = AbstractTexture::from_descriptor(
+            logical_device,
+            &TextureDescriptor {
+                label: Some("BRDF LUT"),
+                size: Extent3d {
+                    width: brdf_lut_size,
+                    height: brdf_lut_size,
+                    depth_or_array_layers: 1,
+                },
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: TextureDimension::D2,
+                format: CubeTexture::TEXTURE_FORMAT,
+                usage,
+                view_formats: &[],
+            },
+            &CubeTexture::SAMPLER_DESCRIPTOR,
+        )?;
+
+        let buffer = logical_device.device().create_buffer(&BufferDescriptor {
+            label: Some("Environment Buffer"),
+            size: size_of::<UEnvironment>() as BufferAddress,
+            usage: BufferUsages::UNIFORM | BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let bind_group = logical_device
+            .device()
+            .create_bind_group(&BindGroupDescriptor {
+                label: Some("Environment Map Bind Group"),
+                layout: &Self::bind_group_layout(logical_device),
+                entries: &[
+                    BindGroupEntry {
+                        binding: 0,
+                        resource: BindingResource::TextureView(irradiance_texture.view()),
+                    },
+                    BindGroupEntry {
+                        binding: 1,
+                        resource: BindingResource::TextureView(prefiltered_texture.view()),
+                    },
+                    BindGroupEntry {
+                        binding: 2,
+                        resource: BindingResource::TextureView(brdf_lut.view()),
+                    },
+                    BindGroupEntry {
+                        binding: 3,
+                        resource: BindingResource::Sampler(prefiltered_texture.sampler()),
+                    },
+                    BindGroupEntry {
+                        binding: 4,
+                        resource: buffer.as_entire_binding(),
+                    },
+                ],
+            });
+
+        let environment_map = Self {
+            irradiance_texture,
+            prefiltered_texture,
+            brdf_lut,
+            buffer,
+            bind_group,
+            intensity,
+            enabled,
+        };
+        environment_map.update_buffer(logical_device);
+
+        Ok(environment_map)
+    }
+
+    pub fn bind_group_layout(logical_device: &LogicalDevice) -> BindGroupLayout {
+        logical_device
+            .device()
+            .create_bind_group_layout(&Self::BIND_GROUP_LAYOUT_DESCRIPTOR)
+    }
+
+    fn update_buffer(&self, logical_device: &LogicalDevice) {
+        let max_mip_level = self.prefiltered_texture.texture().mip_level_count() - 1;
+
+        logical_device.queue().write_buffer(
+            &self.buffer,
+            0,
+            bytemuck::cast_slice(&[UEnvironment::new(
+                self.intensity,
+                max_mip_level as f32,
+                self.enabled,
+            )]),
+        );
+    }
+
+    pub fn intensity(&self) -> f32 {
+        self.intensity
+    }
+
+    pub fn set_intensity(&mut self, logical_device: &LogicalDevice, intensity: f32) {
+        self.intensity = intensity;
+        self.update_buffer(logical_device);
+    }
+
+    /// False for an [`Self::empty`] environment map
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn irradiance_texture(&self) -> &CubeTexture {
+        &self.irradiance_texture
+    }
+
+    pub fn prefiltered_texture(&self) -> &CubeTexture {
+        &self.prefiltered_texture
+    }
+
+    pub fn brdf_lut(&self) -> &AbstractTexture {
+        &self.brdf_lut
+    }
+
+    pub fn bind_group(&self) -> &BindGroup {
+        &self.bind_group
+    }
+}
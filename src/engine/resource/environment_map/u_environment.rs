@@ -0,0 +1,23 @@
+use bytemuck::{Pod, Zeroable};
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Pod, Zeroable)]
+pub struct UEnvironment {
+    intensity: f32,
+    /// Mip level of the prefiltered map used for a roughness of 1.0
+    max_mip_level: f32,
+    /// 0 = use the ambient light instead
+    enabled: u32,
+    _padding: u32,
+}
+
+impl UEnvironment {
+    pub fn new(intensity: f32, max_mip_level: f32, enabled: bool) -> Self {
+        Self {
+            intensity,
+            max_mip_level,
+            enabled: enabled as u32,
+            _padding: 0,
+        }
+    }
+}
@@ -0,0 +1,38 @@
+/// Memory layout rules of WGSL host-shareable types
+///
+/// | Type          | Alignment | Size |
+/// |---------------|-----------|------|
+/// | `f32` etc.    | 4         | 4    |
+/// | `vec2<f32>`   | 8         | 8    |
+/// | `vec3<f32>`   | 16        | 12   |
+/// | `vec4<f32>`   | 16        | 16   |
+/// | `mat3x3<f32>` | 16        | 48   |
+/// | `mat4x4<f32>` | 16        | 64   |
+///
+/// Structures are aligned to their largest member & their size is
+/// rounded up to that alignment, e.g. a `vec3` followed by an `f32`
+/// doesn't need any padding, while two `vec3`'s need 4 bytes in between.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GpuLayout {
+    /// Uniform buffers: Additionally, structures & arrays are aligned to
+    /// 16 bytes and members following them start at a multiple of 16 bytes.
+    /// Array strides must be a multiple of 16 bytes as well,
+    /// e.g. `array<f32, 4>` isn't allowed but `array<vec4<f32>, 1>` is.
+    Std140,
+    /// Storage buffers
+    Std430,
+}
+
+impl GpuLayout {
+    pub fn round_up(alignment: usize, offset: usize) -> usize {
+        offset.div_ceil(alignment) * alignment
+    }
+
+    /// Alignment of a structure or array with the given natural alignment
+    pub fn composite_alignment(&self, alignment: usize) -> usize {
+        match self {
+            Self::Std140 => Self::round_up(16, alignment),
+            Self::Std430 => alignment,
+        }
+    }
+}
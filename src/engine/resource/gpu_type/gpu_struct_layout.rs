@@ -0,0 +1,237 @@
+use std::sync::OnceLock;
+
+use super::{GpuLayout, TGpuType};
+
+/// Alignment & size of a structure member, see [`GpuStructLayout`]
+#[derive(Debug, Clone, Copy)]
+pub struct GpuStructMember {
+    alignment: usize,
+    size: usize,
+    composite: bool,
+}
+
+impl GpuStructMember {
+    pub fn of<T>(layout: GpuLayout) -> Self
+    where
+        T: TGpuType,
+    {
+        Self {
+            alignment: T::alignment(layout),
+            size: T::size(layout),
+            composite: T::is_composite(),
+        }
+    }
+}
+
+/// Member offsets of a WGSL structure, used by `#[derive(GpuType)]`
+#[derive(Debug, Clone)]
+pub struct GpuStructLayout {
+    offsets: Vec<usize>,
+    sizes: Vec<usize>,
+    alignment: usize,
+    size: usize,
+}
+
+impl GpuStructLayout {
+    pub fn new(layout: GpuLayout, members: &[GpuStructMember]) -> Self {
+        let mut offsets = Vec::with_capacity(members.len());
+        let mut offset = 0;
+        let mut previous_composite = false;
+        for member in members {
+            if previous_composite && layout == GpuLayout::Std140 {
+                offset = GpuLayout::round_up(16, offset);
+            }
+            offset = GpuLayout::round_up(member.alignment, offset);
+            offsets.push(offset);
+
+            offset += member.size;
+            previous_composite = member.composite;
+        }
+
+        let alignment =
+            layout.composite_alignment(members.iter().map(|x| x.alignment).max().unwrap_or(1));
+
+        Self {
+            offsets,
+            sizes: members.iter().map(|x| x.size).collect(),
+            alignment,
+            size: GpuLayout::round_up(alignment, offset),
+        }
+    }
+
+    pub fn alignment(&self) -> usize {
+        self.alignment
+    }
+
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    pub fn offset(&self, member: usize) -> usize {
+        self.offsets[member]
+    }
+
+    /// The bytes of the member inside the bytes of the structure
+    pub fn member_bytes<'a>(&self, member: usize, bytes: &'a mut [u8]) -> &'a mut [u8] {
+        let offset = self.offsets[member];
+        &mut bytes[offset..offset + self.sizes[member]]
+    }
+
+    /// `struct Name { member: type, ... }`
+    pub fn wgsl_struct(name: &str, members: &[(&str, String)]) -> String {
+        let mut wgsl = format!("struct {} {{\n", name);
+        for (member, wgsl_type) in members {
+            wgsl += &format!("    {}: {},\n", member, wgsl_type);
+        }
+        wgsl += "}\n";
+        wgsl
+    }
+}
+
+/// The [`GpuStructLayout`] of a structure for each [`GpuLayout`], computed once.
+/// `#[derive(GpuType)]` keeps one in a static per structure.
+pub struct GpuStructLayoutCache {
+    std140: OnceLock<GpuStructLayout>,
+    std430: OnceLock<GpuStructLayout>,
+}
+
+impl GpuStructLayoutCache {
+    pub const fn new() -> Self {
+        Self {
+            std140: OnceLock::new(),
+            std430: OnceLock::new(),
+        }
+    }
+
+    pub fn get(
+        &self,
+        layout: GpuLayout,
+        members: impl FnOnce() -> Vec<GpuStructMember>,
+    ) -> &GpuStructLayout {
+        let cell = match layout {
+            GpuLayout::Std140 => &self.std140,
+            GpuLayout::Std430 => &self.std430,
+        };
+        cell.get_or_init(|| GpuStructLayout::new(layout, &members()))
+    }
+}
+
+impl Default for GpuStructLayoutCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cgmath::Vector3;
+
+    use super::*;
+    use crate::engine::{GpuType, UPointLight};
+
+    #[derive(GpuType)]
+    struct Inner {
+        value: f32,
+    }
+
+    #[derive(GpuType)]
+    struct Outer {
+        inner: Inner,
+        after: f32,
+    }
+
+    fn offsets(layout: &GpuStructLayout, count: usize) -> Vec<usize> {
+        (0..count).map(|x| layout.offset(x)).collect()
+    }
+
+    #[test]
+    fn vec3_followed_by_f32_shares_16_bytes() {
+        for layout in [GpuLayout::Std140, GpuLayout::Std430] {
+            let struct_layout = GpuStructLayout::new(
+                layout,
+                &[
+                    GpuStructMember::of::<Vector3<f32>>(layout),
+                    GpuStructMember::of::<f32>(layout),
+                ],
+            );
+
+            assert_eq!(offsets(&struct_layout, 2), [0, 12]);
+            assert_eq!(struct_layout.size(), 16);
+            assert_eq!(struct_layout.alignment(), 16);
+        }
+    }
+
+    #[test]
+    fn two_vec3_are_padded() {
+        for layout in [GpuLayout::Std140, GpuLayout::Std430] {
+            let struct_layout = GpuStructLayout::new(
+                layout,
+                &[
+                    GpuStructMember::of::<Vector3<f32>>(layout),
+                    GpuStructMember::of::<Vector3<f32>>(layout),
+                ],
+            );
+
+            assert_eq!(offsets(&struct_layout, 2), [0, 16]);
+            assert_eq!(struct_layout.size(), 32);
+        }
+    }
+
+    #[test]
+    fn member_after_nested_struct_starts_at_16_in_std140() {
+        let std140 = GpuStructLayout::new(
+            GpuLayout::Std140,
+            &[
+                GpuStructMember::of::<Inner>(GpuLayout::Std140),
+                GpuStructMember::of::<f32>(GpuLayout::Std140),
+            ],
+        );
+        assert_eq!(offsets(&std140, 2), [0, 16]);
+        assert_eq!(std140.size(), 32);
+        assert_eq!(Outer::size(GpuLayout::Std140), 32);
+
+        let std430 = GpuStructLayout::new(
+            GpuLayout::Std430,
+            &[
+                GpuStructMember::of::<Inner>(GpuLayout::Std430),
+                GpuStructMember::of::<f32>(GpuLayout::Std430),
+            ],
+        );
+        assert_eq!(offsets(&std430, 2), [0, 4]);
+        assert_eq!(Outer::size(GpuLayout::Std430), 8);
+    }
+
+    #[test]
+    fn nested_struct_members_are_written_at_their_offsets() {
+        let outer = Outer {
+            inner: Inner { value: 1.0 },
+            after: 2.0,
+        };
+
+        let bytes = outer.to_bytes(GpuLayout::Std140);
+        assert_eq!(bytes[0..4], 1.0f32.to_le_bytes());
+        assert_eq!(bytes[16..20], 2.0f32.to_le_bytes());
+    }
+
+    #[test]
+    fn point_light_stride_matches_naga() {
+        assert_eq!(UPointLight::size(GpuLayout::Std430), 48);
+        assert_eq!(UPointLight::stride(GpuLayout::Std430), 48);
+
+        let module = naga::front::wgsl::parse_str(&UPointLight::wgsl()).unwrap();
+        let (_, point_light) = module
+            .types
+            .iter()
+            .find(|(_, x)| x.name.as_deref() == Some("PointLight"))
+            .unwrap();
+        let naga::TypeInner::Struct { members, span } = &point_light.inner else {
+            panic!("PointLight isn't a structure");
+        };
+
+        assert_eq!(*span, 48);
+        assert_eq!(
+            members.iter().map(|x| x.offset).collect::<Vec<_>>(),
+            [0, 16, 28, 32, 36, 40]
+        );
+    }
+}
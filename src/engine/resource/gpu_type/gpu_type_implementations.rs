@@ -0,0 +1,244 @@
+use cgmath::{Matrix2, Matrix3, Matrix4, Point2, Point3, Vector2, Vector3, Vector4};
+
+use super::{GpuLayout, TGpuType};
+
+/// WGSL scalar types, the components of vectors & matrices
+pub trait TGpuScalar: Copy {
+    const WGSL_TYPE: &'static str;
+
+    fn to_le_bytes(self) -> [u8; 4];
+}
+
+impl TGpuScalar for f32 {
+    const WGSL_TYPE: &'static str = "f32";
+
+    fn to_le_bytes(self) -> [u8; 4] {
+        f32::to_le_bytes(self)
+    }
+}
+
+impl TGpuScalar for u32 {
+    const WGSL_TYPE: &'static str = "u32";
+
+    fn to_le_bytes(self) -> [u8; 4] {
+        u32::to_le_bytes(self)
+    }
+}
+
+impl TGpuScalar for i32 {
+    const WGSL_TYPE: &'static str = "i32";
+
+    fn to_le_bytes(self) -> [u8; 4] {
+        i32::to_le_bytes(self)
+    }
+}
+
+impl<S> TGpuType for S
+where
+    S: TGpuScalar,
+{
+    fn wgsl_type() -> String {
+        S::WGSL_TYPE.to_string()
+    }
+
+    fn alignment(_layout: GpuLayout) -> usize {
+        4
+    }
+
+    fn size(_layout: GpuLayout) -> usize {
+        4
+    }
+
+    fn write(&self, _layout: GpuLayout, bytes: &mut [u8]) {
+        bytes.copy_from_slice(&self.to_le_bytes());
+    }
+}
+
+/// `bool` isn't host-shareable in WGSL, thus it's a `u32` of 0 or 1
+impl TGpuType for bool {
+    fn wgsl_type() -> String {
+        u32::wgsl_type()
+    }
+
+    fn alignment(layout: GpuLayout) -> usize {
+        u32::alignment(layout)
+    }
+
+    fn size(layout: GpuLayout) -> usize {
+        u32::size(layout)
+    }
+
+    fn write(&self, layout: GpuLayout, bytes: &mut [u8]) {
+        (*self as u32).write(layout, bytes);
+    }
+}
+
+fn write_components<S>(components: &[S], bytes: &mut [u8])
+where
+    S: TGpuScalar,
+{
+    for (component, chunk) in components.iter().zip(bytes.chunks_exact_mut(4)) {
+        chunk.copy_from_slice(&component.to_le_bytes());
+    }
+}
+
+/// `vecN<S>`: vec2 is aligned to 8 bytes, vec3 & vec4 to 16
+fn vector_alignment(components: usize) -> usize {
+    if components == 2 {
+        8
+    } else {
+        16
+    }
+}
+
+macro_rules! impl_gpu_vector {
+    ($vector:ident, $components:literal, $($field:ident),+) => {
+        impl<S> TGpuType for $vector<S>
+        where
+            S: TGpuScalar,
+        {
+            fn wgsl_type() -> String {
+                format!("vec{}<{}>", $components, S::WGSL_TYPE)
+            }
+
+            fn alignment(_layout: GpuLayout) -> usize {
+                vector_alignment($components)
+            }
+
+            fn size(_layout: GpuLayout) -> usize {
+                $components * 4
+            }
+
+            fn write(&self, _layout: GpuLayout, bytes: &mut [u8]) {
+                write_components(&[$(self.$field),+], bytes);
+            }
+        }
+    };
+}
+
+impl_gpu_vector!(Vector2, 2, x, y);
+impl_gpu_vector!(Vector3, 3, x, y, z);
+impl_gpu_vector!(Vector4, 4, x, y, z, w);
+impl_gpu_vector!(Point2, 2, x, y);
+impl_gpu_vector!(Point3, 3, x, y, z);
+
+/// `matCxR<f32>` is stored as an array of C column vectors with R components
+macro_rules! impl_gpu_matrix {
+    ($matrix:ident, $size:literal, $($column:ident),+) => {
+        impl TGpuType for $matrix<f32> {
+            fn wgsl_type() -> String {
+                format!("mat{0}x{0}<f32>", $size)
+            }
+
+            fn alignment(_layout: GpuLayout) -> usize {
+                vector_alignment($size)
+            }
+
+            fn size(layout: GpuLayout) -> usize {
+                $size * Self::column_stride(layout)
+            }
+
+            fn write(&self, layout: GpuLayout, bytes: &mut [u8]) {
+                let stride = Self::column_stride(layout);
+                for (column, chunk) in [$(self.$column),+].iter().zip(bytes.chunks_mut(stride)) {
+                    column.write(layout, &mut chunk[..$size * 4]);
+                }
+            }
+        }
+
+        impl GpuMatrix for $matrix<f32> {
+            fn column_stride(_layout: GpuLayout) -> usize {
+                GpuLayout::round_up(vector_alignment($size), $size * 4)
+            }
+        }
+    };
+}
+
+trait GpuMatrix {
+    fn column_stride(layout: GpuLayout) -> usize;
+}
+
+impl_gpu_matrix!(Matrix2, 2, x, y);
+impl_gpu_matrix!(Matrix3, 3, x, y, z);
+impl_gpu_matrix!(Matrix4, 4, x, y, z, w);
+
+/// `array<T, N>`
+impl<T, const N: usize> TGpuType for [T; N]
+where
+    T: TGpuType,
+{
+    fn wgsl_type() -> String {
+        format!("array<{}, {}>", T::wgsl_type(), N)
+    }
+
+    fn alignment(layout: GpuLayout) -> usize {
+        layout.composite_alignment(T::alignment(layout))
+    }
+
+    fn size(layout: GpuLayout) -> usize {
+        debug_assert!(
+            layout != GpuLayout::Std140 || T::stride(layout) % 16 == 0,
+            "The stride of {} must be a multiple of 16 bytes in uniform buffers",
+            Self::wgsl_type()
+        );
+
+        N * T::stride(layout)
+    }
+
+    fn is_composite() -> bool {
+        true
+    }
+
+    fn write(&self, layout: GpuLayout, bytes: &mut [u8]) {
+        let stride = T::stride(layout);
+        let size = T::size(layout);
+        for (value, chunk) in self.iter().zip(bytes.chunks_mut(stride)) {
+            value.write(layout, &mut chunk[..size]);
+        }
+    }
+
+    fn wgsl_definitions(definitions: &mut Vec<String>) {
+        T::wgsl_definitions(definitions);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cgmath::{Matrix3, Vector3};
+
+    use super::*;
+
+    #[test]
+    fn mat3x3_columns_have_a_stride_of_16() {
+        for layout in [GpuLayout::Std140, GpuLayout::Std430] {
+            assert_eq!(Matrix3::<f32>::size(layout), 48);
+            assert_eq!(Matrix3::<f32>::alignment(layout), 16);
+        }
+
+        let matrix = Matrix3::from_cols(
+            Vector3::new(1.0, 2.0, 3.0),
+            Vector3::new(4.0, 5.0, 6.0),
+            Vector3::new(7.0, 8.0, 9.0),
+        );
+        let bytes = matrix.to_bytes(GpuLayout::Std430);
+        assert_eq!(bytes[0..4], 1.0f32.to_le_bytes());
+        assert_eq!(bytes[16..20], 4.0f32.to_le_bytes());
+        assert_eq!(bytes[32..36], 7.0f32.to_le_bytes());
+        assert_eq!(bytes[44..48], [0; 4]);
+    }
+
+    #[test]
+    fn array_strides() {
+        assert_eq!(<[f32; 4]>::size(GpuLayout::Std430), 16);
+        assert_eq!(<[Vector3<f32>; 2]>::size(GpuLayout::Std430), 32);
+        assert_eq!(<[Vector4<f32>; 2]>::size(GpuLayout::Std140), 32);
+        assert_eq!(<[Vector4<f32>; 2]>::alignment(GpuLayout::Std140), 16);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "must be a multiple of 16 bytes")]
+    fn std140_array_stride_must_be_a_multiple_of_16() {
+        <[f32; 4]>::size(GpuLayout::Std140);
+    }
+}
@@ -0,0 +1,11 @@
+mod gpu_layout;
+pub use gpu_layout::*;
+
+mod t_gpu_type;
+pub use t_gpu_type::*;
+
+mod gpu_struct_layout;
+pub use gpu_struct_layout::*;
+
+mod gpu_type_implementations;
+pub use gpu_type_implementations::*;
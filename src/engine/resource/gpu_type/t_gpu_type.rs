@@ -0,0 +1,78 @@
+pub use wgpu_engine_derive::GpuType;
+
+use super::GpuLayout;
+
+/// A type with a WGSL counterpart, which can be written into GPU buffers
+/// with the padding WGSL expects.
+///
+/// Implemented for `f32`, `u32`, `i32`, `bool` (`u32` in WGSL),
+/// the `cgmath` vectors, points & matrices and arrays.
+/// Structures use `#[derive(GpuType)]`:
+/// ```ignore
+/// #[derive(GpuType)]
+/// #[gpu_type(name = "PointLight")]
+/// pub struct UPointLight {
+///     color: Vector3<f32>,
+///     strength: f32,
+/// }
+///
+/// let bytes = light.to_bytes(GpuLayout::Std430);
+/// let wgsl = UPointLight::wgsl();
+/// ```
+pub trait TGpuType {
+    /// Name of the type in WGSL, e.g. `vec3<f32>`
+    fn wgsl_type() -> String;
+
+    fn alignment(layout: GpuLayout) -> usize;
+
+    /// Size without trailing padding of arrays
+    fn size(layout: GpuLayout) -> usize;
+
+    /// Structures & arrays, see [`GpuLayout::Std140`]
+    fn is_composite() -> bool {
+        false
+    }
+
+    /// Writes the value into `bytes`, which has exactly [`Self::size`] bytes.
+    /// Padding is left untouched.
+    fn write(&self, layout: GpuLayout, bytes: &mut [u8]);
+
+    /// Definitions of the WGSL structures the type consists of,
+    /// dependencies first
+    fn wgsl_definitions(_definitions: &mut Vec<String>) {}
+
+    /// Distance between the elements of an array of this type
+    fn stride(layout: GpuLayout) -> usize {
+        GpuLayout::round_up(Self::alignment(layout), Self::size(layout))
+    }
+
+    /// The value with zeroed padding
+    fn to_bytes(&self, layout: GpuLayout) -> Vec<u8> {
+        let mut bytes = vec![0; Self::size(layout)];
+        self.write(layout, &mut bytes);
+        bytes
+    }
+
+    /// Values one after another with the array stride,
+    /// i.e. the content of an `array<T>`
+    fn slice_to_bytes(values: &[Self], layout: GpuLayout) -> Vec<u8>
+    where
+        Self: Sized,
+    {
+        let stride = Self::stride(layout);
+        let size = Self::size(layout);
+
+        let mut bytes = vec![0; values.len() * stride];
+        for (value, chunk) in values.iter().zip(bytes.chunks_exact_mut(stride)) {
+            value.write(layout, &mut chunk[..size]);
+        }
+        bytes
+    }
+
+    /// WGSL source of all structure definitions the type needs
+    fn wgsl() -> String {
+        let mut definitions = Vec::new();
+        Self::wgsl_definitions(&mut definitions);
+        definitions.join("\n")
+    }
+}
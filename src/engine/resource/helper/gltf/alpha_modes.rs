@@ -0,0 +1,33 @@
+use gltf::{material, scene::Node, Scene};
+
+use crate::engine::AlphaMode;
+
+/// Alpha modes of all models of the scene, in the order `easy_gltf` loads them:
+/// depth first, the children before the primitives of the node itself.
+pub fn scene_alpha_modes(scene: &Scene) -> Vec<AlphaMode> {
+    let mut alpha_modes = Vec::new();
+    for node in scene.nodes() {
+        read_node(&node, &mut alpha_modes);
+    }
+    alpha_modes
+}
+
+fn read_node(node: &Node, alpha_modes: &mut Vec<AlphaMode>) {
+    for child in node.children() {
+        read_node(&child, alpha_modes);
+    }
+
+    if let Some(mesh) = node.mesh() {
+        alpha_modes.extend(mesh.primitives().map(|x| to_alpha_mode(&x.material())));
+    }
+}
+
+fn to_alpha_mode(material: &material::Material) -> AlphaMode {
+    match material.alpha_mode() {
+        material::AlphaMode::Opaque => AlphaMode::Opaque,
+        material::AlphaMode::Mask => {
+            AlphaMode::Mask(material.alpha_cutoff().unwrap_or(AlphaMode::DEFAULT_CUTOFF))
+        }
+        material::AlphaMode::Blend => AlphaMode::Blend,
+    }
+}
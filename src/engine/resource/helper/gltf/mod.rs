@@ -3,3 +3,6 @@ pub use vertex::*;
 
 mod model;
 pub use model::*;
+
+mod alpha_modes;
+pub use alpha_modes::*;
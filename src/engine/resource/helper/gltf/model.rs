@@ -1,22 +1,27 @@
 use cgmath::{Quaternion, Vector2, Vector3, Zero};
-use easy_gltf::Model;
+use easy_gltf::{Material, Model};
+use image::{imageops, imageops::FilterType, DynamicImage, GrayImage, Rgba, RgbaImage};
 use logical_device::LogicalDevice;
 
 use crate::engine::{
-    logical_device, DiffuseTexture, EngineError, EngineResult, MaterialLoading, NormalTexture,
-    StandardInstance, StandardMaterial, StandardMesh, TInstance, TMaterial, VertexPoint,
-    NORMAL_TEXTURE_FORMAT,
+    logical_device, AlphaMode, EngineError, EngineResult, MaterialLoading, PbrMaterial,
+    PbrMaterialDescriptor, StandardInstance, StandardMesh, TInstance, TMaterial, VertexPoint,
 };
 
+/// `alpha_mode` is the one of the GLTF material, which `easy_gltf` doesn't expose,
+/// see [`crate::engine::ResourceManager::read_resource_gltf_alpha_modes`].
+/// Only used if the material gets loaded.
 pub trait ToStandardMesh {
     fn to_mesh(
         &self,
         logical_device: &LogicalDevice,
         material_loading: MaterialLoading,
+        alpha_mode: AlphaMode,
     ) -> EngineResult<StandardMesh> {
         self.to_instanced_mesh(
             logical_device,
             material_loading,
+            alpha_mode,
             vec![StandardInstance::new(
                 Vector3::zero(),
                 Quaternion {
@@ -31,6 +36,7 @@ pub trait ToStandardMesh {
         &self,
         logical_device: &LogicalDevice,
         material_loading: MaterialLoading,
+        alpha_mode: AlphaMode,
         instances: Vec<StandardInstance>,
     ) -> EngineResult<StandardMesh>;
 }
@@ -40,6 +46,7 @@ impl ToStandardMesh for Model {
         &self,
         logical_device: &LogicalDevice,
         material_loading: MaterialLoading,
+        alpha_mode: AlphaMode,
         instances: Vec<StandardInstance>,
     ) -> EngineResult<StandardMesh> {
         let mut vertices: Vec<VertexPoint> = self.vertices().iter().map(|x| x.into()).collect();
@@ -108,34 +115,99 @@ impl ToStandardMesh for Model {
 
         let material: Option<Box<dyn TMaterial>> = match material_loading {
             MaterialLoading::Ignore => None,
-            MaterialLoading::Try => match &self.material().pbr.base_color_texture {
-                Some(base_color_texture) => {
-                    match DiffuseTexture::from_bytes(logical_device, base_color_texture, None, None)
-                    {
-                        Ok(diffuse_texture) => {
-                            match NormalTexture::empty(logical_device, Some(NORMAL_TEXTURE_FORMAT))
-                            {
-                                Ok(normal_texture) => {
-                                    match StandardMaterial::from_texture(
-                                        logical_device,
-                                        diffuse_texture,
-                                        normal_texture,
-                                    ) {
-                                        Ok(material) => Some(Box::new(material)),
-                                        Err(_) => None,
-                                    }
-                                }
-                                Err(_) => None,
-                            }
-                        }
-                        Err(_) => None,
+            MaterialLoading::Try => {
+                match PbrMaterial::new(
+                    logical_device,
+                    &pbr_material_descriptor(&self.material(), alpha_mode),
+                ) {
+                    Ok(material) => Some(Box::new(material)),
+                    Err(e) => {
+                        log::warn!("Failed to load GLTF material, using default: {:?}", e);
+                        None
                     }
                 }
-                None => None,
-            },
-            MaterialLoading::Replace(material) => Some(Box::new(material)),
+            }
+            MaterialLoading::Replace(material) => Some(material),
         };
 
         StandardMesh::from_raw(None, logical_device, vertices, indices, instances, material)
     }
 }
+
+/// Maps every input of a GLTF metallic-roughness material
+fn pbr_material_descriptor(material: &Material, alpha_mode: AlphaMode) -> PbrMaterialDescriptor {
+    let pbr = &material.pbr;
+
+    PbrMaterialDescriptor {
+        name: None,
+        base_color_texture: pbr
+            .base_color_texture
+            .as_ref()
+            .map(|x| DynamicImage::ImageRgba8(x.as_ref().clone())),
+        base_color_factor: pbr.base_color_factor.into(),
+        metallic_roughness_texture: metallic_roughness_image(
+            pbr.metallic_texture.as_deref(),
+            pbr.roughness_texture.as_deref(),
+        ),
+        metallic_factor: pbr.metallic_factor,
+        roughness_factor: pbr.roughness_factor,
+        normal_texture: material
+            .normal
+            .as_ref()
+            .map(|x| DynamicImage::ImageRgb8(x.texture.as_ref().clone())),
+        normal_scale: material.normal.as_ref().map_or(1.0, |x| x.factor),
+        occlusion_texture: material
+            .occlusion
+            .as_ref()
+            .map(|x| DynamicImage::ImageLuma8(x.texture.as_ref().clone())),
+        occlusion_strength: material.occlusion.as_ref().map_or(1.0, |x| x.factor),
+        emissive_texture: material
+            .emissive
+            .texture
+            .as_ref()
+            .map(|x| DynamicImage::ImageRgb8(x.as_ref().clone())),
+        emissive_factor: material.emissive.factor.into(),
+        alpha_mode,
+    }
+}
+
+/// GLTF stores metallic (B) & roughness (G) in one texture,
+/// which gets split up into two images while loading.
+/// Recombines them, a missing one stays at full strength.
+fn metallic_roughness_image(
+    metallic: Option<&GrayImage>,
+    roughness: Option<&GrayImage>,
+) -> Option<DynamicImage> {
+    let (width, height) = match (metallic, roughness) {
+        (None, None) => return None,
+        (Some(x), None) | (None, Some(x)) => x.dimensions(),
+        (Some(metallic), Some(roughness)) => (
+            metallic.width().max(roughness.width()),
+            metallic.height().max(roughness.height()),
+        ),
+    };
+
+    // Both usually come from the same texture, but not necessarily in the same size
+    let resized = |x: &GrayImage| {
+        if x.dimensions() == (width, height) {
+            x.clone()
+        } else {
+            imageops::resize(x, width, height, FilterType::Triangle)
+        }
+    };
+    let metallic = metallic.map(resized);
+    let roughness = roughness.map(resized);
+
+    Some(DynamicImage::ImageRgba8(RgbaImage::from_fn(
+        width,
+        height,
+        |x, y| {
+            Rgba([
+                0,
+                roughness.as_ref().map_or(255, |r| r.get_pixel(x, y)[0]),
+                metallic.as_ref().map_or(255, |m| m.get_pixel(x, y)[0]),
+                255,
+            ])
+        },
+    )))
+}
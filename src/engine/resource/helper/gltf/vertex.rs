@@ -11,6 +11,7 @@ impl From<&Vertex> for VertexPoint {
             normal_coordinates: value.normal.into(),
             tangent: [0.0; 3],
             bitangent: [0.0; 3],
+            color: [1.0; 4],
         }
     }
 }
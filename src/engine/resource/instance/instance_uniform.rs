@@ -1,8 +1,12 @@
 use bytemuck::{Pod, Zeroable};
 
 #[repr(C)]
-#[derive(Copy, Clone, Pod, Zeroable)]
+#[derive(Debug, Copy, Clone, Pod, Zeroable)]
 pub struct InstanceUniform {
     pub model_space_matrix: [[f32; 4]; 4],
+    /// Inverse-transpose of the model space matrix (rotation & scale only).
+    /// Keeps normals perpendicular to surfaces under non-uniform scaling.
     pub normal_space_matrix: [[f32; 3]; 3],
+    pub tint: [f32; 4],
+    pub user_data: [f32; 4],
 }
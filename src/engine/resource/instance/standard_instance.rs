@@ -1,16 +1,45 @@
-use cgmath::{Quaternion, Vector3};
+use cgmath::{Quaternion, Vector3, Vector4};
 
 use super::TInstance;
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
 pub struct StandardInstance {
     position: Vector3<f32>,
     rotation: Quaternion<f32>,
+    scale: Vector3<f32>,
+    tint: Vector4<f32>,
+    user_data: [f32; 4],
+}
+
+impl StandardInstance {
+    pub const DEFAULT_SCALE: Vector3<f32> = Vector3::new(1.0, 1.0, 1.0);
+    pub const DEFAULT_TINT: Vector4<f32> = Vector4::new(1.0, 1.0, 1.0, 1.0);
+
+    pub fn with_scale(mut self, scale: Vector3<f32>) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    pub fn with_tint(mut self, tint: Vector4<f32>) -> Self {
+        self.tint = tint;
+        self
+    }
+
+    pub fn with_user_data(mut self, user_data: [f32; 4]) -> Self {
+        self.user_data = user_data;
+        self
+    }
 }
 
 impl TInstance for StandardInstance {
     fn new(position: Vector3<f32>, rotation: Quaternion<f32>) -> Self {
-        Self { position, rotation }
+        Self {
+            position,
+            rotation,
+            scale: Self::DEFAULT_SCALE,
+            tint: Self::DEFAULT_TINT,
+            user_data: [0.0; 4],
+        }
     }
 
     fn position(&self) -> Vector3<f32> {
@@ -28,4 +57,28 @@ impl TInstance for StandardInstance {
     fn set_rotation(&mut self, rotation: Quaternion<f32>) {
         self.rotation = rotation;
     }
+
+    fn scale(&self) -> Vector3<f32> {
+        self.scale
+    }
+
+    fn set_scale(&mut self, scale: Vector3<f32>) {
+        self.scale = scale;
+    }
+
+    fn tint(&self) -> Vector4<f32> {
+        self.tint
+    }
+
+    fn set_tint(&mut self, tint: Vector4<f32>) {
+        self.tint = tint;
+    }
+
+    fn user_data(&self) -> [f32; 4] {
+        self.user_data
+    }
+
+    fn set_user_data(&mut self, user_data: [f32; 4]) {
+        self.user_data = user_data;
+    }
 }
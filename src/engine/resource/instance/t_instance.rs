@@ -1,4 +1,4 @@
-use cgmath::{Matrix3, Matrix4, Quaternion, Vector3};
+use cgmath::{Matrix, Matrix3, Matrix4, Quaternion, SquareMatrix, Vector3, Vector4};
 use wgpu::{BufferAddress, VertexAttribute, VertexBufferLayout, VertexFormat, VertexStepMode};
 
 use super::InstanceUniform;
@@ -14,12 +14,41 @@ pub trait TInstance {
 
     fn set_rotation(&mut self, rotation: Quaternion<f32>);
 
+    fn scale(&self) -> Vector3<f32>;
+
+    fn set_scale(&mut self, scale: Vector3<f32>);
+
+    /// Color the instance gets multiplied with
+    fn tint(&self) -> Vector4<f32>;
+
+    fn set_tint(&mut self, tint: Vector4<f32>);
+
+    /// Free to use data, passed along to the shader
+    fn user_data(&self) -> [f32; 4];
+
+    fn set_user_data(&mut self, user_data: [f32; 4]);
+
     fn to_instance_uniform(&self) -> InstanceUniform {
+        let rotation_scale_matrix =
+            Matrix3::from(self.rotation()) * Matrix3::from_diagonal(self.scale());
+
+        let model_space_matrix = Matrix4::from_translation(self.position())
+            * Matrix4::from(self.rotation())
+            * Matrix4::from_nonuniform_scale(self.scale().x, self.scale().y, self.scale().z);
+
+        // Normals need the inverse-transpose, otherwise non-uniform scaling
+        // would skew them.
+        // A scale of zero can't be inverted, fall back to rotation only.
+        let normal_space_matrix = rotation_scale_matrix
+            .invert()
+            .map(|x| x.transpose())
+            .unwrap_or(Matrix3::from(self.rotation()));
+
         InstanceUniform {
-            model_space_matrix: (Matrix4::from_translation(self.position())
-                * Matrix4::from(self.rotation()))
-            .into(),
-            normal_space_matrix: Matrix3::from(self.rotation()).into(),
+            model_space_matrix: model_space_matrix.into(),
+            normal_space_matrix: normal_space_matrix.into(),
+            tint: self.tint().into(),
+            user_data: self.user_data(),
         }
     }
 
@@ -64,6 +93,18 @@ pub trait TInstance {
                     shader_location: 11,
                     format: VertexFormat::Float32x3,
                 },
+                // Tint
+                VertexAttribute {
+                    offset: std::mem::size_of::<[f32; 25]>() as BufferAddress,
+                    shader_location: 12,
+                    format: VertexFormat::Float32x4,
+                },
+                // User data
+                VertexAttribute {
+                    offset: std::mem::size_of::<[f32; 29]>() as BufferAddress,
+                    shader_location: 13,
+                    format: VertexFormat::Float32x4,
+                },
             ],
         }
     }
@@ -0,0 +1,8 @@
+/// Intensity below which a light is considered to have no effect anymore
+pub const LIGHT_CUTOFF_INTENSITY: f32 = 0.005;
+
+/// Distance at which a light with the given strength falls below
+/// [`LIGHT_CUTOFF_INTENSITY`] under inverse-square falloff.
+pub fn light_range_from_strength(strength: f32) -> f32 {
+    (strength.max(0.0) / LIGHT_CUTOFF_INTENSITY).sqrt()
+}
@@ -0,0 +1,97 @@
+use std::{marker::PhantomData, mem::size_of};
+
+use wgpu::{BindingResource, Buffer, BufferAddress, BufferDescriptor, BufferUsages};
+
+use crate::engine::{GpuLayout, LogicalDevice, TGpuType};
+
+use super::ULightArrayHeader;
+
+/// Storage buffer holding a [`ULightArrayHeader`] followed by an array of
+/// light uniforms, laid out by [`TGpuType`].
+///
+/// Matches the following WGSL structure:
+/// ```wgsl
+/// struct Lights {
+///     count: u32,
+///     lights: array<Light>,
+/// }
+/// ```
+#[derive(Debug)]
+pub struct LightArrayBuffer<U>
+where
+    U: TGpuType,
+{
+    label: String,
+    capacity: usize,
+    buffer: Buffer,
+    _uniform: PhantomData<U>,
+}
+
+impl<U> LightArrayBuffer<U>
+where
+    U: TGpuType,
+{
+    pub fn new<S>(logical_device: &LogicalDevice, label: S, uniforms: &[U]) -> Self
+    where
+        S: Into<String>,
+    {
+        let label = label.into();
+        // At least one element, empty storage buffers can't be bound
+        let capacity = uniforms.len().max(1);
+        let buffer = Self::make_buffer(logical_device, &label, capacity);
+
+        let light_array_buffer = Self {
+            label,
+            capacity,
+            buffer,
+            _uniform: PhantomData,
+        };
+        light_array_buffer.write(logical_device, uniforms);
+
+        light_array_buffer
+    }
+
+    fn make_buffer(logical_device: &LogicalDevice, label: &str, capacity: usize) -> Buffer {
+        logical_device.device().create_buffer(&BufferDescriptor {
+            label: Some(label),
+            size: (size_of::<ULightArrayHeader>() + capacity * U::stride(GpuLayout::Std430))
+                as BufferAddress,
+            usage: BufferUsages::STORAGE | BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        })
+    }
+
+    fn write(&self, logical_device: &LogicalDevice, uniforms: &[U]) {
+        let mut bytes = bytemuck::bytes_of(&ULightArrayHeader::new(uniforms.len() as u32)).to_vec();
+        bytes.extend_from_slice(&U::slice_to_bytes(uniforms, GpuLayout::Std430));
+
+        logical_device.queue().write_buffer(&self.buffer, 0, &bytes);
+    }
+
+    /// Uploads the given lights.
+    /// Returns `true` if the buffer had to grow, in which case bind groups
+    /// using it must be recreated.
+    pub fn update(&mut self, logical_device: &LogicalDevice, uniforms: &[U]) -> bool {
+        let grown = uniforms.len() > self.capacity;
+        if grown {
+            self.capacity = uniforms.len().next_power_of_two();
+            self.buffer = Self::make_buffer(logical_device, &self.label, self.capacity);
+        }
+
+        self.write(logical_device, uniforms);
+
+        grown
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn buffer(&self) -> &Buffer {
+        &self.buffer
+    }
+
+    pub fn binding_resource(&self) -> BindingResource<'_> {
+        self.buffer.as_entire_binding()
+    }
+}
@@ -0,0 +1,24 @@
+use super::{Light, LightKind};
+
+/// Changes to the lights of a world.
+///
+/// Indices address the world lights of the given kind in the order they
+/// were added, see [`super::LightStorage::point_lights`] and co.
+/// Lights owned by entities aren't affected.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LightChange {
+    /// Adds a light to the world
+    Add(Light),
+
+    /// Replaces the light at the index with the given light of the same kind
+    Set(usize, Light),
+
+    /// Removes the light at the index
+    Remove(LightKind, usize),
+
+    /// Enables or disables the light at the index
+    SetEnabled(LightKind, usize, bool),
+
+    /// Removes all lights
+    Clear,
+}
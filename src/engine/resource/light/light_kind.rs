@@ -0,0 +1,45 @@
+use super::{StandardDirectionalLight, StandardPointLight, StandardSpotLight};
+
+/// Kind of a [`Light`], used to address lights of the same kind
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LightKind {
+    Point,
+    Directional,
+    Spot,
+}
+
+/// Any light, except for the ambient light
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Light {
+    Point(StandardPointLight),
+    Directional(StandardDirectionalLight),
+    Spot(StandardSpotLight),
+}
+
+impl Light {
+    pub fn kind(&self) -> LightKind {
+        match self {
+            Light::Point(_) => LightKind::Point,
+            Light::Directional(_) => LightKind::Directional,
+            Light::Spot(_) => LightKind::Spot,
+        }
+    }
+}
+
+impl From<StandardPointLight> for Light {
+    fn from(value: StandardPointLight) -> Self {
+        Light::Point(value)
+    }
+}
+
+impl From<StandardDirectionalLight> for Light {
+    fn from(value: StandardDirectionalLight) -> Self {
+        Light::Directional(value)
+    }
+}
+
+impl From<StandardSpotLight> for Light {
+    fn from(value: StandardSpotLight) -> Self {
+        Light::Spot(value)
+    }
+}
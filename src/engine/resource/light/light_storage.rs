@@ -0,0 +1,463 @@
+use wgpu::{
+    BindGroup, BindGroupDescriptor, BindGroupEntry, BindGroupLayout, BindGroupLayoutDescriptor,
+    BindGroupLayoutEntry, BindingType, BufferBindingType, ShaderStages,
+};
+
+use cgmath::Matrix4;
+
+use crate::engine::{EngineResult, LogicalDevice};
+
+use super::{
+    Light, LightArrayBuffer, LightChange, LightKind, ShadowMaps, ShadowSettings,
+    StandardDirectionalLight, StandardPointLight, StandardSpotLight, TDirectionalLight,
+    TPointLight, TSpotLight, UDirectionalLight, UPointLight, USpotLight,
+};
+
+/// Light uniforms with their shadow map layers assigned, and the
+/// shadow casters of those layers
+struct LightUniforms {
+    point_lights: Vec<UPointLight>,
+    directional_lights: Vec<UDirectionalLight>,
+    spot_lights: Vec<USpotLight>,
+    shadow_casters: Vec<Matrix4<f32>>,
+}
+
+/// Holds all lights of a world (except for the ambient light) and uploads
+/// them into storage buffers, so any amount of lights can be looped over
+/// in the shader.
+///
+/// There are two sources of lights:
+/// - World lights, which are kept until changed or removed
+/// - Entity lights, which are collected from the entities every frame
+pub struct LightStorage {
+    point_lights: Vec<StandardPointLight>,
+    directional_lights: Vec<StandardDirectionalLight>,
+    spot_lights: Vec<StandardSpotLight>,
+    entity_lights: Vec<Light>,
+    point_light_buffer: LightArrayBuffer<UPointLight>,
+    directional_light_buffer: LightArrayBuffer<UDirectionalLight>,
+    spot_light_buffer: LightArrayBuffer<USpotLight>,
+    shadow_maps: ShadowMaps,
+    bind_group: BindGroup,
+    changed: bool,
+}
+
+impl LightStorage {
+    pub const BIND_GROUP_LAYOUT_DESCRIPTOR: BindGroupLayoutDescriptor<'static> =
+        BindGroupLayoutDescriptor {
+            label: Some("Light Storage Bind Group Layout"),
+            entries: &[
+                // Point Lights
+                BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: ShaderStages::VERTEX_FRAGMENT.union(ShaderStages::COMPUTE),
+                    ty: BindingType::Buffer {
+                        ty: BufferBindingType::Storage { read_only: true },
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                // Directional Lights
+                BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: ShaderStages::VERTEX_FRAGMENT.union(ShaderStages::COMPUTE),
+                    ty: BindingType::Buffer {
+                        ty: BufferBindingType::Storage { read_only: true },
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                // Spot Lights
+                BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: ShaderStages::VERTEX_FRAGMENT.union(ShaderStages::COMPUTE),
+                    ty: BindingType::Buffer {
+                        ty: BufferBindingType::Storage { read_only: true },
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+            ],
+        };
+
+    pub fn new(
+        logical_device: &LogicalDevice,
+        point_lights: Vec<StandardPointLight>,
+        directional_lights: Vec<StandardDirectionalLight>,
+        spot_lights: Vec<StandardSpotLight>,
+        shadow_settings: ShadowSettings,
+    ) -> EngineResult<Self> {
+        let uniforms = Self::make_uniforms(
+            &shadow_settings,
+            &point_lights,
+            &directional_lights,
+            &spot_lights,
+        );
+
+        let point_light_buffer =
+            LightArrayBuffer::new(logical_device, "Point Light Buffer", &uniforms.point_lights);
+        let directional_light_buffer = LightArrayBuffer::new(
+            logical_device,
+            "Directional Light Buffer",
+            &uniforms.directional_lights,
+        );
+        let spot_light_buffer =
+            LightArrayBuffer::new(logical_device, "Spot Light Buffer", &uniforms.spot_lights);
+
+        let mut shadow_maps = ShadowMaps::new(logical_device, shadow_settings)?;
+        shadow_maps.update(logical_device, &uniforms.shadow_casters);
+
+        let bind_group = Self::make_bind_group(
+            logical_device,
+            &point_light_buffer,
+            &directional_light_buffer,
+            &spot_light_buffer,
+        );
+
+        Ok(Self {
+            point_lights,
+            directional_lights,
+            spot_lights,
+            entity_lights: vec![],
+            point_light_buffer,
+            directional_light_buffer,
+            spot_light_buffer,
+            shadow_maps,
+            bind_group,
+            changed: false,
+        })
+    }
+
+    /// Converts the lights into uniforms and assigns shadow map layers.
+    ///
+    /// Enabled lights get layers in the order: directional, spot, point.
+    /// Once all layers are taken, the remaining lights don't cast shadows.
+    fn make_uniforms(
+        shadow_settings: &ShadowSettings,
+        point_lights: &[StandardPointLight],
+        directional_lights: &[StandardDirectionalLight],
+        spot_lights: &[StandardSpotLight],
+    ) -> LightUniforms {
+        let mut shadow_casters = vec![];
+        let mut allocate_layers = |enabled: bool, casters: &[Matrix4<f32>]| -> Option<u32> {
+            if !enabled
+                || shadow_casters.len() + casters.len() > shadow_settings.max_layers as usize
+            {
+                return None;
+            }
+
+            let first_layer = shadow_casters.len() as u32;
+            shadow_casters.extend_from_slice(casters);
+            Some(first_layer)
+        };
+
+        let directional_lights = directional_lights
+            .iter()
+            .map(|x| {
+                x.to_uniform().with_shadow_layer(allocate_layers(
+                    x.enabled(),
+                    &[ShadowMaps::directional_shadow_caster(
+                        shadow_settings,
+                        x.direction(),
+                    )],
+                ))
+            })
+            .collect();
+        let spot_lights = spot_lights
+            .iter()
+            .map(|x| {
+                x.to_uniform().with_shadow_layer(allocate_layers(
+                    x.enabled(),
+                    &[ShadowMaps::spot_shadow_caster(
+                        shadow_settings,
+                        x.position(),
+                        x.direction(),
+                        x.outer_cone_angle(),
+                    )],
+                ))
+            })
+            .collect();
+        let point_lights = point_lights
+            .iter()
+            .map(|x| {
+                x.to_uniform().with_shadow_layer(allocate_layers(
+                    x.enabled(),
+                    &ShadowMaps::point_shadow_casters(shadow_settings, x.position()),
+                ))
+            })
+            .collect();
+
+        LightUniforms {
+            point_lights,
+            directional_lights,
+            spot_lights,
+            shadow_casters,
+        }
+    }
+
+    fn make_bind_group(
+        logical_device: &LogicalDevice,
+        point_light_buffer: &LightArrayBuffer<UPointLight>,
+        directional_light_buffer: &LightArrayBuffer<UDirectionalLight>,
+        spot_light_buffer: &LightArrayBuffer<USpotLight>,
+    ) -> BindGroup {
+        let bind_group_layout = Self::bind_group_layout(logical_device);
+
+        logical_device
+            .device()
+            .create_bind_group(&BindGroupDescriptor {
+                label: Some("Light Storage Bind Group"),
+                layout: &bind_group_layout,
+                entries: &[
+                    BindGroupEntry {
+                        binding: 0,
+                        resource: point_light_buffer.binding_resource(),
+                    },
+                    BindGroupEntry {
+                        binding: 1,
+                        resource: directional_light_buffer.binding_resource(),
+                    },
+                    BindGroupEntry {
+                        binding: 2,
+                        resource: spot_light_buffer.binding_resource(),
+                    },
+                ],
+            })
+    }
+
+    /// Uploads the lights if any changed since the last call
+    pub fn update_buffer(&mut self, logical_device: &LogicalDevice) {
+        if !self.changed {
+            return;
+        }
+        self.changed = false;
+
+        // World lights first, then entity lights
+        let mut point_lights = self.point_lights.clone();
+        let mut directional_lights = self.directional_lights.clone();
+        let mut spot_lights = self.spot_lights.clone();
+        for light in &self.entity_lights {
+            match light {
+                Light::Point(x) => point_lights.push(*x),
+                Light::Directional(x) => directional_lights.push(*x),
+                Light::Spot(x) => spot_lights.push(*x),
+            }
+        }
+
+        let uniforms = Self::make_uniforms(
+            self.shadow_maps.settings(),
+            &point_lights,
+            &directional_lights,
+            &spot_lights,
+        );
+
+        let point_lights_grown = self
+            .point_light_buffer
+            .update(logical_device, &uniforms.point_lights);
+        let directional_lights_grown = self
+            .directional_light_buffer
+            .update(logical_device, &uniforms.directional_lights);
+        let spot_lights_grown = self
+            .spot_light_buffer
+            .update(logical_device, &uniforms.spot_lights);
+
+        self.shadow_maps
+            .update(logical_device, &uniforms.shadow_casters);
+
+        if point_lights_grown || directional_lights_grown || spot_lights_grown {
+            self.bind_group = Self::make_bind_group(
+                logical_device,
+                &self.point_light_buffer,
+                &self.directional_light_buffer,
+                &self.spot_light_buffer,
+            );
+        }
+    }
+
+    /// Replaces the lights owned by entities.
+    /// Only marks the lights as changed if they differ from the last call.
+    pub fn set_entity_lights(&mut self, entity_lights: Vec<Light>) {
+        if self.entity_lights != entity_lights {
+            self.entity_lights = entity_lights;
+            self.changed = true;
+        }
+    }
+
+    pub fn entity_lights(&self) -> &[Light] {
+        &self.entity_lights
+    }
+
+    /// Applies a change to the world lights.
+    /// Changes addressing a non-existing light are ignored with a warning.
+    pub fn apply_light_change(&mut self, light_change: LightChange) {
+        let applied = match light_change {
+            LightChange::Add(light) => {
+                match light {
+                    Light::Point(x) => self.add_point_light(x),
+                    Light::Directional(x) => self.add_directional_light(x),
+                    Light::Spot(x) => self.add_spot_light(x),
+                }
+                true
+            }
+            LightChange::Set(index, light) => match light {
+                Light::Point(x) => self
+                    .point_lights_mut()
+                    .get_mut(index)
+                    .map(|y| *y = x)
+                    .is_some(),
+                Light::Directional(x) => self
+                    .directional_lights_mut()
+                    .get_mut(index)
+                    .map(|y| *y = x)
+                    .is_some(),
+                Light::Spot(x) => self
+                    .spot_lights_mut()
+                    .get_mut(index)
+                    .map(|y| *y = x)
+                    .is_some(),
+            },
+            LightChange::Remove(kind, index) => {
+                if index < self.light_count(kind) {
+                    match kind {
+                        LightKind::Point => {
+                            self.remove_point_light(index);
+                        }
+                        LightKind::Directional => {
+                            self.remove_directional_light(index);
+                        }
+                        LightKind::Spot => {
+                            self.remove_spot_light(index);
+                        }
+                    }
+                    true
+                } else {
+                    false
+                }
+            }
+            LightChange::SetEnabled(kind, index, enabled) => match kind {
+                LightKind::Point => self
+                    .point_lights_mut()
+                    .get_mut(index)
+                    .map(|x| x.set_enabled(enabled))
+                    .is_some(),
+                LightKind::Directional => self
+                    .directional_lights_mut()
+                    .get_mut(index)
+                    .map(|x| x.set_enabled(enabled))
+                    .is_some(),
+                LightKind::Spot => self
+                    .spot_lights_mut()
+                    .get_mut(index)
+                    .map(|x| x.set_enabled(enabled))
+                    .is_some(),
+            },
+            LightChange::Clear => {
+                self.point_lights_mut().clear();
+                self.directional_lights_mut().clear();
+                self.spot_lights_mut().clear();
+                true
+            }
+        };
+
+        if !applied {
+            log::warn!(
+                "Light change {:?} ignored, light doesn't exist!",
+                light_change
+            );
+        }
+    }
+
+    /// Amount of world lights of the given kind
+    pub fn light_count(&self, kind: LightKind) -> usize {
+        match kind {
+            LightKind::Point => self.point_lights.len(),
+            LightKind::Directional => self.directional_lights.len(),
+            LightKind::Spot => self.spot_lights.len(),
+        }
+    }
+
+    /// World lights only, see [`Self::entity_lights`]
+    pub fn point_lights(&self) -> &[StandardPointLight] {
+        &self.point_lights
+    }
+
+    /// Marks the lights as changed
+    pub fn point_lights_mut(&mut self) -> &mut Vec<StandardPointLight> {
+        self.changed = true;
+        &mut self.point_lights
+    }
+
+    pub fn add_point_light(&mut self, point_light: StandardPointLight) {
+        self.point_lights_mut().push(point_light);
+    }
+
+    pub fn remove_point_light(&mut self, index: usize) -> StandardPointLight {
+        self.point_lights_mut().remove(index)
+    }
+
+    pub fn directional_lights(&self) -> &[StandardDirectionalLight] {
+        &self.directional_lights
+    }
+
+    /// Marks the lights as changed
+    pub fn directional_lights_mut(&mut self) -> &mut Vec<StandardDirectionalLight> {
+        self.changed = true;
+        &mut self.directional_lights
+    }
+
+    pub fn add_directional_light(&mut self, directional_light: StandardDirectionalLight) {
+        self.directional_lights_mut().push(directional_light);
+    }
+
+    pub fn remove_directional_light(&mut self, index: usize) -> StandardDirectionalLight {
+        self.directional_lights_mut().remove(index)
+    }
+
+    pub fn spot_lights(&self) -> &[StandardSpotLight] {
+        &self.spot_lights
+    }
+
+    /// Marks the lights as changed
+    pub fn spot_lights_mut(&mut self) -> &mut Vec<StandardSpotLight> {
+        self.changed = true;
+        &mut self.spot_lights
+    }
+
+    pub fn add_spot_light(&mut self, spot_light: StandardSpotLight) {
+        self.spot_lights_mut().push(spot_light);
+    }
+
+    pub fn remove_spot_light(&mut self, index: usize) -> StandardSpotLight {
+        self.spot_lights_mut().remove(index)
+    }
+
+    pub fn shadow_maps(&self) -> &ShadowMaps {
+        &self.shadow_maps
+    }
+
+    /// Recreates the shadow maps with the new settings
+    pub fn set_shadow_settings(
+        &mut self,
+        logical_device: &LogicalDevice,
+        shadow_settings: ShadowSettings,
+    ) -> EngineResult<()> {
+        self.shadow_maps = ShadowMaps::new(logical_device, shadow_settings)?;
+        self.changed = true;
+        self.update_buffer(logical_device);
+
+        Ok(())
+    }
+
+    pub fn bind_group_layout(logical_device: &LogicalDevice) -> BindGroupLayout {
+        logical_device
+            .device()
+            .create_bind_group_layout(&Self::BIND_GROUP_LAYOUT_DESCRIPTOR)
+    }
+
+    pub fn bind_group(&self) -> &BindGroup {
+        &self.bind_group
+    }
+}
@@ -4,14 +4,59 @@ pub use u_ambient_light::*;
 mod u_point_light;
 pub use u_point_light::*;
 
+mod u_directional_light;
+pub use u_directional_light::*;
+
+mod u_spot_light;
+pub use u_spot_light::*;
+
+mod attenuation;
+pub use attenuation::*;
+
 mod t_ambient_light;
 pub use t_ambient_light::*;
 
 mod t_point_light;
 pub use t_point_light::*;
 
+mod t_directional_light;
+pub use t_directional_light::*;
+
+mod t_spot_light;
+pub use t_spot_light::*;
+
 mod standard_ambient_light;
 pub use standard_ambient_light::*;
 
 mod standard_point_light;
 pub use standard_point_light::*;
+
+mod standard_directional_light;
+pub use standard_directional_light::*;
+
+mod standard_spot_light;
+pub use standard_spot_light::*;
+
+mod u_light_array_header;
+pub use u_light_array_header::*;
+
+mod light_array_buffer;
+pub use light_array_buffer::*;
+
+mod light_storage;
+pub use light_storage::*;
+
+mod shadow_settings;
+pub use shadow_settings::*;
+
+mod u_shadow_settings;
+pub use u_shadow_settings::*;
+
+mod shadow_maps;
+pub use shadow_maps::*;
+
+mod light_kind;
+pub use light_kind::*;
+
+mod light_change;
+pub use light_change::*;
@@ -0,0 +1,336 @@
+use std::{mem::size_of, num::NonZeroU64};
+
+use cgmath::{ortho, perspective, Deg, InnerSpace, Matrix4, Point3, Rad, Vector3};
+use wgpu::{
+    BindGroup, BindGroupDescriptor, BindGroupEntry, BindGroupLayout, BindGroupLayoutDescriptor,
+    BindGroupLayoutEntry, BindingResource, BindingType, Buffer, BufferAddress, BufferBinding,
+    BufferBindingType, BufferDescriptor, BufferUsages, SamplerBindingType, ShaderStages,
+    TextureAspect, TextureSampleType, TextureView, TextureViewDescriptor, TextureViewDimension,
+};
+
+use crate::engine::{Camera, DepthTexture, EngineResult, LogicalDevice, TTexture, TextureHelper};
+
+use super::{ShadowSettings, UShadowSettings};
+
+/// Shadow maps of all lights, stored as layers of a single depth texture.
+///
+/// Each layer has a view-projection matrix ("shadow caster") which is used
+/// to render the depth from the lights view and to look up the shadow map
+/// in the main pass.
+pub struct ShadowMaps {
+    settings: ShadowSettings,
+    depth_texture: DepthTexture,
+    array_view: TextureView,
+    layer_views: Vec<TextureView>,
+    caster_buffer: Buffer,
+    pass_buffer: Buffer,
+    bind_group: BindGroup,
+    pass_bind_group: BindGroup,
+    active_layers: u32,
+}
+
+impl ShadowMaps {
+    /// Dynamic uniform buffer offsets must be aligned to 256 bytes
+    pub const PASS_UNIFORM_STRIDE: BufferAddress = 256;
+
+    /// Layout used by the main pass to sample the shadow maps
+    pub const BIND_GROUP_LAYOUT_DESCRIPTOR: BindGroupLayoutDescriptor<'static> =
+        BindGroupLayoutDescriptor {
+            label: Some("Shadow Maps Bind Group Layout"),
+            entries: &[
+                // Shadow Maps
+                BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Texture {
+                        sample_type: TextureSampleType::Depth,
+                        view_dimension: TextureViewDimension::D2Array,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                // Comparison Sampler
+                BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Sampler(SamplerBindingType::Comparison),
+                    count: None,
+                },
+                // Settings & Shadow Casters
+                BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Buffer {
+                        ty: BufferBindingType::Storage { read_only: true },
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+            ],
+        };
+
+    /// Layout used by the depth-only pass, one shadow caster per layer
+    pub const PASS_BIND_GROUP_LAYOUT_DESCRIPTOR: BindGroupLayoutDescriptor<'static> =
+        BindGroupLayoutDescriptor {
+            label: Some("Shadow Pass Bind Group Layout"),
+            entries: &[BindGroupLayoutEntry {
+                binding: 0,
+                visibility: ShaderStages::VERTEX,
+                ty: BindingType::Buffer {
+                    ty: BufferBindingType::Uniform,
+                    has_dynamic_offset: true,
+                    min_binding_size: NonZeroU64::new(size_of::<[[f32; 4]; 4]>() as u64),
+                },
+                count: None,
+            }],
+        };
+
+    pub fn new(logical_device: &LogicalDevice, settings: ShadowSettings) -> EngineResult<Self> {
+        // At least one layer, otherwise the texture can't be created
+        let layer_count = settings.max_layers.max(1);
+
+        let depth_texture = DepthTexture::from_empty_layered(
+            logical_device,
+            settings.resolution,
+            layer_count,
+            Some("Shadow Maps"),
+        )?;
+
+        let array_view =
+            depth_texture
+                .texture()
+                .make_texture_view_descriptor(&TextureViewDescriptor {
+                    label: Some("Shadow Maps Array View"),
+                    dimension: Some(TextureViewDimension::D2Array),
+                    aspect: TextureAspect::DepthOnly,
+                    ..Default::default()
+                });
+        let layer_views = (0..layer_count)
+            .map(|layer| {
+                depth_texture
+                    .texture()
+                    .make_texture_view_descriptor(&TextureViewDescriptor {
+                        label: Some("Shadow Map Layer View"),
+                        dimension: Some(TextureViewDimension::D2),
+                        aspect: TextureAspect::DepthOnly,
+                        base_array_layer: layer,
+                        array_layer_count: Some(1),
+                        ..Default::default()
+                    })
+            })
+            .collect::<Vec<_>>();
+
+        let caster_buffer = logical_device.device().create_buffer(&BufferDescriptor {
+            label: Some("Shadow Caster Buffer"),
+            size: (size_of::<UShadowSettings>() + layer_count as usize * size_of::<[[f32; 4]; 4]>())
+                as BufferAddress,
+            usage: BufferUsages::STORAGE | BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let pass_buffer = logical_device.device().create_buffer(&BufferDescriptor {
+            label: Some("Shadow Pass Buffer"),
+            size: layer_count as BufferAddress * Self::PASS_UNIFORM_STRIDE,
+            usage: BufferUsages::UNIFORM | BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let bind_group = logical_device
+            .device()
+            .create_bind_group(&BindGroupDescriptor {
+                label: Some("Shadow Maps Bind Group"),
+                layout: &Self::bind_group_layout(logical_device),
+                entries: &[
+                    BindGroupEntry {
+                        binding: 0,
+                        resource: BindingResource::TextureView(&array_view),
+                    },
+                    BindGroupEntry {
+                        binding: 1,
+                        resource: BindingResource::Sampler(depth_texture.sampler()),
+                    },
+                    BindGroupEntry {
+                        binding: 2,
+                        resource: caster_buffer.as_entire_binding(),
+                    },
+                ],
+            });
+        let pass_bind_group = logical_device
+            .device()
+            .create_bind_group(&BindGroupDescriptor {
+                label: Some("Shadow Pass Bind Group"),
+                layout: &Self::pass_bind_group_layout(logical_device),
+                entries: &[BindGroupEntry {
+                    binding: 0,
+                    resource: BindingResource::Buffer(BufferBinding {
+                        buffer: &pass_buffer,
+                        offset: 0,
+                        size: NonZeroU64::new(size_of::<[[f32; 4]; 4]>() as u64),
+                    }),
+                }],
+            });
+
+        Ok(Self {
+            settings,
+            depth_texture,
+            array_view,
+            layer_views,
+            caster_buffer,
+            pass_buffer,
+            bind_group,
+            pass_bind_group,
+            active_layers: 0,
+        })
+    }
+
+    /// Uploads the shadow casters, one view-projection matrix per layer.
+    /// Casters exceeding the available layers are ignored.
+    pub fn update(&mut self, logical_device: &LogicalDevice, shadow_casters: &[Matrix4<f32>]) {
+        let shadow_casters = &shadow_casters[..shadow_casters.len().min(self.layer_views.len())];
+        self.active_layers = shadow_casters.len() as u32;
+
+        let casters = shadow_casters
+            .iter()
+            .map(|x| -> [[f32; 4]; 4] { (*x).into() })
+            .collect::<Vec<_>>();
+
+        let mut bytes = Vec::with_capacity(
+            size_of::<UShadowSettings>() + std::mem::size_of_val(casters.as_slice()),
+        );
+        bytes.extend_from_slice(bytemuck::bytes_of(&UShadowSettings::from_shadow_settings(
+            &self.settings,
+        )));
+        bytes.extend_from_slice(bytemuck::cast_slice(&casters));
+        logical_device
+            .queue()
+            .write_buffer(&self.caster_buffer, 0, &bytes);
+
+        for (layer, caster) in casters.iter().enumerate() {
+            logical_device.queue().write_buffer(
+                &self.pass_buffer,
+                layer as BufferAddress * Self::PASS_UNIFORM_STRIDE,
+                bytemuck::cast_slice(caster),
+            );
+        }
+    }
+
+    /// View-projection of a directional light.
+    /// Orthographic, covering [`ShadowSettings::directional_extent`] around
+    /// the world origin.
+    pub fn directional_shadow_caster(
+        settings: &ShadowSettings,
+        direction: Vector3<f32>,
+    ) -> Matrix4<f32> {
+        let direction = direction.normalize();
+        let extent = settings.directional_extent;
+        let eye = Point3::new(0.0, 0.0, 0.0) - direction * (settings.far_plane * 0.5);
+
+        let view = Matrix4::look_to_rh(eye, direction, Self::up_vector(direction));
+        let projection = ortho(-extent, extent, -extent, extent, 0.0, settings.far_plane);
+
+        Camera::OPENGL_TO_WGPU_MATRIX * projection * view
+    }
+
+    /// View-projection of a spot light.
+    /// Perspective, covering the outer cone.
+    pub fn spot_shadow_caster(
+        settings: &ShadowSettings,
+        position: Vector3<f32>,
+        direction: Vector3<f32>,
+        outer_cone_angle: Rad<f32>,
+    ) -> Matrix4<f32> {
+        let direction = direction.normalize();
+        let fovy = Rad((outer_cone_angle.0 * 2.0).clamp(0.01, Rad::from(Deg(170.0)).0));
+
+        let view = Matrix4::look_to_rh(
+            Point3::new(position.x, position.y, position.z),
+            direction,
+            Self::up_vector(direction),
+        );
+        let projection = perspective(fovy, 1.0, 0.05, settings.far_plane);
+
+        Camera::OPENGL_TO_WGPU_MATRIX * projection * view
+    }
+
+    /// View-projections of a point light, one per cube face.
+    /// Order: +X, -X, +Y, -Y, +Z, -Z
+    pub fn point_shadow_casters(
+        settings: &ShadowSettings,
+        position: Vector3<f32>,
+    ) -> [Matrix4<f32>; 6] {
+        let projection = perspective(Deg(90.0), 1.0, 0.05, settings.far_plane);
+
+        [
+            Vector3::unit_x(),
+            -Vector3::unit_x(),
+            Vector3::unit_y(),
+            -Vector3::unit_y(),
+            Vector3::unit_z(),
+            -Vector3::unit_z(),
+        ]
+        .map(|direction| {
+            let view = Matrix4::look_to_rh(
+                Point3::new(position.x, position.y, position.z),
+                direction,
+                Self::up_vector(direction),
+            );
+
+            Camera::OPENGL_TO_WGPU_MATRIX * projection * view
+        })
+    }
+
+    /// Up vector which isn't parallel to the given direction
+    fn up_vector(direction: Vector3<f32>) -> Vector3<f32> {
+        if direction.y.abs() > 0.99 {
+            Vector3::unit_z()
+        } else {
+            Vector3::unit_y()
+        }
+    }
+
+    pub fn bind_group_layout(logical_device: &LogicalDevice) -> BindGroupLayout {
+        logical_device
+            .device()
+            .create_bind_group_layout(&Self::BIND_GROUP_LAYOUT_DESCRIPTOR)
+    }
+
+    pub fn pass_bind_group_layout(logical_device: &LogicalDevice) -> BindGroupLayout {
+        logical_device
+            .device()
+            .create_bind_group_layout(&Self::PASS_BIND_GROUP_LAYOUT_DESCRIPTOR)
+    }
+
+    pub fn settings(&self) -> &ShadowSettings {
+        &self.settings
+    }
+
+    pub fn depth_texture(&self) -> &DepthTexture {
+        &self.depth_texture
+    }
+
+    pub fn array_view(&self) -> &TextureView {
+        &self.array_view
+    }
+
+    pub fn layer_views(&self) -> &[TextureView] {
+        &self.layer_views
+    }
+
+    /// Amount of layers in use by shadow casters
+    pub fn active_layers(&self) -> u32 {
+        self.active_layers
+    }
+
+    /// Offset into the pass buffer for [`Self::pass_bind_group`]
+    pub fn pass_offset(layer: u32) -> u32 {
+        layer * Self::PASS_UNIFORM_STRIDE as u32
+    }
+
+    pub fn bind_group(&self) -> &BindGroup {
+        &self.bind_group
+    }
+
+    pub fn pass_bind_group(&self) -> &BindGroup {
+        &self.pass_bind_group
+    }
+}
@@ -0,0 +1,46 @@
+/// Settings for the shadow maps of all lights
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ShadowSettings {
+    /// Width and height of a single shadow map
+    pub resolution: u32,
+
+    /// Amount of shadow map layers.
+    /// Directional and spot lights take one layer, point lights take six
+    /// (one per cube face).
+    /// Lights exceeding the available layers won't cast shadows.
+    pub max_layers: u32,
+
+    /// Subtracted from the fragments depth before comparing it against the
+    /// shadow map to prevent "shadow acne"
+    pub depth_bias: f32,
+
+    /// Offsets the fragments position along its normal (in world units)
+    /// before looking up the shadow map
+    pub normal_bias: f32,
+
+    /// Radius of the PCF kernel in texels.
+    /// 0 = a single sample, 1 = 3x3 samples, 2 = 5x5 samples, ...
+    pub pcf_radius: u32,
+
+    /// Half size of the area covered by directional light shadows,
+    /// centered around the world origin
+    pub directional_extent: f32,
+
+    /// Far plane of the light projections.
+    /// Geometry further away from a light won't cast shadows.
+    pub far_plane: f32,
+}
+
+impl Default for ShadowSettings {
+    fn default() -> Self {
+        Self {
+            resolution: 1024,
+            max_layers: 16,
+            depth_bias: 0.0005,
+            normal_bias: 0.02,
+            pcf_radius: 1,
+            directional_extent: 25.0,
+            far_plane: 100.0,
+        }
+    }
+}
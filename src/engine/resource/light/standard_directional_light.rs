@@ -0,0 +1,60 @@
+use cgmath::{InnerSpace, Vector3};
+
+use super::{TDirectionalLight, UDirectionalLight};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StandardDirectionalLight {
+    color: Vector3<f32>,
+    direction: Vector3<f32>,
+    strength: f32,
+    enabled: bool,
+}
+
+impl StandardDirectionalLight {
+    pub fn new(color: Vector3<f32>, direction: Vector3<f32>, strength: f32, enabled: bool) -> Self {
+        Self {
+            color,
+            direction: direction.normalize(),
+            strength,
+            enabled,
+        }
+    }
+}
+
+impl TDirectionalLight for StandardDirectionalLight {
+    fn to_uniform(&self) -> UDirectionalLight {
+        UDirectionalLight::new(self.color, self.direction, self.strength, self.enabled)
+    }
+
+    fn color(&self) -> Vector3<f32> {
+        self.color
+    }
+
+    fn set_color(&mut self, color: Vector3<f32>) {
+        self.color = color;
+    }
+
+    fn direction(&self) -> Vector3<f32> {
+        self.direction
+    }
+
+    fn set_direction(&mut self, direction: Vector3<f32>) {
+        self.direction = direction.normalize();
+    }
+
+    fn strength(&self) -> f32 {
+        self.strength
+    }
+
+    fn set_strength(&mut self, strength: f32) {
+        self.strength = strength;
+    }
+
+    fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+}
@@ -1,74 +1,38 @@
 use cgmath::Vector3;
-use wgpu::{
-    util::{BufferInitDescriptor, DeviceExt},
-    BindGroup, BindGroupDescriptor, BindGroupEntry, Buffer, BufferUsages,
-};
 
-use crate::engine::LogicalDevice;
-
-use super::{TPointLight, UPointLight};
+use super::{light_range_from_strength, TPointLight, UPointLight};
 
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct StandardPointLight {
     color: Vector3<f32>,
     position: Vector3<f32>,
     strength: f32,
+    range: Option<f32>,
     enabled: bool,
-    buffer: Buffer,
-    bind_group: BindGroup,
 }
 
 impl StandardPointLight {
-    pub fn new(
-        logical_device: &LogicalDevice,
-        color: Vector3<f32>,
-        position: Vector3<f32>,
-        strength: f32,
-        enabled: bool,
-    ) -> Self {
-        let empty_uniform = UPointLight::empty();
-        let buffer = logical_device
-            .device()
-            .create_buffer_init(&BufferInitDescriptor {
-                label: Some("Point Light Buffer"),
-                contents: bytemuck::cast_slice(&[empty_uniform]),
-                usage: BufferUsages::UNIFORM | BufferUsages::COPY_DST,
-            });
-
-        let bind_group_layout = Self::bind_group_layout(logical_device);
-        let bind_group = logical_device
-            .device()
-            .create_bind_group(&BindGroupDescriptor {
-                label: Some("Point Light Bind Group"),
-                layout: &bind_group_layout,
-                entries: &[BindGroupEntry {
-                    binding: 0,
-                    resource: buffer.as_entire_binding(),
-                }],
-            });
-
-        let mut light = Self {
+    pub fn new(color: Vector3<f32>, position: Vector3<f32>, strength: f32, enabled: bool) -> Self {
+        Self {
             color,
             position,
             strength,
+            range: None,
             enabled,
-            buffer,
-            bind_group,
-        };
-
-        light.update_buffer(logical_device);
+        }
+    }
 
-        light
+    /// Overrides the range derived from the strength
+    pub fn with_range(mut self, range: f32) -> Self {
+        self.range = Some(range);
+        self
     }
 }
 
 impl TPointLight for StandardPointLight {
     fn to_uniform(&self) -> UPointLight {
-        UPointLight::new(
-            self.color.into(),
-            self.position.into(),
-            self.strength,
-            self.enabled,
-        )
+        UPointLight::new(self.color, self.position, self.strength, self.enabled)
+            .with_range(self.range())
     }
 
     fn color(&self) -> Vector3<f32> {
@@ -95,19 +59,20 @@ impl TPointLight for StandardPointLight {
         self.strength = strength;
     }
 
-    fn enabled(&self) -> bool {
-        self.enabled
+    fn range(&self) -> f32 {
+        self.range
+            .unwrap_or_else(|| light_range_from_strength(self.strength))
     }
 
-    fn set_enabled(&mut self, enabled: bool) {
-        self.enabled = enabled;
+    fn set_range(&mut self, range: Option<f32>) {
+        self.range = range;
     }
 
-    fn buffer(&self) -> &Buffer {
-        &self.buffer
+    fn enabled(&self) -> bool {
+        self.enabled
     }
 
-    fn bind_group(&self) -> &BindGroup {
-        &self.bind_group
+    fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
     }
 }
@@ -0,0 +1,129 @@
+use cgmath::{InnerSpace, Rad, Vector3};
+
+use super::{light_range_from_strength, TSpotLight, USpotLight};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StandardSpotLight {
+    color: Vector3<f32>,
+    position: Vector3<f32>,
+    direction: Vector3<f32>,
+    inner_cone_angle: Rad<f32>,
+    outer_cone_angle: Rad<f32>,
+    strength: f32,
+    range: Option<f32>,
+    enabled: bool,
+}
+
+impl StandardSpotLight {
+    pub fn new<I: Into<Rad<f32>>, O: Into<Rad<f32>>>(
+        color: Vector3<f32>,
+        position: Vector3<f32>,
+        direction: Vector3<f32>,
+        inner_cone_angle: I,
+        outer_cone_angle: O,
+        strength: f32,
+        enabled: bool,
+    ) -> Self {
+        Self {
+            color,
+            position,
+            direction: direction.normalize(),
+            inner_cone_angle: inner_cone_angle.into(),
+            outer_cone_angle: outer_cone_angle.into(),
+            strength,
+            range: None,
+            enabled,
+        }
+    }
+
+    /// Overrides the range derived from the strength
+    pub fn with_range(mut self, range: f32) -> Self {
+        self.range = Some(range);
+        self
+    }
+}
+
+impl TSpotLight for StandardSpotLight {
+    fn to_uniform(&self) -> USpotLight {
+        // The outer cone must be wider than the inner cone, otherwise
+        // the falloff in the shader would be inverted or undefined
+        let inner_cone_cos = self.inner_cone_angle.0.cos();
+        let outer_cone_cos = self.outer_cone_angle.0.cos().min(inner_cone_cos - 0.0001);
+
+        USpotLight::new(
+            self.color,
+            self.position,
+            self.direction,
+            self.strength,
+            self.enabled,
+            inner_cone_cos,
+            outer_cone_cos,
+        )
+        .with_range(self.range())
+    }
+
+    fn color(&self) -> Vector3<f32> {
+        self.color
+    }
+
+    fn set_color(&mut self, color: Vector3<f32>) {
+        self.color = color;
+    }
+
+    fn position(&self) -> Vector3<f32> {
+        self.position
+    }
+
+    fn set_position(&mut self, position: Vector3<f32>) {
+        self.position = position;
+    }
+
+    fn direction(&self) -> Vector3<f32> {
+        self.direction
+    }
+
+    fn set_direction(&mut self, direction: Vector3<f32>) {
+        self.direction = direction.normalize();
+    }
+
+    fn inner_cone_angle(&self) -> Rad<f32> {
+        self.inner_cone_angle
+    }
+
+    fn set_inner_cone_angle(&mut self, inner_cone_angle: Rad<f32>) {
+        self.inner_cone_angle = inner_cone_angle;
+    }
+
+    fn outer_cone_angle(&self) -> Rad<f32> {
+        self.outer_cone_angle
+    }
+
+    fn set_outer_cone_angle(&mut self, outer_cone_angle: Rad<f32>) {
+        self.outer_cone_angle = outer_cone_angle;
+    }
+
+    fn strength(&self) -> f32 {
+        self.strength
+    }
+
+    fn set_strength(&mut self, strength: f32) {
+        self.strength = strength;
+    }
+
+    fn range(&self) -> f32 {
+        self.range
+            .unwrap_or_else(|| light_range_from_strength(self.strength))
+    }
+
+    fn set_range(&mut self, range: Option<f32>) {
+        self.range = range;
+    }
+
+    fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+}
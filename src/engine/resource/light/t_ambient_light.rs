@@ -1,3 +1,5 @@
+use std::{mem::size_of, num::NonZeroU64};
+
 use cgmath::Vector3;
 use wgpu::{
     BindGroup, BindGroupLayout, BindGroupLayoutDescriptor, BindGroupLayoutEntry, BindingType,
@@ -18,7 +20,7 @@ pub trait TAmbientLight {
                 ty: BindingType::Buffer {
                     ty: BufferBindingType::Uniform,
                     has_dynamic_offset: false,
-                    min_binding_size: None,
+                    min_binding_size: NonZeroU64::new(size_of::<UAmbientLight>() as u64),
                 },
                 count: None,
             }],
@@ -0,0 +1,24 @@
+use cgmath::Vector3;
+
+use super::UDirectionalLight;
+
+pub trait TDirectionalLight {
+    fn to_uniform(&self) -> UDirectionalLight;
+
+    fn color(&self) -> Vector3<f32>;
+
+    fn set_color(&mut self, color: Vector3<f32>);
+
+    /// Direction the light is travelling in
+    fn direction(&self) -> Vector3<f32>;
+
+    fn set_direction(&mut self, direction: Vector3<f32>);
+
+    fn strength(&self) -> f32;
+
+    fn set_strength(&mut self, strength: f32);
+
+    fn enabled(&self) -> bool;
+
+    fn set_enabled(&mut self, enabled: bool);
+}
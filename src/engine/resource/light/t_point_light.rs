@@ -1,37 +1,8 @@
 use cgmath::Vector3;
-use wgpu::{
-    BindGroup, BindGroupLayout, BindGroupLayoutDescriptor, BindGroupLayoutEntry, BindingType,
-    Buffer, BufferBindingType, ShaderStages,
-};
-
-use crate::engine::LogicalDevice;
 
 use super::UPointLight;
 
 pub trait TPointLight {
-    const BIND_GROUP_LAYOUT_DESCRIPTOR: BindGroupLayoutDescriptor<'static> =
-        BindGroupLayoutDescriptor {
-            label: Some("Point Light Bind Group Layout"),
-            entries: &[BindGroupLayoutEntry {
-                binding: 0,
-                visibility: ShaderStages::VERTEX_FRAGMENT,
-                ty: BindingType::Buffer {
-                    ty: BufferBindingType::Uniform,
-                    has_dynamic_offset: false,
-                    min_binding_size: None,
-                },
-                count: None,
-            }],
-        };
-
-    fn update_buffer(&mut self, logical_device: &LogicalDevice) {
-        logical_device.queue().write_buffer(
-            self.buffer(),
-            0,
-            bytemuck::cast_slice(&[self.to_uniform()]),
-        )
-    }
-
     fn to_uniform(&self) -> UPointLight;
 
     fn color(&self) -> Vector3<f32>;
@@ -46,17 +17,14 @@ pub trait TPointLight {
 
     fn set_strength(&mut self, strength: f32);
 
-    fn enabled(&self) -> bool;
+    /// Distance at which the light fades out entirely.
+    /// Derived from the strength unless set explicitly.
+    fn range(&self) -> f32;
 
-    fn set_enabled(&mut self, enabled: bool);
+    /// `None` derives the range from the strength
+    fn set_range(&mut self, range: Option<f32>);
 
-    fn bind_group_layout(logical_device: &LogicalDevice) -> BindGroupLayout {
-        logical_device
-            .device()
-            .create_bind_group_layout(&Self::BIND_GROUP_LAYOUT_DESCRIPTOR)
-    }
-
-    fn buffer(&self) -> &Buffer;
+    fn enabled(&self) -> bool;
 
-    fn bind_group(&self) -> &BindGroup;
+    fn set_enabled(&mut self, enabled: bool);
 }
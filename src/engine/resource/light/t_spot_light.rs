@@ -0,0 +1,46 @@
+use cgmath::{Rad, Vector3};
+
+use super::USpotLight;
+
+pub trait TSpotLight {
+    fn to_uniform(&self) -> USpotLight;
+
+    fn color(&self) -> Vector3<f32>;
+
+    fn set_color(&mut self, color: Vector3<f32>);
+
+    fn position(&self) -> Vector3<f32>;
+
+    fn set_position(&mut self, position: Vector3<f32>);
+
+    /// Direction the spot light is pointing at
+    fn direction(&self) -> Vector3<f32>;
+
+    fn set_direction(&mut self, direction: Vector3<f32>);
+
+    /// Angle between the direction and the edge of the fully lit cone
+    fn inner_cone_angle(&self) -> Rad<f32>;
+
+    fn set_inner_cone_angle(&mut self, inner_cone_angle: Rad<f32>);
+
+    /// Angle between the direction and the edge of the light cone.
+    /// Between the inner and outer cone angle the light fades out.
+    fn outer_cone_angle(&self) -> Rad<f32>;
+
+    fn set_outer_cone_angle(&mut self, outer_cone_angle: Rad<f32>);
+
+    fn strength(&self) -> f32;
+
+    fn set_strength(&mut self, strength: f32);
+
+    /// Distance at which the light fades out entirely.
+    /// Derived from the strength unless set explicitly.
+    fn range(&self) -> f32;
+
+    /// `None` derives the range from the strength
+    fn set_range(&mut self, range: Option<f32>);
+
+    fn enabled(&self) -> bool;
+
+    fn set_enabled(&mut self, enabled: bool);
+}
@@ -0,0 +1,51 @@
+use cgmath::Vector3;
+
+use crate::engine::GpuType;
+
+/// Padding is inserted by [`GpuType`], see [`crate::engine::GpuLayout`]
+#[derive(Debug, Copy, Clone, GpuType)]
+#[gpu_type(name = "DirectionalLight")]
+pub struct UDirectionalLight {
+    /// Color of the directional light
+    color: Vector3<f32>,
+
+    /// Direction the light is travelling in, normalized
+    direction: Vector3<f32>,
+
+    /// Strength of the directional light
+    strength: f32,
+
+    /// Whether the light is enabled or not
+    enabled: bool,
+
+    /// First shadow map layer of the light
+    /// -1 = the light doesn't cast shadows
+    shadow_layer: i32,
+}
+
+impl UDirectionalLight {
+    pub fn new(color: Vector3<f32>, direction: Vector3<f32>, strength: f32, enabled: bool) -> Self {
+        Self {
+            color,
+            direction,
+            strength,
+            enabled,
+            shadow_layer: -1,
+        }
+    }
+
+    /// Sets the first shadow map layer, `None` if the light doesn't cast shadows
+    pub fn with_shadow_layer(mut self, shadow_layer: Option<u32>) -> Self {
+        self.shadow_layer = shadow_layer.map_or(-1, |x| x as i32);
+        self
+    }
+
+    pub fn empty() -> Self {
+        Self::new(
+            Vector3::new(0.0, 0.0, 0.0),
+            Vector3::new(0.0, -1.0, 0.0),
+            0.0,
+            false,
+        )
+    }
+}
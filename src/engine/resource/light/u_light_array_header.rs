@@ -0,0 +1,24 @@
+use bytemuck::{Pod, Zeroable};
+
+/// Header in front of each light array inside a storage buffer
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Pod, Zeroable)]
+pub struct ULightArrayHeader {
+    /// Amount of lights following the header
+    count: u32,
+
+    /// Padding
+    ///
+    /// The light structures following the header are aligned to 16 bytes,
+    /// thus the header has to be 16 bytes as well.
+    _padding: [u32; 3],
+}
+
+impl ULightArrayHeader {
+    pub fn new(count: u32) -> Self {
+        Self {
+            count,
+            _padding: [0; 3],
+        }
+    }
+}
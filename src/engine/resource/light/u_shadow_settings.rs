@@ -0,0 +1,26 @@
+use bytemuck::{Pod, Zeroable};
+
+use super::ShadowSettings;
+
+/// Header in front of the shadow caster matrices
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Pod, Zeroable)]
+pub struct UShadowSettings {
+    depth_bias: f32,
+    normal_bias: f32,
+    pcf_radius: i32,
+
+    /// Size of a single texel in UV space
+    texel_size: f32,
+}
+
+impl UShadowSettings {
+    pub fn from_shadow_settings(shadow_settings: &ShadowSettings) -> Self {
+        Self {
+            depth_bias: shadow_settings.depth_bias,
+            normal_bias: shadow_settings.normal_bias,
+            pcf_radius: shadow_settings.pcf_radius as i32,
+            texel_size: 1.0 / shadow_settings.resolution as f32,
+        }
+    }
+}
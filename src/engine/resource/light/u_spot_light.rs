@@ -0,0 +1,85 @@
+use cgmath::Vector3;
+
+use crate::engine::GpuType;
+
+/// Padding is inserted by [`GpuType`], see [`crate::engine::GpuLayout`]
+#[derive(Debug, Copy, Clone, GpuType)]
+#[gpu_type(name = "SpotLight")]
+pub struct USpotLight {
+    /// Color of the spot light
+    color: Vector3<f32>,
+
+    /// Position of the spot light
+    position: Vector3<f32>,
+
+    /// Direction the spot light is pointing at, normalized
+    direction: Vector3<f32>,
+
+    /// Strength of the spot light
+    strength: f32,
+
+    /// Whether the light is enabled or not
+    enabled: bool,
+
+    /// Cosine of the inner cone angle.
+    /// Inside the inner cone the light has full strength.
+    inner_cone_cos: f32,
+
+    /// Cosine of the outer cone angle.
+    /// Between inner and outer cone the light fades out.
+    outer_cone_cos: f32,
+
+    /// Shadow map layer of the light
+    /// -1 = the light doesn't cast shadows
+    shadow_layer: i32,
+
+    /// Distance at which the light fades out entirely
+    range: f32,
+}
+
+impl USpotLight {
+    pub fn new(
+        color: Vector3<f32>,
+        position: Vector3<f32>,
+        direction: Vector3<f32>,
+        strength: f32,
+        enabled: bool,
+        inner_cone_cos: f32,
+        outer_cone_cos: f32,
+    ) -> Self {
+        Self {
+            color,
+            position,
+            direction,
+            strength,
+            enabled,
+            inner_cone_cos,
+            outer_cone_cos,
+            shadow_layer: -1,
+            range: f32::MAX,
+        }
+    }
+
+    pub fn with_range(mut self, range: f32) -> Self {
+        self.range = range;
+        self
+    }
+
+    /// Sets the shadow map layer, `None` if the light doesn't cast shadows
+    pub fn with_shadow_layer(mut self, shadow_layer: Option<u32>) -> Self {
+        self.shadow_layer = shadow_layer.map_or(-1, |x| x as i32);
+        self
+    }
+
+    pub fn empty() -> Self {
+        Self::new(
+            Vector3::new(0.0, 0.0, 0.0),
+            Vector3::new(0.0, 0.0, 0.0),
+            Vector3::new(0.0, -1.0, 0.0),
+            0.0,
+            false,
+            1.0,
+            1.0,
+        )
+    }
+}
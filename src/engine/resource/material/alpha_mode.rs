@@ -0,0 +1,31 @@
+/// How the alpha of a material gets used, mirrors the glTF `alphaMode`
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum AlphaMode {
+    /// Alpha is ignored
+    #[default]
+    Opaque,
+    /// Fragments with an alpha below the cutoff get discarded,
+    /// the remaining ones are opaque
+    Mask(f32),
+    /// Blended with whatever is behind it.
+    /// Rendered after all opaque meshes, sorted back-to-front.
+    Blend,
+}
+
+impl AlphaMode {
+    /// Cutoff of glTF masks without an explicit one
+    pub const DEFAULT_CUTOFF: f32 = 0.5;
+
+    /// Cutoff uploaded with the material, 0.0 never discards anything
+    pub fn alpha_cutoff(&self) -> f32 {
+        match self {
+            Self::Mask(cutoff) => *cutoff,
+            Self::Opaque | Self::Blend => 0.0,
+        }
+    }
+
+    /// Rendered in the transparent pass
+    pub fn is_transparent(&self) -> bool {
+        matches!(self, Self::Blend)
+    }
+}
@@ -0,0 +1,149 @@
+use std::hash::{Hash, Hasher};
+
+use wgpu::{
+    BindGroupLayoutDescriptor, BlendState, CompareFunction, Face, PolygonMode, PrimitiveTopology,
+};
+
+use super::AlphaMode;
+
+/// Describes how the meshes of a material type get rendered.
+/// The renderer resolves one render pipeline per distinct descriptor
+/// (`id`, shader defines & render state) and reuses it for every material
+/// returning an equal descriptor.
+#[derive(Debug, Clone)]
+pub struct MaterialPipelineDescriptor {
+    /// Unique per material type
+    pub id: &'static str,
+    /// WGSL shader, resolved by the [`crate::engine::ShaderPreprocessor`].
+    /// Includes `common/mesh.wgsl` or `common/lighting.wgsl` and defines
+    /// the group 0 bindings and the `fs_main` entry point.
+    pub shader_path: &'static str,
+    /// Defined while preprocessing the shader
+    pub shader_defines: Vec<&'static str>,
+    /// Layout of the group 0 bind group of the material
+    pub bind_group_layout_descriptor: &'static BindGroupLayoutDescriptor<'static>,
+    /// Drawn in the transparent pass, sorted back-to-front
+    pub transparent: bool,
+    pub blend: BlendState,
+    pub cull_mode: Option<Face>,
+    /// `Line` is only available if the adapter supports `Features::POLYGON_MODE_LINE`,
+    /// `Point` isn't enabled at all
+    pub polygon_mode: PolygonMode,
+    pub topology: PrimitiveTopology,
+    pub depth_write_enabled: bool,
+    pub depth_compare: CompareFunction,
+}
+
+// `BindGroupLayoutDescriptor` isn't comparable, the layouts are `'static`
+// constants though, so they're compared by address
+impl PartialEq for MaterialPipelineDescriptor {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+            && self.shader_path == other.shader_path
+            && self.shader_defines == other.shader_defines
+            && std::ptr::eq(
+                self.bind_group_layout_descriptor,
+                other.bind_group_layout_descriptor,
+            )
+            && self.transparent == other.transparent
+            && self.blend == other.blend
+            && self.cull_mode == other.cull_mode
+            && self.polygon_mode == other.polygon_mode
+            && self.topology == other.topology
+            && self.depth_write_enabled == other.depth_write_enabled
+            && self.depth_compare == other.depth_compare
+    }
+}
+
+impl Eq for MaterialPipelineDescriptor {}
+
+impl Hash for MaterialPipelineDescriptor {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+        self.shader_path.hash(state);
+        self.shader_defines.hash(state);
+        std::ptr::hash(self.bind_group_layout_descriptor, state);
+        self.transparent.hash(state);
+        self.blend.hash(state);
+        self.cull_mode.hash(state);
+        self.polygon_mode.hash(state);
+        self.topology.hash(state);
+        self.depth_write_enabled.hash(state);
+        self.depth_compare.hash(state);
+    }
+}
+
+impl MaterialPipelineDescriptor {
+    /// Opaque, back-face culled and depth tested
+    pub fn new(
+        id: &'static str,
+        shader_path: &'static str,
+        bind_group_layout_descriptor: &'static BindGroupLayoutDescriptor<'static>,
+    ) -> Self {
+        Self {
+            id,
+            shader_path,
+            shader_defines: Vec::new(),
+            bind_group_layout_descriptor,
+            transparent: false,
+            blend: BlendState::REPLACE,
+            cull_mode: Some(Face::Back),
+            polygon_mode: PolygonMode::Fill,
+            topology: PrimitiveTopology::TriangleList,
+            depth_write_enabled: true,
+            depth_compare: CompareFunction::Less,
+        }
+    }
+
+    pub fn with_shader_define(mut self, name: &'static str) -> Self {
+        if !self.shader_defines.contains(&name) {
+            self.shader_defines.push(name);
+            self.shader_defines.sort_unstable();
+        }
+        self
+    }
+
+    /// [`AlphaMode::Blend`] makes the pipeline transparent,
+    /// i.e. alpha blended without depth writes.
+    /// [`AlphaMode::Mask`] defines `ALPHA_MASK`, the material shader discards
+    /// fragments below the cutoff then.
+    pub fn with_alpha_mode(mut self, alpha_mode: AlphaMode) -> Self {
+        if let AlphaMode::Mask(_) = alpha_mode {
+            self = self.with_shader_define("ALPHA_MASK");
+        }
+        self.transparent = alpha_mode.is_transparent();
+        if self.transparent {
+            self.blend = BlendState::ALPHA_BLENDING;
+            self.depth_write_enabled = false;
+        }
+        self
+    }
+
+    pub fn with_blend(mut self, blend: BlendState) -> Self {
+        self.blend = blend;
+        self
+    }
+
+    /// `None` renders both sides
+    pub fn with_cull_mode(mut self, cull_mode: Option<Face>) -> Self {
+        self.cull_mode = cull_mode;
+        self
+    }
+
+    /// E.g. `PolygonMode::Line` for wireframes
+    pub fn with_polygon_mode(mut self, polygon_mode: PolygonMode) -> Self {
+        self.polygon_mode = polygon_mode;
+        self
+    }
+
+    pub fn with_topology(mut self, topology: PrimitiveTopology) -> Self {
+        self.topology = topology;
+        self
+    }
+
+    pub fn with_depth(mut self, depth_write_enabled: bool, depth_compare: CompareFunction) -> Self {
+        self.depth_write_enabled = depth_write_enabled;
+        self.depth_compare = depth_compare;
+        self
+    }
+}
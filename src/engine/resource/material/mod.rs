@@ -1,5 +1,41 @@
+mod u_material;
+pub use u_material::*;
+
 mod t_material;
 pub use t_material::*;
 
 mod standard_material;
 pub use standard_material::*;
+
+mod u_pbr_material;
+pub use u_pbr_material::*;
+
+mod alpha_mode;
+pub use alpha_mode::*;
+
+mod material_pipeline_descriptor;
+pub use material_pipeline_descriptor::*;
+
+mod pbr_material_descriptor;
+pub use pbr_material_descriptor::*;
+
+mod pbr_material;
+pub use pbr_material::*;
+
+mod u_unlit_material;
+pub use u_unlit_material::*;
+
+mod unlit_material;
+pub use unlit_material::*;
+
+mod u_toon_material;
+pub use u_toon_material::*;
+
+mod toon_material;
+pub use toon_material::*;
+
+mod u_vertex_color_material;
+pub use u_vertex_color_material::*;
+
+mod vertex_color_material;
+pub use vertex_color_material::*;
@@ -0,0 +1,357 @@
+use std::{mem::size_of, num::NonZeroU64};
+
+use image::DynamicImage;
+use wgpu::{
+    BindGroup, BindGroupDescriptor, BindGroupEntry, BindGroupLayout, BindGroupLayoutDescriptor,
+    BindGroupLayoutEntry, BindingResource, BindingType, Buffer, BufferBindingType, BufferUsages,
+    SamplerBindingType, ShaderStages, TextureFormat, TextureSampleType, TextureViewDimension,
+};
+
+use crate::engine::{
+    BufferHelper, DiffuseTexture, EngineResult, LogicalDevice, NormalTexture, TTexture,
+    NORMAL_TEXTURE_FORMAT,
+};
+
+use super::{
+    AlphaMode, MaterialPipelineDescriptor, PbrMaterialDescriptor, TMaterial, UPbrMaterial,
+};
+
+/// Physically based material following the glTF 2.0 metallic-roughness model.
+/// Gets shaded with Cook-Torrance and lit by the environment map, if any.
+pub struct PbrMaterial {
+    name: String,
+    base_color_texture: DiffuseTexture,
+    normal_texture: NormalTexture,
+    metallic_roughness_texture: DiffuseTexture,
+    occlusion_texture: DiffuseTexture,
+    emissive_texture: DiffuseTexture,
+    base_color_factor: [f32; 4],
+    emissive_factor: [f32; 3],
+    metallic_factor: f32,
+    roughness_factor: f32,
+    normal_scale: f32,
+    occlusion_strength: f32,
+    alpha_mode: AlphaMode,
+    buffer: Buffer,
+    bind_group: BindGroup,
+}
+
+impl PbrMaterial {
+    /// Format of the linear (non-color) textures
+    pub const DATA_TEXTURE_FORMAT: TextureFormat = TextureFormat::Rgba8Unorm;
+
+    pub const BIND_GROUP_LAYOUT_DESCRIPTOR: BindGroupLayoutDescriptor<'static> =
+        BindGroupLayoutDescriptor {
+            label: Some("PBR Material"),
+            entries: &[
+                // Base Color
+                Self::texture_layout_entry(0),
+                Self::sampler_layout_entry(1),
+                // Normal
+                Self::texture_layout_entry(2),
+                Self::sampler_layout_entry(3),
+                BindGroupLayoutEntry {
+                    binding: 4,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Buffer {
+                        ty: BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: NonZeroU64::new(size_of::<UPbrMaterial>() as u64),
+                    },
+                    count: None,
+                },
+                // Metallic & Roughness
+                Self::texture_layout_entry(5),
+                Self::sampler_layout_entry(6),
+                // Occlusion
+                Self::texture_layout_entry(7),
+                Self::sampler_layout_entry(8),
+                // Emissive
+                Self::texture_layout_entry(9),
+                Self::sampler_layout_entry(10),
+            ],
+        };
+
+    const fn texture_layout_entry(binding: u32) -> BindGroupLayoutEntry {
+        BindGroupLayoutEntry {
+            binding,
+            visibility: ShaderStages::FRAGMENT,
+            ty: BindingType::Texture {
+                sample_type: TextureSampleType::Float { filterable: true },
+                view_dimension: TextureViewDimension::D2,
+                multisampled: false,
+            },
+            count: None,
+        }
+    }
+
+    const fn sampler_layout_entry(binding: u32) -> BindGroupLayoutEntry {
+        BindGroupLayoutEntry {
+            binding,
+            visibility: ShaderStages::FRAGMENT,
+            ty: BindingType::Sampler(SamplerBindingType::Filtering),
+            count: None,
+        }
+    }
+
+    pub fn new(
+        logical_device: &LogicalDevice,
+        descriptor: &PbrMaterialDescriptor,
+    ) -> EngineResult<Self> {
+        let name = descriptor
+            .name
+            .clone()
+            .unwrap_or(String::from("PbrMaterial"));
+
+        let base_color_texture = Self::make_texture(
+            logical_device,
+            descriptor.base_color_texture.as_ref(),
+            [255, 255, 255, 255],
+            DiffuseTexture::TEXTURE_FORMAT,
+            &format!("{} Base Color", name),
+        )?;
+        let normal_texture = Self::make_texture(
+            logical_device,
+            descriptor.normal_texture.as_ref(),
+            // Flat, pointing straight out of the surface
+            [128, 128, 255, 255],
+            NORMAL_TEXTURE_FORMAT,
+            &format!("{} Normal", name),
+        )?;
+        let metallic_roughness_texture = Self::make_texture(
+            logical_device,
+            descriptor.metallic_roughness_texture.as_ref(),
+            [255, 255, 255, 255],
+            Self::DATA_TEXTURE_FORMAT,
+            &format!("{} Metallic Roughness", name),
+        )?;
+        let occlusion_texture = Self::make_texture(
+            logical_device,
+            descriptor.occlusion_texture.as_ref(),
+            [255, 255, 255, 255],
+            Self::DATA_TEXTURE_FORMAT,
+            &format!("{} Occlusion", name),
+        )?;
+        let emissive_texture = Self::make_texture(
+            logical_device,
+            descriptor.emissive_texture.as_ref(),
+            [255, 255, 255, 255],
+            DiffuseTexture::TEXTURE_FORMAT,
+            &format!("{} Emissive", name),
+        )?;
+
+        let buffer = logical_device.make_buffer(
+            Some(&format!("{} Buffer", name)),
+            &[UPbrMaterial::new(
+                descriptor.base_color_factor,
+                descriptor.emissive_factor,
+                descriptor.metallic_factor,
+                descriptor.roughness_factor,
+                descriptor.normal_scale,
+                descriptor.occlusion_strength,
+                descriptor.alpha_mode.alpha_cutoff(),
+            )],
+            BufferUsages::UNIFORM | BufferUsages::COPY_DST,
+        );
+
+        let bind_group = logical_device
+            .device()
+            .create_bind_group(&BindGroupDescriptor {
+                label: Some(&format!("{} Bind Group", name)),
+                layout: &Self::bind_group_layout(logical_device),
+                entries: &[
+                    BindGroupEntry {
+                        binding: 0,
+                        resource: BindingResource::TextureView(base_color_texture.view()),
+                    },
+                    BindGroupEntry {
+                        binding: 1,
+                        resource: BindingResource::Sampler(base_color_texture.sampler()),
+                    },
+                    BindGroupEntry {
+                        binding: 2,
+                        resource: BindingResource::TextureView(normal_texture.view()),
+                    },
+                    BindGroupEntry {
+                        binding: 3,
+                        resource: BindingResource::Sampler(normal_texture.sampler()),
+                    },
+                    BindGroupEntry {
+                        binding: 4,
+                        resource: buffer.as_entire_binding(),
+                    },
+                    BindGroupEntry {
+                        binding: 5,
+                        resource: BindingResource::TextureView(metallic_roughness_texture.view()),
+                    },
+                    BindGroupEntry {
+                        binding: 6,
+                        resource: BindingResource::Sampler(metallic_roughness_texture.sampler()),
+                    },
+                    BindGroupEntry {
+                        binding: 7,
+                        resource: BindingResource::TextureView(occlusion_texture.view()),
+                    },
+                    BindGroupEntry {
+                        binding: 8,
+                        resource: BindingResource::Sampler(occlusion_texture.sampler()),
+                    },
+                    BindGroupEntry {
+                        binding: 9,
+                        resource: BindingResource::TextureView(emissive_texture.view()),
+                    },
+                    BindGroupEntry {
+                        binding: 10,
+                        resource: BindingResource::Sampler(emissive_texture.sampler()),
+                    },
+                ],
+            });
+
+        Ok(Self {
+            name,
+            base_color_texture,
+            normal_texture,
+            metallic_roughness_texture,
+            occlusion_texture,
+            emissive_texture,
+            base_color_factor: descriptor.base_color_factor,
+            emissive_factor: descriptor.emissive_factor,
+            metallic_factor: descriptor.metallic_factor,
+            roughness_factor: descriptor.roughness_factor,
+            normal_scale: descriptor.normal_scale,
+            occlusion_strength: descriptor.occlusion_strength,
+            alpha_mode: descriptor.alpha_mode,
+            buffer,
+            bind_group,
+        })
+    }
+
+    /// Uploads the image or, if there is none, a single pixel of `fallback`
+    fn make_texture(
+        logical_device: &LogicalDevice,
+        image: Option<&DynamicImage>,
+        fallback: [u8; 4],
+        format: TextureFormat,
+        label: &str,
+    ) -> EngineResult<DiffuseTexture> {
+        match image {
+            Some(image) => {
+                DiffuseTexture::from_image(logical_device, image, Some(format), Some(label))
+            }
+            None => DiffuseTexture::from_color(logical_device, fallback, Some(format), Some(label)),
+        }
+    }
+
+    pub fn bind_group_layout(logical_device: &LogicalDevice) -> BindGroupLayout {
+        logical_device
+            .device()
+            .create_bind_group_layout(&Self::BIND_GROUP_LAYOUT_DESCRIPTOR)
+    }
+
+    pub fn base_color_texture(&self) -> &DiffuseTexture {
+        &self.base_color_texture
+    }
+
+    pub fn normal_texture(&self) -> &NormalTexture {
+        &self.normal_texture
+    }
+
+    pub fn buffer(&self) -> &Buffer {
+        &self.buffer
+    }
+
+    pub fn to_uniform(&self) -> UPbrMaterial {
+        UPbrMaterial::new(
+            self.base_color_factor,
+            self.emissive_factor,
+            self.metallic_factor,
+            self.roughness_factor,
+            self.normal_scale,
+            self.occlusion_strength,
+            self.alpha_mode.alpha_cutoff(),
+        )
+    }
+
+    pub fn metallic_roughness_texture(&self) -> &DiffuseTexture {
+        &self.metallic_roughness_texture
+    }
+
+    pub fn occlusion_texture(&self) -> &DiffuseTexture {
+        &self.occlusion_texture
+    }
+
+    pub fn emissive_texture(&self) -> &DiffuseTexture {
+        &self.emissive_texture
+    }
+
+    pub fn base_color_factor(&self) -> [f32; 4] {
+        self.base_color_factor
+    }
+
+    /// Requires [`TMaterial::update_buffer`] to be called afterwards
+    pub fn set_base_color_factor(&mut self, base_color_factor: [f32; 4]) {
+        self.base_color_factor = base_color_factor;
+    }
+
+    pub fn metallic_factor(&self) -> f32 {
+        self.metallic_factor
+    }
+
+    /// Requires [`TMaterial::update_buffer`] to be called afterwards
+    pub fn set_metallic_factor(&mut self, metallic_factor: f32) {
+        self.metallic_factor = metallic_factor;
+    }
+
+    pub fn roughness_factor(&self) -> f32 {
+        self.roughness_factor
+    }
+
+    /// Requires [`TMaterial::update_buffer`] to be called afterwards
+    pub fn set_roughness_factor(&mut self, roughness_factor: f32) {
+        self.roughness_factor = roughness_factor;
+    }
+
+    pub fn emissive_factor(&self) -> [f32; 3] {
+        self.emissive_factor
+    }
+
+    /// Requires [`TMaterial::update_buffer`] to be called afterwards
+    pub fn set_emissive_factor(&mut self, emissive_factor: [f32; 3]) {
+        self.emissive_factor = emissive_factor;
+    }
+}
+
+impl TMaterial for PbrMaterial {
+    fn update_buffer(&self, logical_device: &LogicalDevice) {
+        logical_device.queue().write_buffer(
+            &self.buffer,
+            0,
+            bytemuck::cast_slice(&[self.to_uniform()]),
+        )
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn alpha_mode(&self) -> AlphaMode {
+        self.alpha_mode
+    }
+
+    fn set_alpha_mode(&mut self, alpha_mode: AlphaMode) {
+        self.alpha_mode = alpha_mode;
+    }
+
+    fn pipeline_descriptor(&self) -> MaterialPipelineDescriptor {
+        MaterialPipelineDescriptor::new(
+            "PbrMaterial",
+            "pbr.wgsl",
+            &Self::BIND_GROUP_LAYOUT_DESCRIPTOR,
+        )
+        .with_alpha_mode(self.alpha_mode)
+    }
+
+    fn bind_group(&self) -> &BindGroup {
+        &self.bind_group
+    }
+}
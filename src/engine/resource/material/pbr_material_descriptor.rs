@@ -0,0 +1,55 @@
+use image::DynamicImage;
+
+use super::AlphaMode;
+
+/// All inputs of the glTF metallic-roughness material model.
+/// Missing textures behave as if they were white (or flat for the normal map),
+/// so only the factors take effect.
+#[derive(Debug, Clone)]
+pub struct PbrMaterialDescriptor {
+    pub name: Option<String>,
+
+    /// sRGB color and alpha
+    pub base_color_texture: Option<DynamicImage>,
+    pub base_color_factor: [f32; 4],
+
+    /// Roughness in the G channel, metallic in the B channel
+    pub metallic_roughness_texture: Option<DynamicImage>,
+    pub metallic_factor: f32,
+    pub roughness_factor: f32,
+
+    /// Tangent space normal map
+    pub normal_texture: Option<DynamicImage>,
+    pub normal_scale: f32,
+
+    /// Ambient occlusion in the R channel
+    pub occlusion_texture: Option<DynamicImage>,
+    pub occlusion_strength: f32,
+
+    /// sRGB color of the light the surface emits itself
+    pub emissive_texture: Option<DynamicImage>,
+    pub emissive_factor: [f32; 3],
+
+    pub alpha_mode: AlphaMode,
+}
+
+impl Default for PbrMaterialDescriptor {
+    /// Defaults as defined by glTF 2.0
+    fn default() -> Self {
+        Self {
+            name: None,
+            base_color_texture: None,
+            base_color_factor: [1.0; 4],
+            metallic_roughness_texture: None,
+            metallic_factor: 1.0,
+            roughness_factor: 1.0,
+            normal_texture: None,
+            normal_scale: 1.0,
+            occlusion_texture: None,
+            occlusion_strength: 1.0,
+            emissive_texture: None,
+            emissive_factor: [0.0; 3],
+            alpha_mode: AlphaMode::Opaque,
+        }
+    }
+}
@@ -1,25 +1,33 @@
-use std::path::Path;
+use std::{mem::size_of, num::NonZeroU64, path::Path};
 
 use wgpu::{
     BindGroup, BindGroupDescriptor, BindGroupEntry, BindGroupLayout, BindGroupLayoutDescriptor,
-    BindGroupLayoutEntry, BindingResource, BindingType, SamplerBindingType, ShaderStages,
-    TextureSampleType, TextureViewDimension,
+    BindGroupLayoutEntry, BindingResource, BindingType, Buffer, BufferBindingType, BufferUsages,
+    SamplerBindingType, ShaderStages, TextureSampleType, TextureViewDimension,
 };
 
 use crate::engine::{
-    DiffuseTexture, EngineResult, LogicalDevice, NormalTexture, ResourceManager, TTexture,
+    BufferHelper, DiffuseTexture, EngineResult, LogicalDevice, NormalTexture, ResourceManager,
+    TTexture,
 };
 
-use super::TMaterial;
+use super::{AlphaMode, MaterialPipelineDescriptor, TMaterial, UMaterial};
 
 pub struct StandardMaterial {
     name: String,
     diffuse_texture: DiffuseTexture,
     normal_texture: NormalTexture,
+    specular_strength: f32,
+    shininess: f32,
+    alpha_mode: AlphaMode,
+    buffer: Buffer,
     bind_group: BindGroup,
 }
 
 impl StandardMaterial {
+    pub const DEFAULT_SPECULAR_STRENGTH: f32 = 0.5;
+    pub const DEFAULT_SHININESS: f32 = 32.0;
+
     pub const BIND_GROUP_LAYOUT_DESCRIPTOR: BindGroupLayoutDescriptor<'static> =
         BindGroupLayoutDescriptor {
             label: Some("Standard Material"),
@@ -56,6 +64,16 @@ impl StandardMaterial {
                     ty: BindingType::Sampler(SamplerBindingType::Filtering),
                     count: None,
                 },
+                BindGroupLayoutEntry {
+                    binding: 4,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Buffer {
+                        ty: BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: NonZeroU64::new(size_of::<UMaterial>() as u64),
+                    },
+                    count: None,
+                },
             ],
         };
 
@@ -85,10 +103,21 @@ impl StandardMaterial {
         diffuse_texture: DiffuseTexture,
         normal_texture: NormalTexture,
     ) -> EngineResult<Self> {
+        let buffer = logical_device.make_buffer(
+            Some("StandardMaterialBuffer"),
+            &[UMaterial::new(
+                Self::DEFAULT_SPECULAR_STRENGTH,
+                Self::DEFAULT_SHININESS,
+                AlphaMode::default().alpha_cutoff(),
+            )],
+            BufferUsages::UNIFORM | BufferUsages::COPY_DST,
+        );
+
         let bind_group = Self::make_bind_group(
             Some("StandardMaterialBindGroup"),
             &diffuse_texture,
             &normal_texture,
+            &buffer,
             logical_device,
         );
 
@@ -96,14 +125,78 @@ impl StandardMaterial {
             name: String::from("StandardMaterial"),
             diffuse_texture,
             normal_texture,
+            specular_strength: Self::DEFAULT_SPECULAR_STRENGTH,
+            shininess: Self::DEFAULT_SHININESS,
+            alpha_mode: AlphaMode::default(),
+            buffer,
             bind_group,
         })
     }
 
+    /// Sets specular strength and shininess and uploads them
+    pub fn with_specular(
+        mut self,
+        logical_device: &LogicalDevice,
+        specular_strength: f32,
+        shininess: f32,
+    ) -> Self {
+        self.specular_strength = specular_strength;
+        self.shininess = shininess;
+        self.update_buffer(logical_device);
+        self
+    }
+
+    pub fn bind_group_layout(logical_device: &LogicalDevice) -> BindGroupLayout {
+        logical_device
+            .device()
+            .create_bind_group_layout(&Self::BIND_GROUP_LAYOUT_DESCRIPTOR)
+    }
+
+    pub fn diffuse_texture(&self) -> &DiffuseTexture {
+        &self.diffuse_texture
+    }
+
+    pub fn normal_texture(&self) -> &NormalTexture {
+        &self.normal_texture
+    }
+
+    pub fn buffer(&self) -> &Buffer {
+        &self.buffer
+    }
+
+    pub fn to_uniform(&self) -> UMaterial {
+        UMaterial::new(
+            self.specular_strength,
+            self.shininess,
+            self.alpha_mode.alpha_cutoff(),
+        )
+    }
+
+    /// Multiplier for the specular highlights
+    pub fn specular_strength(&self) -> f32 {
+        self.specular_strength
+    }
+
+    /// Requires [`TMaterial::update_buffer`] to be called afterwards
+    pub fn set_specular_strength(&mut self, specular_strength: f32) {
+        self.specular_strength = specular_strength;
+    }
+
+    /// Exponent of the specular highlights
+    pub fn shininess(&self) -> f32 {
+        self.shininess
+    }
+
+    /// Requires [`TMaterial::update_buffer`] to be called afterwards
+    pub fn set_shininess(&mut self, shininess: f32) {
+        self.shininess = shininess;
+    }
+
     fn make_bind_group(
         label: Option<&str>,
         diffuse_texture: &DiffuseTexture,
         normal_texture: &NormalTexture,
+        buffer: &Buffer,
         logical_device: &LogicalDevice,
     ) -> BindGroup {
         let bind_group_layout = Self::bind_group_layout(logical_device);
@@ -130,28 +223,43 @@ impl StandardMaterial {
                         binding: 3,
                         resource: BindingResource::Sampler(normal_texture.sampler()),
                     },
+                    BindGroupEntry {
+                        binding: 4,
+                        resource: buffer.as_entire_binding(),
+                    },
                 ],
             })
     }
 }
 
 impl TMaterial for StandardMaterial {
+    fn update_buffer(&self, logical_device: &LogicalDevice) {
+        logical_device.queue().write_buffer(
+            &self.buffer,
+            0,
+            bytemuck::cast_slice(&[self.to_uniform()]),
+        )
+    }
+
     fn name(&self) -> &str {
         &self.name
     }
 
-    fn diffuse_texture(&self) -> &DiffuseTexture {
-        &self.diffuse_texture
+    fn alpha_mode(&self) -> AlphaMode {
+        self.alpha_mode
     }
 
-    fn normal_texture(&self) -> &NormalTexture {
-        &self.normal_texture
+    fn set_alpha_mode(&mut self, alpha_mode: AlphaMode) {
+        self.alpha_mode = alpha_mode;
     }
 
-    fn bind_group_layout(logical_device: &LogicalDevice) -> BindGroupLayout {
-        logical_device
-            .device()
-            .create_bind_group_layout(&Self::BIND_GROUP_LAYOUT_DESCRIPTOR)
+    fn pipeline_descriptor(&self) -> MaterialPipelineDescriptor {
+        MaterialPipelineDescriptor::new(
+            "StandardMaterial",
+            "blinn_phong.wgsl",
+            &Self::BIND_GROUP_LAYOUT_DESCRIPTOR,
+        )
+        .with_alpha_mode(self.alpha_mode)
     }
 
     fn bind_group(&self) -> &BindGroup {
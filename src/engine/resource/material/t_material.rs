@@ -1,19 +1,32 @@
 use core::fmt::{Debug, Formatter, Result};
 
-use wgpu::{BindGroup, BindGroupLayout};
+use wgpu::BindGroup;
 
-use crate::engine::{DiffuseTexture, LogicalDevice, NormalTexture};
+use crate::engine::LogicalDevice;
 
+use super::{AlphaMode, MaterialPipelineDescriptor};
+
+/// A material decides how a mesh gets shaded.
+/// Besides the built-in materials, custom ones can bring their own shader,
+/// bind group layout and render state via [`Self::pipeline_descriptor`].
 pub trait TMaterial {
+    /// Uploads the uniform of the material, if it has any
+    fn update_buffer(&self, logical_device: &LogicalDevice);
+
     fn name(&self) -> &str;
 
-    fn diffuse_texture(&self) -> &DiffuseTexture;
+    fn alpha_mode(&self) -> AlphaMode;
+
+    /// Switching between [`AlphaMode::Blend`] and the others moves the material
+    /// into another render pass.
+    /// Requires [`TMaterial::update_buffer`] to be called afterwards.
+    fn set_alpha_mode(&mut self, alpha_mode: AlphaMode);
 
-    fn normal_texture(&self) -> &NormalTexture;
+    /// Shader, bind group layout & render state of the material type
+    fn pipeline_descriptor(&self) -> MaterialPipelineDescriptor;
 
-    fn bind_group_layout(logical_device: &LogicalDevice) -> BindGroupLayout
-    where
-        Self: Sized;
+    /// Bound to group 0, must match
+    /// [`MaterialPipelineDescriptor::bind_group_layout_descriptor`]
     fn bind_group(&self) -> &BindGroup;
 }
 
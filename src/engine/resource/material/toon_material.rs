@@ -0,0 +1,221 @@
+use std::{mem::size_of, num::NonZeroU64, path::Path};
+
+use wgpu::{
+    BindGroup, BindGroupDescriptor, BindGroupEntry, BindGroupLayout, BindGroupLayoutDescriptor,
+    BindGroupLayoutEntry, BindingResource, BindingType, Buffer, BufferBindingType, BufferUsages,
+    SamplerBindingType, ShaderStages, TextureSampleType, TextureViewDimension,
+};
+
+use crate::engine::{
+    BufferHelper, DiffuseTexture, EngineResult, LogicalDevice, ResourceManager, TTexture,
+};
+
+use super::{AlphaMode, MaterialPipelineDescriptor, TMaterial, UToonMaterial};
+
+/// Cel shading: lighting in flat bands, a hard specular highlight and a rim light
+pub struct ToonMaterial {
+    name: String,
+    texture: DiffuseTexture,
+    color: [f32; 4],
+    band_count: f32,
+    shininess: f32,
+    rim_strength: f32,
+    alpha_mode: AlphaMode,
+    buffer: Buffer,
+    bind_group: BindGroup,
+}
+
+impl ToonMaterial {
+    pub const DEFAULT_BAND_COUNT: f32 = 3.0;
+    pub const DEFAULT_SHININESS: f32 = 32.0;
+    pub const DEFAULT_RIM_STRENGTH: f32 = 0.3;
+
+    pub const BIND_GROUP_LAYOUT_DESCRIPTOR: BindGroupLayoutDescriptor<'static> =
+        BindGroupLayoutDescriptor {
+            label: Some("Toon Material"),
+            entries: &[
+                BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Texture {
+                        sample_type: TextureSampleType::Float { filterable: true },
+                        view_dimension: TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Sampler(SamplerBindingType::Filtering),
+                    count: None,
+                },
+                BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Buffer {
+                        ty: BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: NonZeroU64::new(size_of::<UToonMaterial>() as u64),
+                    },
+                    count: None,
+                },
+            ],
+        };
+
+    pub fn from_path<P>(logical_device: &LogicalDevice, path: P) -> EngineResult<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let texture = ResourceManager::diffuse_texture_from_path(logical_device, path)?;
+
+        Self::from_texture(logical_device, texture)
+    }
+
+    /// A single color without texture
+    pub fn from_color(logical_device: &LogicalDevice, color: [f32; 4]) -> EngineResult<Self> {
+        let texture =
+            DiffuseTexture::from_color(logical_device, [255; 4], None, Some("Toon White"))?;
+
+        Ok(Self::from_texture(logical_device, texture)?.with_color(logical_device, color))
+    }
+
+    pub fn from_texture(
+        logical_device: &LogicalDevice,
+        texture: DiffuseTexture,
+    ) -> EngineResult<Self> {
+        let color = [1.0; 4];
+
+        let buffer = logical_device.make_buffer(
+            Some("ToonMaterialBuffer"),
+            &[UToonMaterial::new(
+                color,
+                Self::DEFAULT_BAND_COUNT,
+                Self::DEFAULT_SHININESS,
+                Self::DEFAULT_RIM_STRENGTH,
+                AlphaMode::default().alpha_cutoff(),
+            )],
+            BufferUsages::UNIFORM | BufferUsages::COPY_DST,
+        );
+
+        let bind_group = logical_device
+            .device()
+            .create_bind_group(&BindGroupDescriptor {
+                label: Some("ToonMaterialBindGroup"),
+                layout: &Self::bind_group_layout(logical_device),
+                entries: &[
+                    BindGroupEntry {
+                        binding: 0,
+                        resource: BindingResource::TextureView(texture.view()),
+                    },
+                    BindGroupEntry {
+                        binding: 1,
+                        resource: BindingResource::Sampler(texture.sampler()),
+                    },
+                    BindGroupEntry {
+                        binding: 2,
+                        resource: buffer.as_entire_binding(),
+                    },
+                ],
+            });
+
+        Ok(Self {
+            name: String::from("ToonMaterial"),
+            texture,
+            color,
+            band_count: Self::DEFAULT_BAND_COUNT,
+            shininess: Self::DEFAULT_SHININESS,
+            rim_strength: Self::DEFAULT_RIM_STRENGTH,
+            alpha_mode: AlphaMode::default(),
+            buffer,
+            bind_group,
+        })
+    }
+
+    /// Sets the color the texture gets multiplied with and uploads it
+    pub fn with_color(mut self, logical_device: &LogicalDevice, color: [f32; 4]) -> Self {
+        self.color = color;
+        self.update_buffer(logical_device);
+        self
+    }
+
+    /// Sets the amount of diffuse bands, the specular exponent
+    /// (0.0 = no highlight) and the rim light strength and uploads them
+    pub fn with_toon_shading(
+        mut self,
+        logical_device: &LogicalDevice,
+        band_count: f32,
+        shininess: f32,
+        rim_strength: f32,
+    ) -> Self {
+        self.band_count = band_count;
+        self.shininess = shininess;
+        self.rim_strength = rim_strength;
+        self.update_buffer(logical_device);
+        self
+    }
+
+    pub fn to_uniform(&self) -> UToonMaterial {
+        UToonMaterial::new(
+            self.color,
+            self.band_count,
+            self.shininess,
+            self.rim_strength,
+            self.alpha_mode.alpha_cutoff(),
+        )
+    }
+
+    pub fn bind_group_layout(logical_device: &LogicalDevice) -> BindGroupLayout {
+        logical_device
+            .device()
+            .create_bind_group_layout(&Self::BIND_GROUP_LAYOUT_DESCRIPTOR)
+    }
+
+    pub fn texture(&self) -> &DiffuseTexture {
+        &self.texture
+    }
+
+    pub fn color(&self) -> [f32; 4] {
+        self.color
+    }
+
+    /// Requires [`TMaterial::update_buffer`] to be called afterwards
+    pub fn set_color(&mut self, color: [f32; 4]) {
+        self.color = color;
+    }
+}
+
+impl TMaterial for ToonMaterial {
+    fn update_buffer(&self, logical_device: &LogicalDevice) {
+        logical_device.queue().write_buffer(
+            &self.buffer,
+            0,
+            bytemuck::cast_slice(&[self.to_uniform()]),
+        )
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn alpha_mode(&self) -> AlphaMode {
+        self.alpha_mode
+    }
+
+    fn set_alpha_mode(&mut self, alpha_mode: AlphaMode) {
+        self.alpha_mode = alpha_mode;
+    }
+
+    fn pipeline_descriptor(&self) -> MaterialPipelineDescriptor {
+        MaterialPipelineDescriptor::new(
+            "ToonMaterial",
+            "toon.wgsl",
+            &Self::BIND_GROUP_LAYOUT_DESCRIPTOR,
+        )
+        .with_alpha_mode(self.alpha_mode)
+    }
+
+    fn bind_group(&self) -> &BindGroup {
+        &self.bind_group
+    }
+}
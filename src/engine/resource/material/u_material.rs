@@ -0,0 +1,33 @@
+use bytemuck::{Pod, Zeroable};
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Pod, Zeroable)]
+pub struct UMaterial {
+    /// Multiplier for the specular highlights
+    /// 0.0 = no highlights
+    specular_strength: f32,
+
+    /// Exponent of the specular highlights.
+    /// The higher, the smaller and sharper the highlights.
+    shininess: f32,
+
+    /// Fragments with a lower diffuse alpha get discarded.
+    /// 0.0 = nothing gets discarded
+    alpha_cutoff: f32,
+
+    /// Padding
+    ///
+    /// Uniform buffers must be a multiple of 16 bytes in size.
+    _padding: f32,
+}
+
+impl UMaterial {
+    pub fn new(specular_strength: f32, shininess: f32, alpha_cutoff: f32) -> Self {
+        Self {
+            specular_strength,
+            shininess,
+            alpha_cutoff,
+            _padding: 0.0,
+        }
+    }
+}
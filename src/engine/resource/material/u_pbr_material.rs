@@ -0,0 +1,49 @@
+use bytemuck::{Pod, Zeroable};
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Pod, Zeroable)]
+pub struct UPbrMaterial {
+    /// Multiplied with the base color texture
+    base_color_factor: [f32; 4],
+
+    /// Multiplied with the emissive texture
+    emissive_factor: [f32; 3],
+
+    /// Multiplied with the metallic channel (B) of the metallic-roughness texture
+    metallic_factor: f32,
+
+    /// Multiplied with the roughness channel (G) of the metallic-roughness texture
+    roughness_factor: f32,
+
+    /// Scales the X & Y of the normal map
+    normal_scale: f32,
+
+    /// 0.0 = no occlusion, 1.0 = full occlusion of the occlusion texture
+    occlusion_strength: f32,
+
+    /// Fragments with a lower base color alpha get discarded.
+    /// 0.0 = nothing gets discarded
+    alpha_cutoff: f32,
+}
+
+impl UPbrMaterial {
+    pub fn new(
+        base_color_factor: [f32; 4],
+        emissive_factor: [f32; 3],
+        metallic_factor: f32,
+        roughness_factor: f32,
+        normal_scale: f32,
+        occlusion_strength: f32,
+        alpha_cutoff: f32,
+    ) -> Self {
+        Self {
+            base_color_factor,
+            emissive_factor,
+            metallic_factor,
+            roughness_factor,
+            normal_scale,
+            occlusion_strength,
+            alpha_cutoff,
+        }
+    }
+}
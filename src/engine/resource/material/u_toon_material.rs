@@ -0,0 +1,40 @@
+use bytemuck::{Pod, Zeroable};
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Pod, Zeroable)]
+pub struct UToonMaterial {
+    /// Multiplied with the color texture
+    color: [f32; 4],
+
+    /// Amount of flat brightness steps of the diffuse lighting
+    band_count: f32,
+
+    /// Exponent of the specular highlight.
+    /// 0.0 = no highlight
+    shininess: f32,
+
+    /// Brightness of the rim light along the silhouette
+    rim_strength: f32,
+
+    /// Fragments with a lower alpha get discarded.
+    /// 0.0 = nothing gets discarded
+    alpha_cutoff: f32,
+}
+
+impl UToonMaterial {
+    pub fn new(
+        color: [f32; 4],
+        band_count: f32,
+        shininess: f32,
+        rim_strength: f32,
+        alpha_cutoff: f32,
+    ) -> Self {
+        Self {
+            color,
+            band_count,
+            shininess,
+            rim_strength,
+            alpha_cutoff,
+        }
+    }
+}
@@ -0,0 +1,27 @@
+use bytemuck::{Pod, Zeroable};
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Pod, Zeroable)]
+pub struct UUnlitMaterial {
+    /// Multiplied with the color texture
+    color: [f32; 4],
+
+    /// Fragments with a lower alpha get discarded.
+    /// 0.0 = nothing gets discarded
+    alpha_cutoff: f32,
+
+    /// Padding
+    ///
+    /// Uniform buffers must be a multiple of 16 bytes in size.
+    _padding: [f32; 3],
+}
+
+impl UUnlitMaterial {
+    pub fn new(color: [f32; 4], alpha_cutoff: f32) -> Self {
+        Self {
+            color,
+            alpha_cutoff,
+            _padding: [0.0; 3],
+        }
+    }
+}
@@ -0,0 +1,23 @@
+use bytemuck::{Pod, Zeroable};
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Pod, Zeroable)]
+pub struct UVertexColorMaterial {
+    /// Fragments with a lower vertex alpha get discarded.
+    /// 0.0 = nothing gets discarded
+    alpha_cutoff: f32,
+
+    /// Padding
+    ///
+    /// Uniform buffers must be a multiple of 16 bytes in size.
+    _padding: [f32; 3],
+}
+
+impl UVertexColorMaterial {
+    pub fn new(alpha_cutoff: f32) -> Self {
+        Self {
+            alpha_cutoff,
+            _padding: [0.0; 3],
+        }
+    }
+}
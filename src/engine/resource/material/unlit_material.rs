@@ -0,0 +1,185 @@
+use std::{mem::size_of, num::NonZeroU64, path::Path};
+
+use wgpu::{
+    BindGroup, BindGroupDescriptor, BindGroupEntry, BindGroupLayout, BindGroupLayoutDescriptor,
+    BindGroupLayoutEntry, BindingResource, BindingType, Buffer, BufferBindingType, BufferUsages,
+    SamplerBindingType, ShaderStages, TextureSampleType, TextureViewDimension,
+};
+
+use crate::engine::{
+    BufferHelper, DiffuseTexture, EngineResult, LogicalDevice, ResourceManager, TTexture,
+};
+
+use super::{AlphaMode, MaterialPipelineDescriptor, TMaterial, UUnlitMaterial};
+
+/// Shows its texture & color as they are, unaffected by any light
+pub struct UnlitMaterial {
+    name: String,
+    texture: DiffuseTexture,
+    color: [f32; 4],
+    alpha_mode: AlphaMode,
+    buffer: Buffer,
+    bind_group: BindGroup,
+}
+
+impl UnlitMaterial {
+    pub const BIND_GROUP_LAYOUT_DESCRIPTOR: BindGroupLayoutDescriptor<'static> =
+        BindGroupLayoutDescriptor {
+            label: Some("Unlit Material"),
+            entries: &[
+                BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Texture {
+                        sample_type: TextureSampleType::Float { filterable: true },
+                        view_dimension: TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Sampler(SamplerBindingType::Filtering),
+                    count: None,
+                },
+                BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Buffer {
+                        ty: BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: NonZeroU64::new(size_of::<UUnlitMaterial>() as u64),
+                    },
+                    count: None,
+                },
+            ],
+        };
+
+    pub fn from_path<P>(logical_device: &LogicalDevice, path: P) -> EngineResult<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let texture = ResourceManager::diffuse_texture_from_path(logical_device, path)?;
+
+        Self::from_texture(logical_device, texture)
+    }
+
+    /// A single color without texture
+    pub fn from_color(logical_device: &LogicalDevice, color: [f32; 4]) -> EngineResult<Self> {
+        let texture =
+            DiffuseTexture::from_color(logical_device, [255; 4], None, Some("Unlit White"))?;
+
+        Ok(Self::from_texture(logical_device, texture)?.with_color(logical_device, color))
+    }
+
+    pub fn from_texture(
+        logical_device: &LogicalDevice,
+        texture: DiffuseTexture,
+    ) -> EngineResult<Self> {
+        let color = [1.0; 4];
+
+        let buffer = logical_device.make_buffer(
+            Some("UnlitMaterialBuffer"),
+            &[UUnlitMaterial::new(
+                color,
+                AlphaMode::default().alpha_cutoff(),
+            )],
+            BufferUsages::UNIFORM | BufferUsages::COPY_DST,
+        );
+
+        let bind_group = logical_device
+            .device()
+            .create_bind_group(&BindGroupDescriptor {
+                label: Some("UnlitMaterialBindGroup"),
+                layout: &Self::bind_group_layout(logical_device),
+                entries: &[
+                    BindGroupEntry {
+                        binding: 0,
+                        resource: BindingResource::TextureView(texture.view()),
+                    },
+                    BindGroupEntry {
+                        binding: 1,
+                        resource: BindingResource::Sampler(texture.sampler()),
+                    },
+                    BindGroupEntry {
+                        binding: 2,
+                        resource: buffer.as_entire_binding(),
+                    },
+                ],
+            });
+
+        Ok(Self {
+            name: String::from("UnlitMaterial"),
+            texture,
+            color,
+            alpha_mode: AlphaMode::default(),
+            buffer,
+            bind_group,
+        })
+    }
+
+    /// Sets the color the texture gets multiplied with and uploads it
+    pub fn with_color(mut self, logical_device: &LogicalDevice, color: [f32; 4]) -> Self {
+        self.color = color;
+        self.update_buffer(logical_device);
+        self
+    }
+
+    pub fn bind_group_layout(logical_device: &LogicalDevice) -> BindGroupLayout {
+        logical_device
+            .device()
+            .create_bind_group_layout(&Self::BIND_GROUP_LAYOUT_DESCRIPTOR)
+    }
+
+    pub fn texture(&self) -> &DiffuseTexture {
+        &self.texture
+    }
+
+    pub fn color(&self) -> [f32; 4] {
+        self.color
+    }
+
+    /// Requires [`TMaterial::update_buffer`] to be called afterwards
+    pub fn set_color(&mut self, color: [f32; 4]) {
+        self.color = color;
+    }
+}
+
+impl TMaterial for UnlitMaterial {
+    fn update_buffer(&self, logical_device: &LogicalDevice) {
+        logical_device.queue().write_buffer(
+            &self.buffer,
+            0,
+            bytemuck::cast_slice(&[UUnlitMaterial::new(
+                self.color,
+                self.alpha_mode.alpha_cutoff(),
+            )]),
+        )
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn alpha_mode(&self) -> AlphaMode {
+        self.alpha_mode
+    }
+
+    fn set_alpha_mode(&mut self, alpha_mode: AlphaMode) {
+        self.alpha_mode = alpha_mode;
+    }
+
+    fn pipeline_descriptor(&self) -> MaterialPipelineDescriptor {
+        MaterialPipelineDescriptor::new(
+            "UnlitMaterial",
+            "unlit.wgsl",
+            &Self::BIND_GROUP_LAYOUT_DESCRIPTOR,
+        )
+        .with_alpha_mode(self.alpha_mode)
+    }
+
+    fn bind_group(&self) -> &BindGroup {
+        &self.bind_group
+    }
+}
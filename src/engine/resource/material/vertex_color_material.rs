@@ -0,0 +1,106 @@
+use std::{mem::size_of, num::NonZeroU64};
+
+use wgpu::{
+    BindGroup, BindGroupDescriptor, BindGroupEntry, BindGroupLayout, BindGroupLayoutDescriptor,
+    BindGroupLayoutEntry, BindingType, Buffer, BufferBindingType, BufferUsages, ShaderStages,
+};
+
+use crate::engine::{BufferHelper, LogicalDevice};
+
+use super::{AlphaMode, MaterialPipelineDescriptor, TMaterial, UVertexColorMaterial};
+
+/// Shows the colors of the vertices, multiplied by the instance tint.
+/// Unaffected by any light.
+pub struct VertexColorMaterial {
+    name: String,
+    alpha_mode: AlphaMode,
+    buffer: Buffer,
+    bind_group: BindGroup,
+}
+
+impl VertexColorMaterial {
+    /// Only the alpha cutoff, the colors come from the vertices
+    pub const BIND_GROUP_LAYOUT_DESCRIPTOR: BindGroupLayoutDescriptor<'static> =
+        BindGroupLayoutDescriptor {
+            label: Some("Vertex Color Material"),
+            entries: &[BindGroupLayoutEntry {
+                binding: 0,
+                visibility: ShaderStages::FRAGMENT,
+                ty: BindingType::Buffer {
+                    ty: BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: NonZeroU64::new(size_of::<UVertexColorMaterial>() as u64),
+                },
+                count: None,
+            }],
+        };
+
+    pub fn new(logical_device: &LogicalDevice) -> Self {
+        let buffer = logical_device.make_buffer(
+            Some("VertexColorMaterialBuffer"),
+            &[UVertexColorMaterial::new(
+                AlphaMode::default().alpha_cutoff(),
+            )],
+            BufferUsages::UNIFORM | BufferUsages::COPY_DST,
+        );
+
+        let bind_group = logical_device
+            .device()
+            .create_bind_group(&BindGroupDescriptor {
+                label: Some("VertexColorMaterialBindGroup"),
+                layout: &Self::bind_group_layout(logical_device),
+                entries: &[BindGroupEntry {
+                    binding: 0,
+                    resource: buffer.as_entire_binding(),
+                }],
+            });
+
+        Self {
+            name: String::from("VertexColorMaterial"),
+            alpha_mode: AlphaMode::default(),
+            buffer,
+            bind_group,
+        }
+    }
+
+    pub fn bind_group_layout(logical_device: &LogicalDevice) -> BindGroupLayout {
+        logical_device
+            .device()
+            .create_bind_group_layout(&Self::BIND_GROUP_LAYOUT_DESCRIPTOR)
+    }
+}
+
+impl TMaterial for VertexColorMaterial {
+    fn update_buffer(&self, logical_device: &LogicalDevice) {
+        logical_device.queue().write_buffer(
+            &self.buffer,
+            0,
+            bytemuck::cast_slice(&[UVertexColorMaterial::new(self.alpha_mode.alpha_cutoff())]),
+        )
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn alpha_mode(&self) -> AlphaMode {
+        self.alpha_mode
+    }
+
+    fn set_alpha_mode(&mut self, alpha_mode: AlphaMode) {
+        self.alpha_mode = alpha_mode;
+    }
+
+    fn pipeline_descriptor(&self) -> MaterialPipelineDescriptor {
+        MaterialPipelineDescriptor::new(
+            "VertexColorMaterial",
+            "vertex_color.wgsl",
+            &Self::BIND_GROUP_LAYOUT_DESCRIPTOR,
+        )
+        .with_alpha_mode(self.alpha_mode)
+    }
+
+    fn bind_group(&self) -> &BindGroup {
+        &self.bind_group
+    }
+}
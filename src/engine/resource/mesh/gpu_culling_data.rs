@@ -0,0 +1,191 @@
+use std::{mem::size_of, num::NonZeroU64};
+
+use wgpu::{
+    util::DrawIndexedIndirect, BindGroup, BindGroupDescriptor, BindGroupEntry, BindGroupLayout,
+    BindGroupLayoutDescriptor, BindGroupLayoutEntry, BindingType, Buffer, BufferBindingType,
+    BufferUsages, ShaderStages,
+};
+
+use crate::engine::{BoundingSphere, BufferHelper, LogicalDevice};
+
+use super::UMeshCulling;
+
+/// Per-mesh resources used by the [`InstanceCuller`](crate::engine::InstanceCuller).
+///
+/// The culling compute pass reads all instances, writes the visible ones
+/// compacted into the visible instance buffer of the mesh and counts them
+/// in the indirect buffer, which is then used for an indirect draw call.
+#[derive(Debug)]
+pub struct GpuCullingData {
+    index_count: u32,
+    instance_count: u32,
+    uniform_buffer: Buffer,
+    indirect_buffer: Buffer,
+    bind_group: BindGroup,
+}
+
+impl GpuCullingData {
+    pub const BIND_GROUP_LAYOUT_DESCRIPTOR: BindGroupLayoutDescriptor<'static> =
+        BindGroupLayoutDescriptor {
+            label: Some("GPU Culling Data Bind Group Layout"),
+            entries: &[
+                BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: ShaderStages::COMPUTE,
+                    ty: BindingType::Buffer {
+                        ty: BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: NonZeroU64::new(size_of::<UMeshCulling>() as u64),
+                    },
+                    count: None,
+                },
+                BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: ShaderStages::COMPUTE,
+                    ty: BindingType::Buffer {
+                        ty: BufferBindingType::Storage { read_only: true },
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: ShaderStages::COMPUTE,
+                    ty: BindingType::Buffer {
+                        ty: BufferBindingType::Storage { read_only: false },
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                BindGroupLayoutEntry {
+                    binding: 3,
+                    visibility: ShaderStages::COMPUTE,
+                    ty: BindingType::Buffer {
+                        ty: BufferBindingType::Storage { read_only: false },
+                        has_dynamic_offset: false,
+                        min_binding_size: NonZeroU64::new(size_of::<DrawIndexedIndirect>() as u64),
+                    },
+                    count: None,
+                },
+            ],
+        };
+
+    pub fn new(
+        logical_device: &LogicalDevice,
+        label: &str,
+        bounding_sphere: &BoundingSphere,
+        index_count: u32,
+        instance_count: u32,
+        instance_buffer: &Buffer,
+        visible_instance_buffer: &Buffer,
+    ) -> Self {
+        let uniform_buffer = logical_device.make_buffer(
+            Some(&format!("{} GPU Culling Buffer", label)),
+            &[UMeshCulling::new(bounding_sphere, instance_count)],
+            BufferUsages::UNIFORM | BufferUsages::COPY_DST,
+        );
+
+        let indirect_buffer = logical_device.make_buffer(
+            Some(&format!("{} Indirect Buffer", label)),
+            Self::draw_arguments(index_count).as_bytes(),
+            BufferUsages::INDIRECT | BufferUsages::STORAGE | BufferUsages::COPY_DST,
+        );
+
+        let bind_group_layout = Self::bind_group_layout(logical_device);
+        let bind_group = logical_device
+            .device()
+            .create_bind_group(&BindGroupDescriptor {
+                label: Some(&format!("{} GPU Culling Bind Group", label)),
+                layout: &bind_group_layout,
+                entries: &[
+                    BindGroupEntry {
+                        binding: 0,
+                        resource: uniform_buffer.as_entire_binding(),
+                    },
+                    BindGroupEntry {
+                        binding: 1,
+                        resource: instance_buffer.as_entire_binding(),
+                    },
+                    BindGroupEntry {
+                        binding: 2,
+                        resource: visible_instance_buffer.as_entire_binding(),
+                    },
+                    BindGroupEntry {
+                        binding: 3,
+                        resource: indirect_buffer.as_entire_binding(),
+                    },
+                ],
+            });
+
+        Self {
+            index_count,
+            instance_count,
+            uniform_buffer,
+            indirect_buffer,
+            bind_group,
+        }
+    }
+
+    fn draw_arguments(index_count: u32) -> DrawIndexedIndirect {
+        DrawIndexedIndirect {
+            vertex_count: index_count,
+            // Counted up by the culling pass
+            instance_count: 0,
+            base_index: 0,
+            vertex_offset: 0,
+            base_instance: 0,
+        }
+    }
+
+    /// Updates the amount of instances to test.
+    /// If the instance buffers got replaced, a new [`GpuCullingData`] must
+    /// be created instead, as the bind group still points to the old ones.
+    pub fn update(
+        &mut self,
+        logical_device: &LogicalDevice,
+        bounding_sphere: &BoundingSphere,
+        instance_count: u32,
+    ) {
+        self.instance_count = instance_count;
+
+        logical_device.queue().write_buffer(
+            &self.uniform_buffer,
+            0,
+            bytemuck::cast_slice(&[UMeshCulling::new(bounding_sphere, instance_count)]),
+        );
+    }
+
+    /// Resets the visible instance counter.
+    /// Must be called before each culling pass.
+    pub fn reset(&self, logical_device: &LogicalDevice) {
+        logical_device.queue().write_buffer(
+            &self.indirect_buffer,
+            0,
+            Self::draw_arguments(self.index_count).as_bytes(),
+        );
+    }
+
+    pub fn bind_group_layout(logical_device: &LogicalDevice) -> BindGroupLayout {
+        logical_device
+            .device()
+            .create_bind_group_layout(&Self::BIND_GROUP_LAYOUT_DESCRIPTOR)
+    }
+
+    pub fn instance_count(&self) -> u32 {
+        self.instance_count
+    }
+
+    pub fn uniform_buffer(&self) -> &Buffer {
+        &self.uniform_buffer
+    }
+
+    pub fn indirect_buffer(&self) -> &Buffer {
+        &self.indirect_buffer
+    }
+
+    pub fn bind_group(&self) -> &BindGroup {
+        &self.bind_group
+    }
+}
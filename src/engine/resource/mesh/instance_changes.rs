@@ -0,0 +1,68 @@
+use std::{collections::BTreeSet, ops::Range};
+
+/// Tracks which instances of a mesh changed since they were last uploaded.
+#[derive(Debug, Default)]
+pub struct InstanceChanges {
+    all: bool,
+    indices: BTreeSet<usize>,
+}
+
+impl InstanceChanges {
+    /// If more than `1 / FULL_UPLOAD_DIVISOR` of all instances changed,
+    /// everything gets uploaded at once instead of many small writes.
+    pub const FULL_UPLOAD_DIVISOR: usize = 4;
+
+    pub fn new() -> Self {
+        Self {
+            all: false,
+            indices: BTreeSet::new(),
+        }
+    }
+
+    pub fn mark(&mut self, index: usize) {
+        if !self.all {
+            self.indices.insert(index);
+        }
+    }
+
+    pub fn mark_range(&mut self, range: Range<usize>) {
+        if !self.all {
+            self.indices.extend(range);
+        }
+    }
+
+    pub fn mark_all(&mut self) {
+        self.all = true;
+        self.indices.clear();
+    }
+
+    pub fn has_changes(&self) -> bool {
+        self.all || !self.indices.is_empty()
+    }
+
+    /// Returns the changed instances as coalesced ranges and resets the
+    /// tracker.
+    /// Indices at or beyond `instance_count` (i.e. removed instances)
+    /// are dropped.
+    pub fn take_ranges(&mut self, instance_count: usize) -> Vec<Range<usize>> {
+        let all = self.all || self.indices.len() * Self::FULL_UPLOAD_DIVISOR > instance_count;
+        let indices = std::mem::take(&mut self.indices);
+        self.all = false;
+
+        if all {
+            return (instance_count > 0)
+                .then_some(0..instance_count)
+                .into_iter()
+                .collect();
+        }
+
+        let mut ranges: Vec<Range<usize>> = vec![];
+        for index in indices.into_iter().filter(|x| *x < instance_count) {
+            match ranges.last_mut() {
+                Some(range) if range.end == index => range.end += 1,
+                _ => ranges.push(index..index + 1),
+            }
+        }
+        ranges
+    }
+}
@@ -1,9 +1,9 @@
-use crate::engine::StandardMaterial;
+use crate::engine::TMaterial;
 
 pub enum MaterialLoading {
     Ignore,
     Try,
-    Replace(StandardMaterial),
+    Replace(Box<dyn TMaterial>),
 }
 
 impl PartialEq for MaterialLoading {
@@ -6,3 +6,12 @@ pub use standard_mesh::*;
 
 mod material_loading;
 pub use material_loading::*;
+
+mod u_mesh_culling;
+pub use u_mesh_culling::*;
+
+mod gpu_culling_data;
+pub use gpu_culling_data::*;
+
+mod instance_changes;
+pub use instance_changes::*;
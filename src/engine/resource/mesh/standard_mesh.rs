@@ -1,11 +1,13 @@
-use std::path::Path;
+use std::{mem::size_of, path::Path};
 
-use cgmath::{Quaternion, Vector3, Zero};
-use wgpu::{Buffer, BufferUsages};
+use bytemuck::Zeroable;
+use cgmath::{EuclideanSpace, InnerSpace, Matrix4, Point3, Quaternion, Vector3, Zero};
+use wgpu::{Buffer, BufferAddress, BufferDescriptor, BufferUsages};
 
 use crate::engine::{
-    BufferHelper, EngineError, EngineResult, LogicalDevice, ResourceManager, StandardInstance,
-    StandardMaterial, TInstance, TMaterial, TMesh, ToStandardMesh, VertexPoint,
+    AlphaMode, BoundingBox, BoundingSphere, BufferHelper, EngineError, EngineResult, Frustum,
+    GpuCullingData, InstanceChanges, InstanceUniform, LogicalDevice, ResourceManager,
+    StandardInstance, StandardMaterial, TInstance, TMaterial, TMesh, ToStandardMesh, VertexPoint,
 };
 
 use super::MaterialLoading;
@@ -16,14 +18,26 @@ pub struct StandardMesh {
     vertex_buffer: Buffer,
     index_buffer: Buffer,
     index_count: u32,
+    bounding_box: BoundingBox,
+    world_bounding_box: BoundingBox,
     instances: Vec<StandardInstance>,
+    instance_uniforms: Vec<InstanceUniform>,
+    instance_bounding_spheres: Vec<BoundingSphere>,
+    instance_changes: InstanceChanges,
+    instance_capacity: usize,
     instance_buffer: Buffer,
+    visible_instance_buffer: Buffer,
+    gpu_culling_data: Option<GpuCullingData>,
     material: Box<dyn TMaterial>,
 }
 
 impl StandardMesh {
     pub const MISSING_TEXTURE: &str = "missing_texture.png";
 
+    /// Meshes with at least this many instances get culled on the GPU
+    /// and drawn indirectly, instead of being culled on the CPU.
+    pub const GPU_CULLING_INSTANCE_THRESHOLD: usize = 4096;
+
     pub fn from_gltf_single<P>(
         logical_device: &LogicalDevice,
         path: P,
@@ -79,11 +93,21 @@ impl StandardMesh {
             );
         }
 
-        scene
-            .models
-            .first()
-            .unwrap()
-            .to_instanced_mesh(logical_device, material_loading, instances)
+        let alpha_mode = if material_loading == MaterialLoading::Try {
+            ResourceManager::read_resource_gltf_alpha_modes(&path)?
+                .first()
+                .and_then(|x| x.first().copied())
+                .unwrap_or_default()
+        } else {
+            AlphaMode::default()
+        };
+
+        scene.models.first().unwrap().to_instanced_mesh(
+            logical_device,
+            material_loading,
+            alpha_mode,
+            instances,
+        )
     }
 
     pub fn from_raw_single(
@@ -130,14 +154,33 @@ impl StandardMesh {
             BufferUsages::INDEX,
         );
 
-        let instance_uniform = instances
+        let bounding_box = BoundingBox::from_vertices(&vertices);
+
+        let instance_uniforms = instances
             .iter()
             .map(|x| x.to_instance_uniform())
             .collect::<Vec<_>>();
-        let instance_buffer = logical_device.make_buffer(
-            Some(&format!("{} Instance Buffer", label)),
-            &instance_uniform,
-            BufferUsages::VERTEX,
+
+        let instance_capacity = instances.len().max(1);
+        let (instance_buffer, visible_instance_buffer) =
+            Self::make_instance_buffers(logical_device, label, instance_capacity);
+        logical_device.queue().write_buffer(
+            &instance_buffer,
+            0,
+            bytemuck::cast_slice(&instance_uniforms),
+        );
+
+        let (instance_bounding_spheres, world_bounding_box) =
+            Self::calculate_instance_bounds(&bounding_box, &instance_uniforms);
+
+        let gpu_culling_data = Self::make_gpu_culling_data(
+            logical_device,
+            label,
+            &bounding_box,
+            indices.len() as u32,
+            instances.len(),
+            &instance_buffer,
+            &visible_instance_buffer,
         );
 
         let material = match material {
@@ -154,15 +197,123 @@ impl StandardMesh {
             vertex_buffer,
             index_buffer,
             index_count: indices.len() as u32,
+            bounding_box,
+            world_bounding_box,
             instances,
+            instance_uniforms,
+            instance_bounding_spheres,
+            instance_changes: InstanceChanges::new(),
+            instance_capacity,
             instance_buffer,
+            visible_instance_buffer,
+            gpu_culling_data,
             material,
         })
     }
 
+    /// Makes the instance buffer and the visible instance buffer, both
+    /// big enough to hold `instance_capacity` instances.
+    fn make_instance_buffers(
+        logical_device: &LogicalDevice,
+        label: &str,
+        instance_capacity: usize,
+    ) -> (Buffer, Buffer) {
+        let size = (instance_capacity * size_of::<InstanceUniform>()) as BufferAddress;
+
+        let instance_buffer = logical_device.device().create_buffer(&BufferDescriptor {
+            label: Some(&format!("{} Instance Buffer", label)),
+            size,
+            usage: BufferUsages::VERTEX | BufferUsages::STORAGE | BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        // Same size as the instance buffer, gets filled with the visible
+        // instances only after culling
+        let visible_instance_buffer = logical_device.device().create_buffer(&BufferDescriptor {
+            label: Some(&format!("{} Visible Instance Buffer", label)),
+            size,
+            usage: BufferUsages::VERTEX | BufferUsages::STORAGE | BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        (instance_buffer, visible_instance_buffer)
+    }
+
+    fn make_gpu_culling_data(
+        logical_device: &LogicalDevice,
+        label: &str,
+        bounding_box: &BoundingBox,
+        index_count: u32,
+        instance_count: usize,
+        instance_buffer: &Buffer,
+        visible_instance_buffer: &Buffer,
+    ) -> Option<GpuCullingData> {
+        if instance_count < Self::GPU_CULLING_INSTANCE_THRESHOLD {
+            return None;
+        }
+
+        Some(GpuCullingData::new(
+            logical_device,
+            label,
+            &BoundingSphere::from_bounding_box(bounding_box),
+            index_count,
+            instance_count as u32,
+            instance_buffer,
+            visible_instance_buffer,
+        ))
+    }
+
+    /// Calculates the world space bounding sphere of each instance and
+    /// a bounding box around all instances.
+    fn calculate_instance_bounds(
+        bounding_box: &BoundingBox,
+        instance_uniforms: &[InstanceUniform],
+    ) -> (Vec<BoundingSphere>, BoundingBox) {
+        let local_bounding_sphere = BoundingSphere::from_bounding_box(bounding_box);
+
+        let instance_bounding_spheres = instance_uniforms
+            .iter()
+            .map(|x| local_bounding_sphere.transformed(&Matrix4::from(x.model_space_matrix)))
+            .collect::<Vec<_>>();
+
+        let world_bounding_box = Self::calculate_world_bounding_box(&instance_bounding_spheres);
+
+        (instance_bounding_spheres, world_bounding_box)
+    }
+
+    fn calculate_world_bounding_box(instance_bounding_spheres: &[BoundingSphere]) -> BoundingBox {
+        instance_bounding_spheres
+            .iter()
+            .fold(BoundingBox::empty(), |world_bounding_box, x| {
+                world_bounding_box.extended_by_box(&x.to_bounding_box())
+            })
+    }
+
+    /// Uploads the instances into the visible instance buffer,
+    /// returns their amount
+    fn write_visible_instances(
+        &self,
+        logical_device: &LogicalDevice,
+        visible_instance_uniforms: &[InstanceUniform],
+    ) -> u32 {
+        if !visible_instance_uniforms.is_empty() {
+            logical_device.queue().write_buffer(
+                &self.visible_instance_buffer,
+                0,
+                bytemuck::cast_slice(visible_instance_uniforms),
+            );
+        }
+
+        visible_instance_uniforms.len() as u32
+    }
+
     pub fn set_material(&mut self, material: Box<dyn TMaterial>) {
         self.material = material;
     }
+
+    pub fn material_mut(&mut self) -> &mut dyn TMaterial {
+        self.material.as_mut()
+    }
 }
 
 impl TMesh for StandardMesh {
@@ -178,10 +329,103 @@ impl TMesh for StandardMesh {
         self.index_count
     }
 
-    fn instances(&mut self) -> &mut Vec<StandardInstance> {
+    fn instances(&self) -> &[StandardInstance] {
+        &self.instances
+    }
+
+    fn instances_mut(&mut self) -> &mut Vec<StandardInstance> {
+        self.instance_changes.mark_all();
         &mut self.instances
     }
 
+    fn instance_mut(&mut self, index: usize) -> Option<&mut StandardInstance> {
+        self.instance_changes.mark(index);
+        self.instances.get_mut(index)
+    }
+
+    fn add_instance(&mut self, instance: StandardInstance) {
+        self.instance_changes.mark(self.instances.len());
+        self.instances.push(instance);
+    }
+
+    fn remove_instance(&mut self, index: usize) -> StandardInstance {
+        // The last instance takes the place of the removed one
+        self.instance_changes.mark(index);
+        self.instances.swap_remove(index)
+    }
+
+    fn update_instance_buffer(&mut self, logical_device: &LogicalDevice) {
+        if !self.instance_changes.has_changes() {
+            return;
+        }
+
+        let instance_count = self.instances.len();
+        let changed_ranges = self.instance_changes.take_ranges(instance_count);
+
+        // Recalculate changed uniforms and bounds
+        let local_bounding_sphere = BoundingSphere::from_bounding_box(&self.bounding_box);
+        self.instance_uniforms
+            .resize(instance_count, InstanceUniform::zeroed());
+        self.instance_bounding_spheres
+            .resize(instance_count, BoundingSphere::new(Vector3::zero(), 0.0));
+        for index in changed_ranges.iter().cloned().flatten() {
+            let instance_uniform = self.instances[index].to_instance_uniform();
+            self.instance_bounding_spheres[index] = local_bounding_sphere
+                .transformed(&Matrix4::from(instance_uniform.model_space_matrix));
+            self.instance_uniforms[index] = instance_uniform;
+        }
+        self.world_bounding_box =
+            Self::calculate_world_bounding_box(&self.instance_bounding_spheres);
+
+        let label = self.name.clone().unwrap_or(String::from("Unknown"));
+
+        if instance_count > self.instance_capacity {
+            // Grow, the old buffers are too small.
+            // Everything has to be re-uploaded.
+            self.instance_capacity = instance_count.next_power_of_two();
+            (self.instance_buffer, self.visible_instance_buffer) =
+                Self::make_instance_buffers(logical_device, &label, self.instance_capacity);
+            logical_device.queue().write_buffer(
+                &self.instance_buffer,
+                0,
+                bytemuck::cast_slice(&self.instance_uniforms),
+            );
+
+            // The old bind group references the old buffers
+            self.gpu_culling_data = None;
+        } else {
+            // Partial writes of the changed ranges only
+            for range in changed_ranges {
+                logical_device.queue().write_buffer(
+                    &self.instance_buffer,
+                    (range.start * size_of::<InstanceUniform>()) as BufferAddress,
+                    bytemuck::cast_slice(&self.instance_uniforms[range]),
+                );
+            }
+        }
+
+        match self.gpu_culling_data.as_mut() {
+            Some(gpu_culling_data) if instance_count >= Self::GPU_CULLING_INSTANCE_THRESHOLD => {
+                gpu_culling_data.update(
+                    logical_device,
+                    &local_bounding_sphere,
+                    instance_count as u32,
+                );
+            }
+            _ => {
+                self.gpu_culling_data = Self::make_gpu_culling_data(
+                    logical_device,
+                    &label,
+                    &self.bounding_box,
+                    self.index_count,
+                    instance_count,
+                    &self.instance_buffer,
+                    &self.visible_instance_buffer,
+                );
+            }
+        }
+    }
+
     fn instance_count(&self) -> u32 {
         self.instances.len() as u32
     }
@@ -190,6 +434,62 @@ impl TMesh for StandardMesh {
         &self.instance_buffer
     }
 
+    fn visible_instance_buffer(&self) -> &Buffer {
+        &self.visible_instance_buffer
+    }
+
+    fn gpu_culling_data(&self) -> Option<&GpuCullingData> {
+        self.gpu_culling_data.as_ref()
+    }
+
+    fn bounding_box(&self) -> &BoundingBox {
+        &self.bounding_box
+    }
+
+    fn world_bounding_box(&self) -> &BoundingBox {
+        &self.world_bounding_box
+    }
+
+    fn cull_instances(&self, logical_device: &LogicalDevice, frustum: &Frustum) -> u32 {
+        let visible_instance_uniforms = self
+            .instance_uniforms
+            .iter()
+            .zip(self.instance_bounding_spheres.iter())
+            .filter(|(_, bounding_sphere)| frustum.intersects_sphere(bounding_sphere))
+            .map(|(instance_uniform, _)| *instance_uniform)
+            .collect::<Vec<_>>();
+
+        self.write_visible_instances(logical_device, &visible_instance_uniforms)
+    }
+
+    fn cull_and_sort_instances(
+        &self,
+        logical_device: &LogicalDevice,
+        frustum: &Frustum,
+        view_position: Point3<f32>,
+    ) -> u32 {
+        let mut visible_instances = self
+            .instance_uniforms
+            .iter()
+            .zip(self.instance_bounding_spheres.iter())
+            .filter(|(_, bounding_sphere)| frustum.intersects_sphere(bounding_sphere))
+            .map(|(instance_uniform, bounding_sphere)| {
+                let distance = (bounding_sphere.center() - view_position.to_vec()).magnitude2();
+                (*instance_uniform, distance)
+            })
+            .collect::<Vec<_>>();
+
+        // Farthest first
+        visible_instances.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+
+        let visible_instance_uniforms = visible_instances
+            .into_iter()
+            .map(|(instance_uniform, _)| instance_uniform)
+            .collect::<Vec<_>>();
+
+        self.write_visible_instances(logical_device, &visible_instance_uniforms)
+    }
+
     fn material(&self) -> &dyn TMaterial {
         self.material.as_ref()
     }
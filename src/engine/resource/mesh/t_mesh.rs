@@ -1,14 +1,53 @@
+use cgmath::Point3;
 use wgpu::Buffer;
 
-use crate::engine::{StandardInstance, TMaterial};
+use crate::engine::{
+    BoundingBox, Frustum, GpuCullingData, LogicalDevice, StandardInstance, TMaterial,
+};
 
 pub trait TMesh {
     fn vertex_buffer(&self) -> &Buffer;
     fn index_buffer(&self) -> &Buffer;
     fn index_count(&self) -> u32;
-    fn instances(&mut self) -> &mut Vec<StandardInstance>;
+    fn instances(&self) -> &[StandardInstance];
+    /// Marks all instances as changed.
+    /// Prefer [`TMesh::instance_mut`] for changing only a few instances.
+    fn instances_mut(&mut self) -> &mut Vec<StandardInstance>;
+    /// Marks the instance as changed
+    fn instance_mut(&mut self, index: usize) -> Option<&mut StandardInstance>;
+    fn add_instance(&mut self, instance: StandardInstance);
+    /// Removes the instance by swapping in the last instance.
+    /// Thus, the order of instances is **not** preserved.
+    fn remove_instance(&mut self, index: usize) -> StandardInstance;
+    /// Uploads changed instances, growing the instance buffer if needed.
+    /// Gets called by the [`World`](crate::app::World) before rendering.
+    fn update_instance_buffer(&mut self, logical_device: &LogicalDevice);
     fn instance_count(&self) -> u32;
     fn instance_buffer(&self) -> &Buffer;
+    /// Buffer holding only the instances that passed the last
+    /// [`TMesh::cull_instances`] call
+    fn visible_instance_buffer(&self) -> &Buffer;
+    /// Resources for culling the instances on the GPU.
+    /// If present, the mesh gets culled by the
+    /// [`InstanceCuller`](crate::engine::InstanceCuller) and drawn indirectly.
+    fn gpu_culling_data(&self) -> Option<&GpuCullingData>;
+    /// Bounding box of the mesh in model space
+    fn bounding_box(&self) -> &BoundingBox;
+    /// Bounding box around all instances in world space
+    fn world_bounding_box(&self) -> &BoundingBox;
+    /// Tests every instance against the given frustum and uploads the
+    /// visible ones into [`TMesh::visible_instance_buffer`].
+    /// Returns the amount of visible instances.
+    fn cull_instances(&self, logical_device: &LogicalDevice, frustum: &Frustum) -> u32;
+    /// Like [`TMesh::cull_instances`], but uploads the visible instances
+    /// ordered back-to-front as seen from `view_position`.
+    /// Used for transparent meshes, which have to be blended in that order.
+    fn cull_and_sort_instances(
+        &self,
+        logical_device: &LogicalDevice,
+        frustum: &Frustum,
+        view_position: Point3<f32>,
+    ) -> u32;
     fn material(&self) -> &dyn TMaterial;
     fn name(&self) -> Option<String>;
 }
@@ -0,0 +1,40 @@
+use std::mem::size_of;
+
+use bytemuck::{Pod, Zeroable};
+
+use crate::engine::{BoundingSphere, InstanceUniform};
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Pod, Zeroable)]
+pub struct UMeshCulling {
+    /// Model space bounding sphere of the mesh.
+    /// `xyz` is the center, `w` the radius.
+    bounding_sphere: [f32; 4],
+
+    /// Amount of instances to test
+    instance_count: u32,
+
+    /// Amount of f32's a single [`InstanceUniform`] consists of
+    instance_stride: u32,
+
+    /// Padding
+    ///
+    /// Uniform structures are aligned to 16 bytes.
+    /// 16 (vec4) + 2 * 4 (u32) == 24 bytes, the next multiple of 16 is 32.
+    /// Thus, 8 bytes (2x u32) are needed as padding.
+    _padding: [u32; 2],
+}
+
+impl UMeshCulling {
+    pub fn new(bounding_sphere: &BoundingSphere, instance_count: u32) -> Self {
+        Self {
+            bounding_sphere: bounding_sphere
+                .center()
+                .extend(bounding_sphere.radius())
+                .into(),
+            instance_count,
+            instance_stride: (size_of::<InstanceUniform>() / size_of::<f32>()) as u32,
+            _padding: [0; 2],
+        }
+    }
+}
@@ -21,3 +21,18 @@ pub use light::*;
 
 mod helper;
 pub use helper::*;
+
+mod bounds;
+pub use bounds::*;
+
+mod skybox;
+pub use skybox::*;
+
+mod environment_map;
+pub use environment_map::*;
+
+mod shader;
+pub use shader::*;
+
+mod gpu_type;
+pub use gpu_type::*;
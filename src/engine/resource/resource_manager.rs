@@ -4,8 +4,9 @@ use std::{
 };
 
 use easy_gltf::Scene;
+use gltf::Gltf;
 
-use crate::engine::{EngineError, EngineResult};
+use crate::engine::{scene_alpha_modes, AlphaMode, EngineError, EngineResult};
 
 pub struct ResourceManager;
 
@@ -20,6 +21,12 @@ impl ResourceManager {
         }
     }
 
+    /// The resource folder of the project, which gets copied next to the build in debug builds
+    #[cfg(debug_assertions)]
+    pub fn resource_source_folder_path() -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join(Self::RESOURCE_FOLDER_NAME)
+    }
+
     pub fn resource_path<P>(file_name: P) -> EngineResult<PathBuf>
     where
         P: AsRef<Path>,
@@ -50,4 +57,18 @@ impl ResourceManager {
 
         easy_gltf::load(path).map_err(|e| EngineError::GltfBadMode(e))
     }
+
+    /// Alpha modes of the models of every scene, ordered like the models
+    /// of [`Self::read_resource_gltf`].
+    /// `easy_gltf` drops them while loading, so the document gets read again.
+    pub fn read_resource_gltf_alpha_modes<P>(file_name: P) -> EngineResult<Vec<Vec<AlphaMode>>>
+    where
+        P: AsRef<Path>,
+    {
+        let path = Self::resource_path(file_name)?;
+
+        let gltf = Gltf::open(path).map_err(|e| EngineError::GltfBadMode(Box::new(e)))?;
+
+        Ok(gltf.scenes().map(|x| scene_alpha_modes(&x)).collect())
+    }
 }
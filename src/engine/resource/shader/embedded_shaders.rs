@@ -0,0 +1,108 @@
+#[cfg(debug_assertions)]
+use std::path::{Path, PathBuf};
+
+/// Shaders compiled into the binary, resolvable by the [`super::ShaderPreprocessor`]
+/// under their path relative to `src/engine/shaders`.
+/// They take precedence over shaders in the resource folder.
+pub struct EmbeddedShaders;
+
+impl EmbeddedShaders {
+    /// Where the embedded shaders come from.
+    /// Debug builds read them from here, so they can be hot-reloaded.
+    #[cfg(debug_assertions)]
+    pub fn source_directory() -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("src/engine/shaders")
+    }
+
+    pub const SHADERS: &'static [(&'static str, &'static str)] = &[
+        (
+            "common/lights.wgsl",
+            include_str!("../../shaders/common/lights.wgsl"),
+        ),
+        (
+            "common/vertex.wgsl",
+            include_str!("../../shaders/common/vertex.wgsl"),
+        ),
+        (
+            "common/mesh.wgsl",
+            include_str!("../../shaders/common/mesh.wgsl"),
+        ),
+        (
+            "common/lighting.wgsl",
+            include_str!("../../shaders/common/lighting.wgsl"),
+        ),
+        (
+            "common/post_process.wgsl",
+            include_str!("../../shaders/common/post_process.wgsl"),
+        ),
+        (
+            "common/post_process_settings.wgsl",
+            include_str!("../../shaders/common/post_process_settings.wgsl"),
+        ),
+        (
+            "blinn_phong.wgsl",
+            include_str!("../../shaders/blinn_phong.wgsl"),
+        ),
+        ("pbr.wgsl", include_str!("../../shaders/pbr.wgsl")),
+        ("toon.wgsl", include_str!("../../shaders/toon.wgsl")),
+        ("unlit.wgsl", include_str!("../../shaders/unlit.wgsl")),
+        (
+            "vertex_color.wgsl",
+            include_str!("../../shaders/vertex_color.wgsl"),
+        ),
+        (
+            "light_clustering.wgsl",
+            include_str!("../../shaders/light_clustering.wgsl"),
+        ),
+        ("bloom.wgsl", include_str!("../../shaders/bloom.wgsl")),
+        (
+            "tonemapping.wgsl",
+            include_str!("../../shaders/tonemapping.wgsl"),
+        ),
+        ("fxaa.wgsl", include_str!("../../shaders/fxaa.wgsl")),
+        ("blit.wgsl", include_str!("../../shaders/blit.wgsl")),
+        ("shadow.wgsl", include_str!("../../shaders/shadow.wgsl")),
+        ("skybox.wgsl", include_str!("../../shaders/skybox.wgsl")),
+        (
+            "instance_culling.wgsl",
+            include_str!("../../shaders/instance_culling.wgsl"),
+        ),
+        (
+            "environment_baking.wgsl",
+            include_str!("../../shaders/environment_baking.wgsl"),
+        ),
+        (
+            "debug_lines.wgsl",
+            include_str!("../../shaders/debug_lines.wgsl"),
+        ),
+    ];
+
+    pub fn get(path: &str) -> Option<&'static str> {
+        Self::SHADERS
+            .iter()
+            .find(|(x, _)| *x == path)
+            .map(|(_, source)| *source)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::ShaderPreprocessor;
+
+    /// Shared shaders get validated through the shaders including them
+    #[test]
+    fn embedded_shaders_are_valid() {
+        for (path, _) in EmbeddedShaders::SHADERS
+            .iter()
+            .filter(|(x, _)| !x.starts_with("common/"))
+        {
+            if let Err(e) = ShaderPreprocessor::new()
+                .process_file(path)
+                .and_then(|x| x.validate())
+            {
+                panic!("{}: {:?}", path, e);
+            }
+        }
+    }
+}
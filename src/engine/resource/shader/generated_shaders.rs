@@ -0,0 +1,43 @@
+use crate::engine::{TGpuType, UDirectionalLight, UPointLight, USpotLight};
+
+/// Shaders generated from Rust types, resolvable by the [`super::ShaderPreprocessor`]
+/// under `generated/...`.
+/// Including them instead of writing the structures by hand keeps both
+/// sides in sync, see [`TGpuType::wgsl`].
+pub struct GeneratedShaders;
+
+impl GeneratedShaders {
+    pub const LIGHTS: &'static str = "generated/lights.wgsl";
+
+    pub fn get(path: &str) -> Option<String> {
+        match path {
+            Self::LIGHTS => Some(
+                [
+                    UPointLight::wgsl(),
+                    UDirectionalLight::wgsl(),
+                    USpotLight::wgsl(),
+                ]
+                .join("\n"),
+            ),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::ShaderPreprocessor;
+
+    #[test]
+    fn lights_are_generated_from_the_uniforms() {
+        let shader = ShaderPreprocessor::new()
+            .process_file("common/lights.wgsl")
+            .unwrap();
+
+        assert!(shader.depends_on(GeneratedShaders::LIGHTS));
+        assert!(shader.source().contains(&UPointLight::wgsl()));
+        assert!(shader.source().contains(&UDirectionalLight::wgsl()));
+        assert!(shader.source().contains(&USpotLight::wgsl()));
+    }
+}
@@ -0,0 +1,22 @@
+mod embedded_shaders;
+pub use embedded_shaders::*;
+
+mod generated_shaders;
+pub use generated_shaders::*;
+
+mod shader_location;
+pub use shader_location::*;
+
+mod shader_preprocessor;
+pub use shader_preprocessor::*;
+
+mod preprocessed_shader;
+pub use preprocessed_shader::*;
+
+mod shader_reflection;
+pub use shader_reflection::*;
+
+#[cfg(debug_assertions)]
+mod shader_watcher;
+#[cfg(debug_assertions)]
+pub use shader_watcher::*;
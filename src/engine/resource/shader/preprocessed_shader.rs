@@ -0,0 +1,119 @@
+use std::{error::Error, sync::Arc};
+
+use naga::valid::{Capabilities, ValidationFlags, Validator};
+use wgpu::{ShaderModule, ShaderModuleDescriptor, ShaderSource};
+
+use crate::engine::{EngineError, EngineResult, LogicalDevice};
+
+use super::{ShaderLocation, ShaderReflection};
+
+/// Output of the [`super::ShaderPreprocessor`], remembers where each line came from
+#[derive(Debug, Clone)]
+pub struct PreprocessedShader {
+    source: String,
+    /// One per line of `source`
+    locations: Vec<ShaderLocation>,
+    /// The processed shader & everything it included
+    files: Vec<Arc<str>>,
+}
+
+impl PreprocessedShader {
+    pub(super) fn new(
+        source: String,
+        locations: Vec<ShaderLocation>,
+        files: Vec<Arc<str>>,
+    ) -> Self {
+        Self {
+            source,
+            locations,
+            files,
+        }
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Path of the processed shader (or its name, see
+    /// [`super::ShaderPreprocessor::process_source`]) & of all included shaders
+    pub fn files(&self) -> &[Arc<str>] {
+        &self.files
+    }
+
+    /// Whether the shader at the path is the processed shader or got included
+    pub fn depends_on(&self, path: &str) -> bool {
+        self.files.iter().any(|x| x.as_ref() == path)
+    }
+
+    /// Original file & line of a line of the preprocessed source (starting at 1)
+    pub fn original_location(&self, line: u32) -> Option<&ShaderLocation> {
+        self.locations.get(line.checked_sub(1)? as usize)
+    }
+
+    /// Parses & validates the shader with naga.
+    /// Errors point at the original file & line instead of the preprocessed source.
+    pub fn validate(&self) -> EngineResult<()> {
+        self.reflect().map(|_| ())
+    }
+
+    /// Validates the shader & gives access to its bindings and inputs,
+    /// to check them against the Rust side
+    pub fn reflect(&self) -> EngineResult<ShaderReflection<'_>> {
+        let module = naga::front::wgsl::parse_str(&self.source).map_err(|e| {
+            self.shader_error(e.location(&self.source).map(|x| x.line_number), e.message())
+        })?;
+
+        let info = Validator::new(ValidationFlags::all(), Capabilities::all())
+            .validate(&module)
+            .map_err(|e| {
+                // The validation error itself is often generic, the details are in its sources
+                let mut message = e.as_inner().to_string();
+                let mut source = e.as_inner().source();
+                while let Some(x) = source {
+                    message += &format!(": {}", x);
+                    source = x.source();
+                }
+
+                self.shader_error(e.location(&self.source).map(|x| x.line_number), &message)
+            })?;
+
+        Ok(ShaderReflection::new(self, module, info))
+    }
+
+    /// Validates the shader first, see [`Self::validate`]
+    pub fn create_shader_module(
+        &self,
+        logical_device: &LogicalDevice,
+        label: &str,
+    ) -> EngineResult<ShaderModule> {
+        self.validate()?;
+
+        Ok(self.create_shader_module_unchecked(logical_device, label))
+    }
+
+    /// Prefixes the message with the original location of the line,
+    /// or the shader path if there is none
+    pub(super) fn located_message(&self, line: Option<u32>, message: &str) -> String {
+        match line.and_then(|x| self.original_location(x)) {
+            Some(location) => format!("{}: {}", location, message),
+            None => format!("{}: {}", self.files[0], message),
+        }
+    }
+
+    pub(super) fn create_shader_module_unchecked(
+        &self,
+        logical_device: &LogicalDevice,
+        label: &str,
+    ) -> ShaderModule {
+        logical_device
+            .device()
+            .create_shader_module(ShaderModuleDescriptor {
+                label: Some(label),
+                source: ShaderSource::Wgsl(self.source.as_str().into()),
+            })
+    }
+
+    fn shader_error(&self, line: Option<u32>, message: &str) -> EngineError {
+        EngineError::ShaderError(self.located_message(line, message))
+    }
+}
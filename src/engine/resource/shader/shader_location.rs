@@ -0,0 +1,15 @@
+use std::{fmt::Display, sync::Arc};
+
+/// A line of an original shader file, before preprocessing
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShaderLocation {
+    pub file: Arc<str>,
+    /// Starts at 1
+    pub line: u32,
+}
+
+impl Display for ShaderLocation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.file, self.line)
+    }
+}
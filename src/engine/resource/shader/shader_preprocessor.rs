@@ -0,0 +1,401 @@
+#[cfg(debug_assertions)]
+use std::path::PathBuf;
+use std::{collections::HashSet, fs::read_to_string, sync::Arc};
+
+use crate::engine::{EngineError, EngineResult, ResourceManager};
+
+use super::{EmbeddedShaders, GeneratedShaders, PreprocessedShader, ShaderLocation};
+
+/// Resolves the directives of WGSL shaders:
+/// - `#include "common/lighting.wgsl"`, included once per shader,
+///   looked up in the [`GeneratedShaders`], the [`EmbeddedShaders`] and then
+///   the resource folder
+/// - `#define NAME` & `#undef NAME`
+/// - `#ifdef NAME`, `#ifndef NAME`, `#else` & `#endif`
+///
+/// Directives have to stand on their own line.
+///
+/// Debug builds read shaders from the source tree first
+/// (see [`Self::source_directories`]), so changes get picked up by the hot-reload.
+#[derive(Debug, Clone, Default)]
+pub struct ShaderPreprocessor {
+    defines: HashSet<String>,
+    preludes: Vec<String>,
+}
+
+/// State of a single [`ShaderPreprocessor::process_source`] run
+struct Output {
+    defines: HashSet<String>,
+    /// In the order they got included
+    included: Vec<Arc<str>>,
+    source: String,
+    locations: Vec<ShaderLocation>,
+}
+
+/// An open `#ifdef`/`#ifndef` block
+struct Conditional {
+    location: ShaderLocation,
+    parent_active: bool,
+    condition: bool,
+    in_else: bool,
+}
+
+impl Conditional {
+    fn active(&self) -> bool {
+        self.parent_active && (self.condition != self.in_else)
+    }
+}
+
+impl ShaderPreprocessor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Defined before the shader gets processed, e.g. per pipeline
+    pub fn with_define(mut self, name: &str) -> Self {
+        self.defines.insert(name.to_string());
+        self
+    }
+
+    pub fn with_defines<'a>(mut self, names: impl IntoIterator<Item = &'a str>) -> Self {
+        self.defines.extend(names.into_iter().map(str::to_string));
+        self
+    }
+
+    /// Included before the shader, as if it started with `#include "path"`
+    pub fn with_prelude(mut self, path: &str) -> Self {
+        self.preludes.push(path.to_string());
+        self
+    }
+
+    /// Processes the shader at the path, see [`EmbeddedShaders`]
+    pub fn process_file(&self, path: &str) -> EngineResult<PreprocessedShader> {
+        let source = Self::read_shader(path)
+            .ok_or_else(|| EngineError::ShaderError(format!("Can't find shader '{}'", path)))?;
+
+        self.process_source(path, &source)
+    }
+
+    /// Processes a shader that isn't stored as a file,
+    /// `name` is used in place of the path in errors
+    pub fn process_source(&self, name: &str, source: &str) -> EngineResult<PreprocessedShader> {
+        let mut output = Output {
+            defines: self.defines.clone(),
+            included: vec![name.into()],
+            source: String::new(),
+            locations: Vec::new(),
+        };
+
+        let location = ShaderLocation {
+            file: name.into(),
+            line: 0,
+        };
+        for prelude in &self.preludes {
+            Self::include(&mut output, prelude, &location)?;
+        }
+
+        Self::process(&mut output, name.into(), source)?;
+
+        Ok(PreprocessedShader::new(
+            output.source,
+            output.locations,
+            output.included,
+        ))
+    }
+
+    /// Directories searched before the embedded shaders & the resource folder,
+    /// the [`super::ShaderWatcher`] watches them
+    #[cfg(debug_assertions)]
+    pub fn source_directories() -> Vec<PathBuf> {
+        vec![
+            EmbeddedShaders::source_directory(),
+            ResourceManager::resource_source_folder_path(),
+        ]
+    }
+
+    fn read_shader(path: &str) -> Option<String> {
+        if let Some(source) = GeneratedShaders::get(path) {
+            return Some(source);
+        }
+
+        #[cfg(debug_assertions)]
+        for directory in Self::source_directories() {
+            if let Ok(source) = read_to_string(directory.join(path)) {
+                return Some(source);
+            }
+        }
+
+        if let Some(source) = EmbeddedShaders::get(path) {
+            return Some(source.to_string());
+        }
+
+        ResourceManager::resource_path(path)
+            .ok()
+            .and_then(|x| read_to_string(x).ok())
+    }
+
+    fn include(output: &mut Output, path: &str, location: &ShaderLocation) -> EngineResult<()> {
+        if output.included.iter().any(|x| x.as_ref() == path) {
+            return Ok(());
+        }
+        output.included.push(path.into());
+
+        let source = Self::read_shader(path).ok_or_else(|| {
+            EngineError::ShaderError(format!("{}: Can't find include '{}'", location, path))
+        })?;
+
+        Self::process(output, path.into(), &source)
+    }
+
+    fn process(output: &mut Output, file: Arc<str>, source: &str) -> EngineResult<()> {
+        let mut conditionals: Vec<Conditional> = Vec::new();
+
+        for (i, line) in source.lines().enumerate() {
+            let location = ShaderLocation {
+                file: file.clone(),
+                line: i as u32 + 1,
+            };
+            let active = conditionals.last().is_none_or(Conditional::active);
+
+            let Some(directive) = line.trim().strip_prefix('#') else {
+                if active {
+                    output.source.push_str(line);
+                    output.source.push('\n');
+                    output.locations.push(location);
+                }
+                continue;
+            };
+
+            let error =
+                |message: String| EngineError::ShaderError(format!("{}: {}", location, message));
+
+            let (name, argument) = directive
+                .split_once(char::is_whitespace)
+                .map_or((directive, ""), |(name, argument)| (name, argument.trim()));
+
+            match name {
+                "ifdef" | "ifndef" => conditionals.push(Conditional {
+                    location: location.clone(),
+                    parent_active: active,
+                    condition: output.defines.contains(argument) == (name == "ifdef"),
+                    in_else: false,
+                }),
+                "else" => match conditionals.last_mut() {
+                    Some(x) if !x.in_else => x.in_else = true,
+                    _ => return Err(error("#else without #ifdef".to_string())),
+                },
+                "endif" => {
+                    if conditionals.pop().is_none() {
+                        return Err(error("#endif without #ifdef".to_string()));
+                    }
+                }
+                // Skipped blocks may contain anything else
+                _ if !active => {}
+                "define" => {
+                    output.defines.insert(argument.to_string());
+                }
+                "undef" => {
+                    output.defines.remove(argument);
+                }
+                "include" => {
+                    let path = argument
+                        .strip_prefix('"')
+                        .and_then(|x| x.strip_suffix('"'))
+                        .ok_or_else(|| {
+                            error(format!("Expected a quoted path, got '{}'", argument))
+                        })?;
+
+                    Self::include(output, path, &location)?;
+                }
+                _ => return Err(error(format!("Unknown directive '#{}'", name))),
+            }
+        }
+
+        match conditionals.pop() {
+            Some(x) => Err(EngineError::ShaderError(format!(
+                "{}: #ifdef without #endif",
+                x.location
+            ))),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error_message<T>(result: EngineResult<T>) -> String {
+        match result {
+            Err(EngineError::ShaderError(message)) => message,
+            Err(e) => panic!("Expected a shader error, got {:?}", e),
+            Ok(_) => panic!("Expected a shader error"),
+        }
+    }
+
+    #[test]
+    fn else_of_an_inactive_parent_stays_inactive() {
+        let source = "\
+#ifdef A
+a
+#ifdef B
+b
+#else
+not b
+#endif
+#else
+not a
+#ifdef B
+b
+#else
+not b
+#endif
+#endif
+";
+
+        let shader = ShaderPreprocessor::new()
+            .process_source("test", source)
+            .unwrap();
+        assert_eq!(shader.source(), "not a\nnot b\n");
+
+        let shader = ShaderPreprocessor::new()
+            .with_define("A")
+            .process_source("test", source)
+            .unwrap();
+        assert_eq!(shader.source(), "a\nnot b\n");
+
+        let shader = ShaderPreprocessor::new()
+            .with_defines(["A", "B"])
+            .process_source("test", source)
+            .unwrap();
+        assert_eq!(shader.source(), "a\nb\n");
+    }
+
+    #[test]
+    fn defines_apply_to_the_following_lines() {
+        let source = "\
+#define A
+#ifndef A
+a
+#endif
+#undef A
+#ifndef A
+not a
+#endif
+";
+
+        let shader = ShaderPreprocessor::new()
+            .process_source("test", source)
+            .unwrap();
+        assert_eq!(shader.source(), "not a\n");
+    }
+
+    #[test]
+    fn shaders_are_included_once() {
+        let path = "common/post_process_settings.wgsl";
+        let source = format!("#include \"{0}\"\n#include \"{0}\"\n", path);
+
+        let shader = ShaderPreprocessor::new()
+            .with_prelude(path)
+            .process_source("test", &source)
+            .unwrap();
+
+        assert_eq!(shader.source(), EmbeddedShaders::get(path).unwrap());
+        assert_eq!(shader.files().len(), 2);
+        assert!(shader.depends_on(path));
+    }
+
+    #[test]
+    fn include_cycles_are_cut() {
+        // common/mesh.wgsl includes common/lights.wgsl back
+        let source = "#include \"common/mesh.wgsl\"\n";
+
+        let shader = ShaderPreprocessor::new()
+            .process_source("common/lights.wgsl", source)
+            .unwrap();
+
+        assert!(shader.depends_on("common/mesh.wgsl"));
+        assert!(!shader.source().contains("struct PointLights"));
+    }
+
+    #[test]
+    fn unknown_directives_are_skipped_in_inactive_blocks() {
+        let source = "#ifdef A\n#unknown\n#endif\n";
+
+        assert!(ShaderPreprocessor::new()
+            .process_source("test", source)
+            .is_ok());
+
+        let message = error_message(
+            ShaderPreprocessor::new()
+                .with_define("A")
+                .process_source("test", source),
+        );
+        assert_eq!(message, "test:2: Unknown directive '#unknown'");
+    }
+
+    #[test]
+    fn unterminated_ifdef_is_an_error() {
+        let message = error_message(
+            ShaderPreprocessor::new().process_source("test", "a\n#ifdef A\n#else\nb\n"),
+        );
+        assert_eq!(message, "test:2: #ifdef without #endif");
+
+        let message = error_message(ShaderPreprocessor::new().process_source("test", "#endif\n"));
+        assert_eq!(message, "test:1: #endif without #ifdef");
+    }
+
+    #[test]
+    fn missing_include_points_at_the_directive() {
+        let message = error_message(
+            ShaderPreprocessor::new().process_source("test", "\n#include \"missing.wgsl\"\n"),
+        );
+        assert_eq!(message, "test:2: Can't find include 'missing.wgsl'");
+    }
+
+    #[test]
+    fn locations_map_back_to_the_included_file() {
+        let path = "common/post_process_settings.wgsl";
+        let source = format!(
+            "#include \"{}\"\nfn f() -> f32 {{\n    return foo;\n}}\n",
+            path
+        );
+
+        let shader = ShaderPreprocessor::new()
+            .process_source("test", &source)
+            .unwrap();
+
+        let settings_line = shader
+            .source()
+            .lines()
+            .position(|x| x.starts_with("struct PostProcessSettings"))
+            .unwrap() as u32
+            + 1;
+        assert_eq!(
+            shader.original_location(settings_line),
+            Some(&ShaderLocation {
+                file: path.into(),
+                line: 5,
+            })
+        );
+
+        let message = error_message(shader.validate());
+        assert!(message.starts_with("test:3: "), "{}", message);
+    }
+
+    #[test]
+    fn errors_in_included_files_point_at_them() {
+        let path = "common/post_process_settings.wgsl";
+        let source = format!(
+            "struct PostProcessSettings {{\n    x: f32,\n}}\n#include \"{}\"\n",
+            path
+        );
+
+        let shader = ShaderPreprocessor::new()
+            .process_source("test", &source)
+            .unwrap();
+
+        let message = error_message(shader.validate());
+        assert!(message.starts_with(&format!("{}:5: ", path)), "{}", message);
+    }
+}
@@ -0,0 +1,472 @@
+use naga::{
+    valid::ModuleInfo, AddressSpace, Binding, GlobalVariable, Handle, ImageClass, ImageDimension,
+    Module, ScalarKind, ShaderStage, StorageAccess, TypeInner,
+};
+use wgpu::{
+    BindGroupLayoutDescriptor, BindingType, BufferBindingType, SamplerBindingType, ShaderModule,
+    ShaderStages, TextureSampleType, TextureViewDimension, VertexBufferLayout, VertexFormat,
+};
+
+use crate::engine::{EngineError, EngineResult, LogicalDevice};
+
+use super::PreprocessedShader;
+
+/// A validated shader, see [`PreprocessedShader::reflect`].
+///
+/// Checks the bindings & vertex inputs the shader declares against the
+/// hand-written layouts of the Rust side, so a mismatch results in an
+/// [`EngineError::ShaderLayoutMismatch`] pointing at the shader
+/// instead of a wgpu validation error.
+pub struct ShaderReflection<'a> {
+    shader: &'a PreprocessedShader,
+    module: Module,
+    info: ModuleInfo,
+}
+
+impl<'a> ShaderReflection<'a> {
+    pub(super) fn new(shader: &'a PreprocessedShader, module: Module, info: ModuleInfo) -> Self {
+        Self {
+            shader,
+            module,
+            info,
+        }
+    }
+
+    /// Checks every binding used by the entry points against the bind group
+    /// layouts of the pipeline, indexed by group:
+    /// - The group & binding exist
+    /// - The binding is visible to the stages using it
+    /// - The binding type (buffer, texture, sampler) matches the declaration
+    /// - Buffers with a `min_binding_size` have the size of the declared type
+    ///   (padded to at most 16 bytes), or hold at least one element of a
+    ///   runtime-sized array
+    pub fn validate_bind_group_layouts(
+        &self,
+        entry_points: &[&str],
+        bind_group_layouts: &[&BindGroupLayoutDescriptor],
+    ) -> EngineResult<()> {
+        for (handle, global) in self.module.global_variables.iter() {
+            let Some(binding) = &global.binding else {
+                continue;
+            };
+            let stages = self.used_stages(handle, entry_points);
+            if stages.is_empty() {
+                continue;
+            }
+
+            let error = |message: String| {
+                let line = self
+                    .module
+                    .global_variables
+                    .get_span(handle)
+                    .location(self.shader.source())
+                    .line_number;
+
+                EngineError::ShaderLayoutMismatch(self.shader.located_message(
+                    Some(line),
+                    &format!(
+                        "`{}` (@group({}) @binding({})): {}",
+                        global.name.as_deref().unwrap_or("?"),
+                        binding.group,
+                        binding.binding,
+                        message
+                    ),
+                ))
+            };
+
+            let Some(layout) = bind_group_layouts.get(binding.group as usize) else {
+                return Err(error(format!(
+                    "The pipeline only has {} bind groups",
+                    bind_group_layouts.len()
+                )));
+            };
+            let Some(entry) = layout.entries.iter().find(|x| x.binding == binding.binding) else {
+                return Err(error(format!(
+                    "Missing in the bind group layout {:?}",
+                    layout.label.unwrap_or("?")
+                )));
+            };
+
+            if !entry.visibility.contains(stages) {
+                return Err(error(format!(
+                    "Used by {:?}, but only visible to {:?}",
+                    stages, entry.visibility
+                )));
+            }
+            if !self.binding_type_matches(global, entry.ty) {
+                return Err(error(format!(
+                    "Declared as {}, but the layout has {:?}",
+                    self.describe_global(global),
+                    entry.ty
+                )));
+            }
+            if let BindingType::Buffer {
+                min_binding_size: Some(size),
+                ..
+            } = entry.ty
+            {
+                let inner = &self.module.types[global.ty].inner;
+                let shader_size = inner.size(self.module.to_ctx()) as u64;
+
+                if inner.is_dynamically_sized(&self.module.types) {
+                    if size.get() < shader_size {
+                        return Err(error(format!(
+                            "Needs at least {} bytes, but the layout has a min_binding_size of {}",
+                            shader_size, size
+                        )));
+                    }
+                } else if size.get() < shader_size || size.get() > shader_size.next_multiple_of(16)
+                {
+                    return Err(error(format!(
+                        "Declared with {} bytes, but the layout has a min_binding_size of {}",
+                        shader_size, size
+                    )));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Checks that every `@location` input of the vertex entry point
+    /// is provided by one of the buffers with a matching scalar type
+    pub fn validate_vertex_buffers(
+        &self,
+        entry_point: &str,
+        buffers: &[VertexBufferLayout],
+    ) -> EngineResult<()> {
+        let error = |message: String| {
+            EngineError::ShaderLayoutMismatch(
+                self.shader
+                    .located_message(None, &format!("`{}`: {}", entry_point, message)),
+            )
+        };
+
+        let entry_point = self
+            .module
+            .entry_points
+            .iter()
+            .find(|x| x.name == entry_point && x.stage == ShaderStage::Vertex)
+            .ok_or_else(|| error("No such vertex entry point".to_string()))?;
+
+        // Inputs are either arguments or members of struct arguments
+        let mut inputs = Vec::new();
+        for argument in &entry_point.function.arguments {
+            match (&argument.binding, &self.module.types[argument.ty].inner) {
+                (Some(binding), _) => inputs.push((argument.name.as_deref(), argument.ty, binding)),
+                (None, TypeInner::Struct { members, .. }) => inputs.extend(
+                    members
+                        .iter()
+                        .filter_map(|x| Some((x.name.as_deref(), x.ty, x.binding.as_ref()?))),
+                ),
+                (None, _) => {}
+            }
+        }
+
+        for (name, ty, binding) in inputs {
+            let Binding::Location { location, .. } = binding else {
+                continue;
+            };
+            let name = name.unwrap_or("?");
+
+            let Some(attribute) = buffers
+                .iter()
+                .flat_map(|x| x.attributes)
+                .find(|x| x.shader_location == *location)
+            else {
+                return Err(error(format!(
+                    "Input `{}` (@location({})) isn't provided by any vertex buffer",
+                    name, location
+                )));
+            };
+
+            let shader_kind = Self::scalar_kind(&self.module.types[ty].inner);
+            if shader_kind != Some(Self::vertex_format_kind(attribute.format)) {
+                return Err(error(format!(
+                    "Input `{}` (@location({})) is {:?}, but the vertex buffer provides {:?}",
+                    name, location, self.module.types[ty].inner, attribute.format
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Skips the validation, it already happened while reflecting
+    pub fn create_shader_module(
+        &self,
+        logical_device: &LogicalDevice,
+        label: &str,
+    ) -> ShaderModule {
+        self.shader
+            .create_shader_module_unchecked(logical_device, label)
+    }
+
+    /// Stages of the given entry points using the global
+    fn used_stages(&self, handle: Handle<GlobalVariable>, entry_points: &[&str]) -> ShaderStages {
+        self.module
+            .entry_points
+            .iter()
+            .enumerate()
+            .filter(|(i, x)| {
+                entry_points.contains(&x.name.as_str())
+                    && !self.info.get_entry_point(*i)[handle].is_empty()
+            })
+            .fold(ShaderStages::NONE, |stages, (_, x)| {
+                stages
+                    | match x.stage {
+                        ShaderStage::Vertex => ShaderStages::VERTEX,
+                        ShaderStage::Fragment => ShaderStages::FRAGMENT,
+                        ShaderStage::Compute => ShaderStages::COMPUTE,
+                    }
+            })
+    }
+
+    /// Binding arrays are checked by their element type
+    fn binding_inner(&self, global: &GlobalVariable) -> &TypeInner {
+        match &self.module.types[global.ty].inner {
+            TypeInner::BindingArray { base, .. } => &self.module.types[*base].inner,
+            x => x,
+        }
+    }
+
+    fn binding_type_matches(&self, global: &GlobalVariable, binding_type: BindingType) -> bool {
+        match (global.space, self.binding_inner(global), binding_type) {
+            (
+                AddressSpace::Uniform,
+                _,
+                BindingType::Buffer {
+                    ty: BufferBindingType::Uniform,
+                    ..
+                },
+            ) => true,
+            (
+                AddressSpace::Storage { access },
+                _,
+                BindingType::Buffer {
+                    ty: BufferBindingType::Storage { read_only },
+                    ..
+                },
+            ) => read_only != access.contains(StorageAccess::STORE),
+            (AddressSpace::Handle, TypeInner::Sampler { comparison }, BindingType::Sampler(x)) => {
+                *comparison == (x == SamplerBindingType::Comparison)
+            }
+            (
+                AddressSpace::Handle,
+                TypeInner::Image {
+                    dim,
+                    arrayed,
+                    class,
+                },
+                BindingType::Texture {
+                    sample_type,
+                    view_dimension,
+                    multisampled,
+                },
+            ) => {
+                Self::view_dimension_matches(*dim, *arrayed, view_dimension)
+                    && match (class, sample_type) {
+                        (
+                            ImageClass::Sampled {
+                                kind: ScalarKind::Float,
+                                multi,
+                            },
+                            TextureSampleType::Float { .. } | TextureSampleType::Depth,
+                        )
+                        | (
+                            ImageClass::Sampled {
+                                kind: ScalarKind::Sint,
+                                multi,
+                            },
+                            TextureSampleType::Sint,
+                        )
+                        | (
+                            ImageClass::Sampled {
+                                kind: ScalarKind::Uint,
+                                multi,
+                            },
+                            TextureSampleType::Uint,
+                        )
+                        | (ImageClass::Depth { multi }, TextureSampleType::Depth) => {
+                            *multi == multisampled
+                        }
+                        _ => false,
+                    }
+            }
+            (
+                AddressSpace::Handle,
+                TypeInner::Image {
+                    dim,
+                    arrayed,
+                    class: ImageClass::Storage { .. },
+                },
+                BindingType::StorageTexture { view_dimension, .. },
+            ) => Self::view_dimension_matches(*dim, *arrayed, view_dimension),
+            _ => false,
+        }
+    }
+
+    fn describe_global(&self, global: &GlobalVariable) -> String {
+        match (global.space, self.binding_inner(global)) {
+            (AddressSpace::Uniform, _) => "a uniform buffer".to_string(),
+            (AddressSpace::Storage { access }, _) => format!("a storage buffer ({:?})", access),
+            (AddressSpace::Handle, TypeInner::Sampler { comparison: true }) => {
+                "a comparison sampler".to_string()
+            }
+            (AddressSpace::Handle, TypeInner::Sampler { comparison: false }) => {
+                "a sampler".to_string()
+            }
+            (
+                AddressSpace::Handle,
+                TypeInner::Image {
+                    dim,
+                    arrayed,
+                    class,
+                },
+            ) => format!(
+                "a {:?}{} texture ({:?})",
+                dim,
+                if *arrayed { " array" } else { "" },
+                class
+            ),
+            (space, inner) => format!("{:?} {:?}", space, inner),
+        }
+    }
+
+    fn view_dimension_matches(
+        dim: ImageDimension,
+        arrayed: bool,
+        view_dimension: TextureViewDimension,
+    ) -> bool {
+        matches!(
+            (dim, arrayed, view_dimension),
+            (ImageDimension::D1, false, TextureViewDimension::D1)
+                | (ImageDimension::D2, false, TextureViewDimension::D2)
+                | (ImageDimension::D2, true, TextureViewDimension::D2Array)
+                | (ImageDimension::D3, false, TextureViewDimension::D3)
+                | (ImageDimension::Cube, false, TextureViewDimension::Cube)
+                | (ImageDimension::Cube, true, TextureViewDimension::CubeArray)
+        )
+    }
+
+    fn scalar_kind(inner: &TypeInner) -> Option<ScalarKind> {
+        match inner {
+            TypeInner::Scalar { kind, .. } | TypeInner::Vector { kind, .. } => Some(*kind),
+            _ => None,
+        }
+    }
+
+    /// Normalized formats are read as floats
+    fn vertex_format_kind(format: VertexFormat) -> ScalarKind {
+        match format {
+            VertexFormat::Uint8x2
+            | VertexFormat::Uint8x4
+            | VertexFormat::Uint16x2
+            | VertexFormat::Uint16x4
+            | VertexFormat::Uint32
+            | VertexFormat::Uint32x2
+            | VertexFormat::Uint32x3
+            | VertexFormat::Uint32x4 => ScalarKind::Uint,
+            VertexFormat::Sint8x2
+            | VertexFormat::Sint8x4
+            | VertexFormat::Sint16x2
+            | VertexFormat::Sint16x4
+            | VertexFormat::Sint32
+            | VertexFormat::Sint32x2
+            | VertexFormat::Sint32x3
+            | VertexFormat::Sint32x4 => ScalarKind::Sint,
+            _ => ScalarKind::Float,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroU64;
+
+    use wgpu::{BindGroupLayoutEntry, BufferBindingType};
+
+    use super::*;
+    use crate::engine::ShaderPreprocessor;
+
+    const SHADER: &str = "
+struct Settings {
+    strength: f32,
+    scale: f32,
+    mode: u32,
+}
+
+struct Lights {
+    count: u32,
+    positions: array<vec4<f32>>,
+}
+
+@group(0) @binding(0)
+var<uniform> settings: Settings;
+
+@group(0) @binding(1)
+var<storage, read> lights: Lights;
+
+@compute @workgroup_size(1)
+fn main() {
+    let x = settings.strength + lights.positions[0].x;
+}
+";
+
+    fn validate(settings_size: u64, lights_size: u64) -> EngineResult<()> {
+        let buffer = |binding: u32, ty: BufferBindingType, size: u64| BindGroupLayoutEntry {
+            binding,
+            visibility: ShaderStages::COMPUTE,
+            ty: BindingType::Buffer {
+                ty,
+                has_dynamic_offset: false,
+                min_binding_size: NonZeroU64::new(size),
+            },
+            count: None,
+        };
+        let entries = [
+            buffer(0, BufferBindingType::Uniform, settings_size),
+            buffer(
+                1,
+                BufferBindingType::Storage { read_only: true },
+                lights_size,
+            ),
+        ];
+
+        ShaderPreprocessor::new()
+            .process_source("test", SHADER)?
+            .reflect()?
+            .validate_bind_group_layouts(
+                &["main"],
+                &[&BindGroupLayoutDescriptor {
+                    label: None,
+                    entries: &entries,
+                }],
+            )
+    }
+
+    #[test]
+    fn buffer_sizes_match_the_declared_types() {
+        // Settings is 12 bytes, Lights 16 bytes plus 16 per element
+        assert!(validate(12, 32).is_ok());
+        // Padding to 16 bytes & more elements are fine
+        assert!(validate(16, 64).is_ok());
+        // Unchecked without a min_binding_size
+        assert!(validate(0, 0).is_ok());
+    }
+
+    #[test]
+    fn buffer_size_mismatches_are_errors() {
+        for (settings_size, lights_size) in [(8, 32), (32, 32), (12, 16)] {
+            assert!(
+                matches!(
+                    validate(settings_size, lights_size),
+                    Err(EngineError::ShaderLayoutMismatch(_))
+                ),
+                "{} & {} bytes",
+                settings_size,
+                lights_size
+            );
+        }
+    }
+}
@@ -0,0 +1,94 @@
+use std::{
+    collections::HashMap,
+    fs::read_dir,
+    path::{Path, PathBuf},
+    time::{Duration, Instant, SystemTime},
+};
+
+/// Polls the modification times of the `.wgsl` files in some directories
+/// to find changed shaders, used for the hot-reload of debug builds.
+///
+/// Changes are reported as paths relative to their directory, i.e. the same
+/// paths the [`super::ShaderPreprocessor`] resolves.
+pub struct ShaderWatcher {
+    directories: Vec<PathBuf>,
+    modified: HashMap<String, SystemTime>,
+    last_poll: Instant,
+}
+
+impl ShaderWatcher {
+    /// Directories get scanned at most this often
+    pub const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+    pub fn new(directories: Vec<PathBuf>) -> Self {
+        let modified = Self::scan(&directories);
+
+        Self {
+            directories,
+            modified,
+            last_poll: Instant::now(),
+        }
+    }
+
+    /// Shaders which changed or got added since the last poll,
+    /// empty if the last poll was less than [`Self::POLL_INTERVAL`] ago
+    pub fn poll(&mut self) -> Vec<String> {
+        if self.last_poll.elapsed() < Self::POLL_INTERVAL {
+            return vec![];
+        }
+        self.last_poll = Instant::now();
+
+        let modified = Self::scan(&self.directories);
+        let mut changed: Vec<String> = modified
+            .iter()
+            .filter(|(path, time)| self.modified.get(*path) != Some(time))
+            .map(|(path, _)| path.clone())
+            .collect();
+        changed.sort();
+
+        self.modified = modified;
+        changed
+    }
+
+    fn scan(directories: &[PathBuf]) -> HashMap<String, SystemTime> {
+        let mut modified = HashMap::new();
+        for directory in directories {
+            Self::scan_directory(directory, directory, &mut modified);
+        }
+        modified
+    }
+
+    fn scan_directory(root: &Path, directory: &Path, modified: &mut HashMap<String, SystemTime>) {
+        let Ok(entries) = read_dir(directory) else {
+            return;
+        };
+
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                Self::scan_directory(root, &path, modified);
+                continue;
+            }
+            if path.extension().is_none_or(|x| x != "wgsl") {
+                continue;
+            }
+
+            let (Ok(relative_path), Ok(time)) = (
+                path.strip_prefix(root),
+                entry.metadata().and_then(|x| x.modified()),
+            ) else {
+                continue;
+            };
+
+            // Always `/`, like the paths of `#include`
+            let relative_path = relative_path
+                .components()
+                .map(|x| x.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+
+            // Earlier directories take precedence, like in the preprocessor
+            modified.entry(relative_path).or_insert(time);
+        }
+    }
+}
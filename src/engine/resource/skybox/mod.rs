@@ -0,0 +1,123 @@
+use std::{mem::size_of, num::NonZeroU64};
+
+use wgpu::{
+    BindGroup, BindGroupDescriptor, BindGroupEntry, BindGroupLayout, BindGroupLayoutDescriptor,
+    BindGroupLayoutEntry, BindingResource, BindingType, Buffer, BufferAddress, BufferBindingType,
+    BufferDescriptor, BufferUsages, SamplerBindingType, ShaderStages, TextureSampleType,
+    TextureViewDimension,
+};
+
+use crate::engine::{Camera, CubeTexture, EngineResult, LogicalDevice, TTexture};
+
+mod skybox_source;
+pub use skybox_source::*;
+
+mod u_skybox;
+pub use u_skybox::*;
+
+/// Cubemap rendered behind all geometry
+pub struct Skybox {
+    cube_texture: CubeTexture,
+    buffer: Buffer,
+    bind_group: BindGroup,
+}
+
+impl Skybox {
+    pub const BIND_GROUP_LAYOUT_DESCRIPTOR: BindGroupLayoutDescriptor<'static> =
+        BindGroupLayoutDescriptor {
+            label: Some("Skybox Bind Group Layout"),
+            entries: &[
+                BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Texture {
+                        sample_type: TextureSampleType::Float { filterable: true },
+                        view_dimension: TextureViewDimension::Cube,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Sampler(SamplerBindingType::Filtering),
+                    count: None,
+                },
+                BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Buffer {
+                        ty: BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: NonZeroU64::new(size_of::<USkybox>() as u64),
+                    },
+                    count: None,
+                },
+            ],
+        };
+
+    pub fn new(logical_device: &LogicalDevice, source: &SkyboxSource) -> EngineResult<Self> {
+        let cube_texture = source.load(logical_device, Some("Skybox"))?;
+
+        Ok(Self::from_cube_texture(logical_device, cube_texture))
+    }
+
+    pub fn from_cube_texture(logical_device: &LogicalDevice, cube_texture: CubeTexture) -> Self {
+        let buffer = logical_device.device().create_buffer(&BufferDescriptor {
+            label: Some("Skybox Buffer"),
+            size: size_of::<USkybox>() as BufferAddress,
+            usage: BufferUsages::UNIFORM | BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let bind_group = logical_device
+            .device()
+            .create_bind_group(&BindGroupDescriptor {
+                label: Some("Skybox Bind Group"),
+                layout: &Self::bind_group_layout(logical_device),
+                entries: &[
+                    BindGroupEntry {
+                        binding: 0,
+                        resource: BindingResource::TextureView(cube_texture.view()),
+                    },
+                    BindGroupEntry {
+                        binding: 1,
+                        resource: BindingResource::Sampler(cube_texture.sampler()),
+                    },
+                    BindGroupEntry {
+                        binding: 2,
+                        resource: buffer.as_entire_binding(),
+                    },
+                ],
+            });
+
+        Self {
+            cube_texture,
+            buffer,
+            bind_group,
+        }
+    }
+
+    pub fn bind_group_layout(logical_device: &LogicalDevice) -> BindGroupLayout {
+        logical_device
+            .device()
+            .create_bind_group_layout(&Self::BIND_GROUP_LAYOUT_DESCRIPTOR)
+    }
+
+    /// Follows the rotation of the camera
+    pub fn update_buffer(&self, logical_device: &LogicalDevice, camera: &Camera) {
+        logical_device.queue().write_buffer(
+            &self.buffer,
+            0,
+            bytemuck::cast_slice(&[USkybox::from_camera(camera)]),
+        );
+    }
+
+    pub fn cube_texture(&self) -> &CubeTexture {
+        &self.cube_texture
+    }
+
+    pub fn bind_group(&self) -> &BindGroup {
+        &self.bind_group
+    }
+}
@@ -0,0 +1,33 @@
+use std::path::PathBuf;
+
+use crate::engine::{CubeTexture, EngineResult, LogicalDevice};
+
+/// Where the cubemap of a [`super::Skybox`] comes from.
+/// Paths are relative to the resource folder.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SkyboxSource {
+    /// Six square images, ordered +X, -X, +Y, -Y, +Z, -Z
+    /// (right, left, top, bottom, front, back)
+    Faces([PathBuf; 6]),
+
+    /// A single equirectangular (latitude-longitude) image,
+    /// e.g. an `.hdr` environment map.
+    /// Gets projected onto cube faces of `face_size` pixels.
+    Equirectangular { path: PathBuf, face_size: u32 },
+}
+
+impl SkyboxSource {
+    /// Loads the cubemap the source describes
+    pub fn load(
+        &self,
+        logical_device: &LogicalDevice,
+        label: Option<&str>,
+    ) -> EngineResult<CubeTexture> {
+        match self {
+            Self::Faces(paths) => CubeTexture::from_face_paths(logical_device, paths, label),
+            Self::Equirectangular { path, face_size } => {
+                CubeTexture::from_equirectangular_path(logical_device, path, *face_size, label)
+            }
+        }
+    }
+}
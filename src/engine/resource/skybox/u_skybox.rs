@@ -0,0 +1,27 @@
+use bytemuck::{Pod, Zeroable};
+use cgmath::{Matrix4, SquareMatrix, Vector4};
+
+use crate::engine::Camera;
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Pod, Zeroable)]
+pub struct USkybox {
+    /// Maps screen positions back into world directions.
+    /// Doesn't include the camera position, so the skybox stays at infinity.
+    inverse_view_projection_matrix: [[f32; 4]; 4],
+}
+
+impl USkybox {
+    pub fn from_camera(camera: &Camera) -> Self {
+        let mut view_matrix = camera.calculate_matrix();
+        view_matrix.w = Vector4::new(0.0, 0.0, 0.0, 1.0);
+
+        let inverse_view_projection_matrix = (camera.projection().calculate_matrix() * view_matrix)
+            .invert()
+            .unwrap_or_else(Matrix4::identity);
+
+        Self {
+            inverse_view_projection_matrix: inverse_view_projection_matrix.into(),
+        }
+    }
+}
@@ -121,17 +121,51 @@ impl AbstractTexture {
         usage: TextureUsages,
         label: Option<&str>,
     ) -> EngineResult<Self> {
-        // Make texture
-        let texture = logical_device.device().create_texture(&TextureDescriptor {
-            label,
+        Self::from_empty_multisampled(
+            logical_device,
             size,
-            mip_level_count: 1,
-            sample_count: 1,
-            dimension: TextureDimension::D2,
             format,
+            sampler_descriptor,
             usage,
-            view_formats: &[],
-        });
+            1,
+            label,
+        )
+    }
+
+    pub fn from_empty_multisampled(
+        logical_device: &LogicalDevice,
+        size: Extent3d,
+        format: TextureFormat,
+        sampler_descriptor: &SamplerDescriptor,
+        usage: TextureUsages,
+        sample_count: u32,
+        label: Option<&str>,
+    ) -> EngineResult<Self> {
+        Self::from_descriptor(
+            logical_device,
+            &TextureDescriptor {
+                label,
+                size,
+                mip_level_count: 1,
+                sample_count,
+                dimension: TextureDimension::D2,
+                format,
+                usage,
+                view_formats: &[],
+            },
+            sampler_descriptor,
+        )
+    }
+
+    /// Creates an empty texture from a full descriptor,
+    /// e.g. for textures with multiple mip levels
+    pub fn from_descriptor(
+        logical_device: &LogicalDevice,
+        texture_descriptor: &TextureDescriptor,
+        sampler_descriptor: &SamplerDescriptor,
+    ) -> EngineResult<Self> {
+        // Make texture
+        let texture = logical_device.device().create_texture(texture_descriptor);
 
         // Create texture view
         let view = texture.make_texture_view();
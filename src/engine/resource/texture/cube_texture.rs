@@ -0,0 +1,302 @@
+use std::{f32::consts::PI, path::Path};
+
+use half::f16;
+use image::{DynamicImage, GenericImageView, Rgba32FImage};
+use wgpu::{
+    AddressMode, Extent3d, FilterMode, ImageCopyTexture, ImageDataLayout, Origin3d, Sampler,
+    SamplerDescriptor, Texture, TextureAspect, TextureDescriptor, TextureDimension, TextureFormat,
+    TextureUsages, TextureView, TextureViewDescriptor, TextureViewDimension,
+};
+
+use crate::engine::{
+    srgb_to_linear, EngineError, EngineResult, LogicalDevice, ResourceManager, TextureHelper,
+};
+
+use super::{AbstractTexture, TTexture};
+
+/// Six square textures forming a cube, sampled by direction
+/// (e.g. skyboxes and environment maps).
+///
+/// Faces are stored in the order +X, -X, +Y, -Y, +Z, -Z.
+/// Colors are kept in linear HDR.
+pub struct CubeTexture {
+    internal_texture: AbstractTexture,
+    cube_view: TextureView,
+    face_size: u32,
+}
+
+impl CubeTexture {
+    pub const TEXTURE_FORMAT: TextureFormat = TextureFormat::Rgba16Float;
+    pub const FACE_COUNT: u32 = 6;
+    pub const SAMPLER_DESCRIPTOR: SamplerDescriptor<'static> = SamplerDescriptor {
+        label: Some("Cube Texture Sampler Descriptor"),
+        address_mode_u: AddressMode::ClampToEdge,
+        address_mode_v: AddressMode::ClampToEdge,
+        address_mode_w: AddressMode::ClampToEdge,
+        mag_filter: FilterMode::Linear,
+        min_filter: FilterMode::Linear,
+        mipmap_filter: FilterMode::Linear,
+        lod_min_clamp: 0.0,  // Default
+        lod_max_clamp: 32.0, // Default
+        compare: None,       // Default
+        anisotropy_clamp: 1, // Default
+        border_color: None,  // Default
+    };
+
+    pub fn from_empty(
+        logical_device: &LogicalDevice,
+        face_size: u32,
+        mip_level_count: u32,
+        usage: TextureUsages,
+        label: Option<&str>,
+    ) -> EngineResult<Self> {
+        let internal_texture = AbstractTexture::from_descriptor(
+            logical_device,
+            &TextureDescriptor {
+                label,
+                size: Extent3d {
+                    width: face_size,
+                    height: face_size,
+                    depth_or_array_layers: Self::FACE_COUNT,
+                },
+                mip_level_count,
+                sample_count: 1,
+                dimension: TextureDimension::D2,
+                format: Self::TEXTURE_FORMAT,
+                usage,
+                view_formats: &[],
+            },
+            &Self::SAMPLER_DESCRIPTOR,
+        )?;
+
+        let cube_view =
+            internal_texture
+                .texture()
+                .make_texture_view_descriptor(&TextureViewDescriptor {
+                    label,
+                    dimension: Some(TextureViewDimension::Cube),
+                    ..Default::default()
+                });
+
+        Ok(Self {
+            internal_texture,
+            cube_view,
+            face_size,
+        })
+    }
+
+    /// Loads six images from the resource folder,
+    /// ordered +X, -X, +Y, -Y, +Z, -Z (right, left, top, bottom, front, back)
+    pub fn from_face_paths<P>(
+        logical_device: &LogicalDevice,
+        file_paths: &[P; 6],
+        label: Option<&str>,
+    ) -> EngineResult<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let mut faces = Vec::with_capacity(Self::FACE_COUNT as usize);
+        for file_path in file_paths {
+            let bytes = ResourceManager::read_resource_binary(file_path)?;
+            faces.push(image::load_from_memory(&bytes).map_err(EngineError::ImageError)?);
+        }
+
+        Self::from_face_images(logical_device, &faces, label)
+    }
+
+    /// Creates a cube texture from six square images of the same size,
+    /// ordered +X, -X, +Y, -Y, +Z, -Z
+    pub fn from_face_images(
+        logical_device: &LogicalDevice,
+        faces: &[DynamicImage],
+        label: Option<&str>,
+    ) -> EngineResult<Self> {
+        let face_size = faces.first().map(|x| x.width()).unwrap_or_default();
+        if faces.len() != Self::FACE_COUNT as usize
+            || faces
+                .iter()
+                .any(|x| x.dimensions() != (face_size, face_size))
+        {
+            return Err(EngineError::CubeTextureFaceMismatch);
+        }
+
+        let cube_texture = Self::from_empty(
+            logical_device,
+            face_size,
+            1,
+            TextureUsages::TEXTURE_BINDING | TextureUsages::COPY_DST,
+            label,
+        )?;
+
+        for (layer, face) in faces.iter().enumerate() {
+            cube_texture.write_face(logical_device, layer as u32, &Self::to_linear(face));
+        }
+
+        Ok(cube_texture)
+    }
+
+    /// Loads an equirectangular (i.e. latitude-longitude) image from the
+    /// resource folder, e.g. a `.hdr` environment map
+    pub fn from_equirectangular_path<P>(
+        logical_device: &LogicalDevice,
+        file_path: P,
+        face_size: u32,
+        label: Option<&str>,
+    ) -> EngineResult<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let bytes = ResourceManager::read_resource_binary(file_path)?;
+        let image = image::load_from_memory(&bytes).map_err(EngineError::ImageError)?;
+
+        Self::from_equirectangular_image(logical_device, &image, face_size, label)
+    }
+
+    /// Projects an equirectangular image onto the six faces of a cube
+    pub fn from_equirectangular_image(
+        logical_device: &LogicalDevice,
+        image: &DynamicImage,
+        face_size: u32,
+        label: Option<&str>,
+    ) -> EngineResult<Self> {
+        let cube_texture = Self::from_empty(
+            logical_device,
+            face_size,
+            1,
+            TextureUsages::TEXTURE_BINDING | TextureUsages::COPY_DST,
+            label,
+        )?;
+
+        let equirectangular = Self::to_linear(image);
+        for layer in 0..Self::FACE_COUNT {
+            let face = Rgba32FImage::from_fn(face_size, face_size, |x, y| {
+                let direction = Self::face_direction(layer, face_size, x, y);
+
+                // Direction -> latitude & longitude -> UV
+                let u = 0.5 + direction[2].atan2(direction[0]) / (2.0 * PI);
+                let v = direction[1].clamp(-1.0, 1.0).acos() / PI;
+
+                Self::sample_bilinear(&equirectangular, u, v).into()
+            });
+
+            cube_texture.write_face(logical_device, layer, &face);
+        }
+
+        Ok(cube_texture)
+    }
+
+    /// Normalized direction pointing at the center of a texel of a face
+    pub fn face_direction(layer: u32, face_size: u32, x: u32, y: u32) -> [f32; 3] {
+        let s = 2.0 * (x as f32 + 0.5) / face_size as f32 - 1.0;
+        let t = 2.0 * (y as f32 + 0.5) / face_size as f32 - 1.0;
+
+        let [x, y, z] = match layer {
+            0 => [1.0, -t, -s],
+            1 => [-1.0, -t, s],
+            2 => [s, 1.0, t],
+            3 => [s, -1.0, -t],
+            4 => [s, -t, 1.0],
+            _ => [-s, -t, -1.0],
+        };
+        let length = (x * x + y * y + z * z).sqrt();
+
+        [x / length, y / length, z / length]
+    }
+
+    /// Wraps horizontally, clamps vertically
+    fn sample_bilinear(image: &Rgba32FImage, u: f32, v: f32) -> [f32; 4] {
+        let (width, height) = image.dimensions();
+
+        let x = u * width as f32 - 0.5;
+        let y = (v * height as f32 - 0.5).clamp(0.0, (height - 1) as f32);
+        let (x0, y0) = (x.floor(), y.floor());
+        let (fx, fy) = (x - x0, y - y0);
+
+        let pixel = |x: f32, y: f32| {
+            let x = (x as i64).rem_euclid(width as i64) as u32;
+            let y = (y as u32).min(height - 1);
+            image.get_pixel(x, y).0
+        };
+        let (a, b) = (pixel(x0, y0), pixel(x0 + 1.0, y0));
+        let (c, d) = (pixel(x0, y0 + 1.0), pixel(x0 + 1.0, y0 + 1.0));
+
+        let mut result = [0.0; 4];
+        for i in 0..4 {
+            let top = a[i] + (b[i] - a[i]) * fx;
+            let bottom = c[i] + (d[i] - c[i]) * fx;
+            result[i] = top + (bottom - top) * fy;
+        }
+
+        result
+    }
+
+    /// HDR images are already linear, everything else is assumed to be sRGB
+    fn to_linear(image: &DynamicImage) -> Rgba32FImage {
+        let mut rgba = image.to_rgba32f();
+
+        if !matches!(
+            image,
+            DynamicImage::ImageRgb32F(_) | DynamicImage::ImageRgba32F(_)
+        ) {
+            rgba.pixels_mut().for_each(|pixel| {
+                for channel in &mut pixel.0[..3] {
+                    *channel = srgb_to_linear(*channel);
+                }
+            });
+        }
+
+        rgba
+    }
+
+    fn write_face(&self, logical_device: &LogicalDevice, layer: u32, face: &Rgba32FImage) {
+        let bytes = face
+            .as_raw()
+            .iter()
+            .flat_map(|x| f16::from_f32(*x).to_le_bytes())
+            .collect::<Vec<_>>();
+
+        logical_device.queue().write_texture(
+            ImageCopyTexture {
+                aspect: TextureAspect::All,
+                texture: self.texture(),
+                mip_level: 0,
+                origin: Origin3d {
+                    x: 0,
+                    y: 0,
+                    z: layer,
+                },
+            },
+            &bytes,
+            ImageDataLayout {
+                offset: 0,
+                // 4 channels with 2 bytes each
+                bytes_per_row: Some(8 * self.face_size),
+                rows_per_image: Some(self.face_size),
+            },
+            Extent3d {
+                width: self.face_size,
+                height: self.face_size,
+                depth_or_array_layers: 1,
+            },
+        );
+    }
+
+    pub fn face_size(&self) -> u32 {
+        self.face_size
+    }
+}
+
+impl TTexture for CubeTexture {
+    fn texture(&self) -> &Texture {
+        self.internal_texture.texture()
+    }
+
+    /// View of all six faces as a cube
+    fn view(&self) -> &TextureView {
+        &self.cube_view
+    }
+
+    fn sampler(&self) -> &Sampler {
+        self.internal_texture.sampler()
+    }
+}
@@ -98,6 +98,50 @@ impl DepthTexture {
             )?,
         })
     }
+
+    /// Creates a depth texture matching a multisampled color attachment
+    pub fn from_empty_multisampled(
+        logical_device: &LogicalDevice,
+        size: Extent3d,
+        sample_count: u32,
+        label: Option<&str>,
+    ) -> EngineResult<Self> {
+        Ok(Self {
+            internal_texture: AbstractTexture::from_empty_multisampled(
+                logical_device,
+                size,
+                Self::TEXTURE_FORMAT,
+                &Self::SAMPLER_DESCRIPTOR,
+                TextureUsages::RENDER_ATTACHMENT,
+                sample_count,
+                label,
+            )?,
+        })
+    }
+
+    /// Creates a square depth texture with multiple layers which can be
+    /// rendered into layer by layer and sampled afterwards (e.g. shadow maps).
+    pub fn from_empty_layered(
+        logical_device: &LogicalDevice,
+        resolution: u32,
+        layers: u32,
+        label: Option<&str>,
+    ) -> EngineResult<Self> {
+        Ok(Self {
+            internal_texture: AbstractTexture::from_empty(
+                logical_device,
+                Extent3d {
+                    width: resolution,
+                    height: resolution,
+                    depth_or_array_layers: layers,
+                },
+                Self::TEXTURE_FORMAT,
+                &Self::SAMPLER_DESCRIPTOR,
+                TextureUsages::RENDER_ATTACHMENT | TextureUsages::TEXTURE_BINDING,
+                label,
+            )?,
+        })
+    }
 }
 
 impl TTexture for DepthTexture {
@@ -1,6 +1,6 @@
 use std::path::Path;
 
-use image::DynamicImage;
+use image::{DynamicImage, Rgba, RgbaImage};
 use wgpu::{
     AddressMode, Extent3d, FilterMode, Sampler, SamplerDescriptor, Texture, TextureFormat,
     TextureUsages, TextureView,
@@ -51,6 +51,21 @@ impl DiffuseTexture {
         })
     }
 
+    /// A single pixel of the given color
+    pub fn from_color(
+        logical_device: &LogicalDevice,
+        color: [u8; 4],
+        special_format: Option<TextureFormat>,
+        label: Option<&str>,
+    ) -> EngineResult<Self> {
+        Self::from_image(
+            logical_device,
+            &DynamicImage::ImageRgba8(RgbaImage::from_pixel(1, 1, Rgba(color))),
+            special_format,
+            label,
+        )
+    }
+
     pub fn from_path<P>(
         logical_device: &LogicalDevice,
         file_path: P,
@@ -12,3 +12,12 @@ pub use normal_texture::*;
 
 mod depth_texture;
 pub use depth_texture::*;
+
+mod multisample_texture;
+pub use multisample_texture::*;
+
+mod render_target_texture;
+pub use render_target_texture::*;
+
+mod cube_texture;
+pub use cube_texture::*;
@@ -0,0 +1,65 @@
+use wgpu::{
+    AddressMode, Extent3d, FilterMode, Sampler, SamplerDescriptor, Texture, TextureFormat,
+    TextureUsages, TextureView,
+};
+
+use crate::engine::{EngineResult, LogicalDevice};
+
+use super::{AbstractTexture, TTexture};
+
+/// Multisampled color attachment, resolved into a single sampled texture
+/// (e.g. the surface texture) at the end of a render pass.
+pub struct MultisampleTexture {
+    internal_texture: AbstractTexture,
+}
+
+impl MultisampleTexture {
+    pub const SAMPLER_DESCRIPTOR: SamplerDescriptor<'static> = SamplerDescriptor {
+        label: Some("Multisample Texture Sampler Descriptor"),
+        address_mode_u: AddressMode::ClampToEdge,
+        address_mode_v: AddressMode::ClampToEdge,
+        address_mode_w: AddressMode::ClampToEdge,
+        mag_filter: FilterMode::Linear,
+        min_filter: FilterMode::Linear,
+        mipmap_filter: FilterMode::Nearest,
+        lod_min_clamp: 0.0,  // Default
+        lod_max_clamp: 32.0, // Default
+        compare: None,       // Default
+        anisotropy_clamp: 1, // Default
+        border_color: None,  // Default
+    };
+
+    pub fn from_empty(
+        logical_device: &LogicalDevice,
+        size: Extent3d,
+        format: TextureFormat,
+        sample_count: u32,
+        label: Option<&str>,
+    ) -> EngineResult<Self> {
+        Ok(Self {
+            internal_texture: AbstractTexture::from_empty_multisampled(
+                logical_device,
+                size,
+                format,
+                &Self::SAMPLER_DESCRIPTOR,
+                TextureUsages::RENDER_ATTACHMENT,
+                sample_count,
+                label,
+            )?,
+        })
+    }
+}
+
+impl TTexture for MultisampleTexture {
+    fn texture(&self) -> &Texture {
+        self.internal_texture.texture()
+    }
+
+    fn view(&self) -> &TextureView {
+        self.internal_texture.view()
+    }
+
+    fn sampler(&self) -> &Sampler {
+        self.internal_texture.sampler()
+    }
+}
@@ -0,0 +1,92 @@
+use wgpu::{
+    AddressMode, Extent3d, FilterMode, Sampler, SamplerDescriptor, Texture, TextureDescriptor,
+    TextureDimension, TextureFormat, TextureUsages, TextureView, TextureViewDescriptor,
+};
+
+use crate::engine::{EngineResult, LogicalDevice, TextureHelper};
+
+use super::{AbstractTexture, TTexture};
+
+/// Offscreen color texture which gets rendered into and sampled
+/// afterwards (e.g. by post-processing passes).
+pub struct RenderTargetTexture {
+    internal_texture: AbstractTexture,
+}
+
+impl RenderTargetTexture {
+    pub const SAMPLER_DESCRIPTOR: SamplerDescriptor<'static> = SamplerDescriptor {
+        label: Some("Render Target Texture Sampler Descriptor"),
+        address_mode_u: AddressMode::ClampToEdge,
+        address_mode_v: AddressMode::ClampToEdge,
+        address_mode_w: AddressMode::ClampToEdge,
+        mag_filter: FilterMode::Linear,
+        min_filter: FilterMode::Linear,
+        mipmap_filter: FilterMode::Linear,
+        lod_min_clamp: 0.0,  // Default
+        lod_max_clamp: 32.0, // Default
+        compare: None,       // Default
+        anisotropy_clamp: 1, // Default
+        border_color: None,  // Default
+    };
+
+    pub fn from_empty(
+        logical_device: &LogicalDevice,
+        size: Extent3d,
+        format: TextureFormat,
+        label: Option<&str>,
+    ) -> EngineResult<Self> {
+        Self::from_empty_mipmapped(logical_device, size, format, 1, label)
+    }
+
+    /// Creates a render target with multiple mip levels.
+    /// Each mip level can be rendered into separately, see [`Self::mip_view`].
+    pub fn from_empty_mipmapped(
+        logical_device: &LogicalDevice,
+        size: Extent3d,
+        format: TextureFormat,
+        mip_level_count: u32,
+        label: Option<&str>,
+    ) -> EngineResult<Self> {
+        Ok(Self {
+            internal_texture: AbstractTexture::from_descriptor(
+                logical_device,
+                &TextureDescriptor {
+                    label,
+                    size,
+                    mip_level_count,
+                    sample_count: 1,
+                    dimension: TextureDimension::D2,
+                    format,
+                    usage: TextureUsages::RENDER_ATTACHMENT | TextureUsages::TEXTURE_BINDING,
+                    view_formats: &[],
+                },
+                &Self::SAMPLER_DESCRIPTOR,
+            )?,
+        })
+    }
+
+    /// Makes a view of a single mip level
+    pub fn mip_view(&self, mip_level: u32) -> TextureView {
+        self.texture()
+            .make_texture_view_descriptor(&TextureViewDescriptor {
+                label: Some("Render Target Mip View"),
+                base_mip_level: mip_level,
+                mip_level_count: Some(1),
+                ..Default::default()
+            })
+    }
+}
+
+impl TTexture for RenderTargetTexture {
+    fn texture(&self) -> &Texture {
+        self.internal_texture.texture()
+    }
+
+    fn view(&self) -> &TextureView {
+        self.internal_texture.view()
+    }
+
+    fn sampler(&self) -> &Sampler {
+        self.internal_texture.sampler()
+    }
+}
@@ -8,6 +8,7 @@ pub trait TVertex {
     fn normal_coordinates(&self) -> [f32; 3];
     fn tangent(&self) -> [f32; 3];
     fn bitangent(&self) -> [f32; 3];
+    fn color(&self) -> [f32; 4];
 
     fn descriptor<T>() -> VertexBufferLayout<'static>
     where
@@ -43,6 +44,12 @@ pub trait TVertex {
                     shader_location: 4,
                     format: VertexFormat::Float32x3,
                 },
+                // Locations 5 to 13 are taken by the instance
+                VertexAttribute {
+                    offset: size_of::<[f32; 14]>() as BufferAddress,
+                    shader_location: 14,
+                    format: VertexFormat::Float32x4,
+                },
             ],
         }
     }
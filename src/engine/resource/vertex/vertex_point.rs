@@ -10,6 +10,8 @@ pub struct VertexPoint {
     pub normal_coordinates: [f32; 3],
     pub tangent: [f32; 3],
     pub bitangent: [f32; 3],
+    /// Linear RGBA, only shown by materials using vertex colors
+    pub color: [f32; 4],
 }
 
 impl TVertex for VertexPoint {
@@ -32,4 +34,8 @@ impl TVertex for VertexPoint {
     fn bitangent(&self) -> [f32; 3] {
         self.bitangent
     }
+
+    fn color(&self) -> [f32; 4] {
+        self.color
+    }
 }
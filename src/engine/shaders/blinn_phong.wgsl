@@ -0,0 +1,79 @@
+// Blinn-Phong shading of the `StandardMaterial`.
+
+#include "common/lighting.wgsl"
+
+struct Material {
+    specular_strength: f32,
+    shininess: f32,
+    // Lower diffuse alphas get discarded if `ALPHA_MASK` is defined
+    alpha_cutoff: f32,
+}
+
+struct Surface {
+    normal: vec3<f32>,
+    view_dir: vec3<f32>,
+}
+
+@group(0) @binding(0)
+var t_diffuse: texture_2d<f32>;
+
+@group(0) @binding(1)
+var s_diffuse: sampler;
+
+@group(0) @binding(2)
+var t_normal: texture_2d<f32>;
+
+@group(0) @binding(3)
+var s_normal: sampler;
+
+@group(0) @binding(4)
+var<uniform> material: Material;
+
+// Blinn-Phong diffuse + specular of a single light.
+// `light_dir` points from the fragment towards the light.
+fn shade_light(
+    light_color: vec3<f32>,
+    light_dir: vec3<f32>,
+    surface: Surface,
+) -> vec3<f32> {
+    let half_dir = normalize(surface.view_dir + light_dir);
+
+    let diffuse_strength = max(dot(surface.normal, light_dir), 0.0);
+    let specular_strength = material.specular_strength
+        * pow(max(dot(surface.normal, half_dir), 0.0), material.shininess);
+
+    return light_color * (diffuse_strength + specular_strength);
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    // Get texel from texture
+    let object_diffuse_map = textureSample(t_diffuse, s_diffuse, in.texture_coordinates);
+    let object_normal_map = textureSample(t_normal, s_normal, in.texture_coordinates);
+
+    let object_color = object_diffuse_map * in.tint;
+    #ifdef ALPHA_MASK
+    if object_color.a < material.alpha_cutoff {
+        discard;
+    }
+    #endif
+
+    var surface: Surface;
+    surface.normal = surface_normal(in, object_normal_map.xyz * 2.0 - 1.0);
+    surface.view_dir = normalize(camera.position.xyz - in.world_position);
+
+    // Ambient Light, from the environment map if there is one
+    var ambient_color = ambient_light.color * ambient_light.strength;
+    if environment.enabled != 0u {
+        // Blinn-Phong shininess to an approximate roughness
+        let roughness = clamp(sqrt(2.0 / (material.shininess + 2.0)), 0.0, 1.0);
+        let environment_color = environment_lighting(surface.normal, surface.view_dir, F0, roughness);
+        ambient_color = environment_color.diffuse
+            + environment_color.specular * material.specular_strength;
+    }
+
+    let light_color = direct_lighting(in, surface);
+
+    let result = (ambient_color + light_color) * object_color.xyz;
+    return vec4<f32>(result, object_color.a);
+}
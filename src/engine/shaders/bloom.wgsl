@@ -0,0 +1,91 @@
+// Bloom based on the "Next Generation Post Processing in Call of Duty:
+// Advanced Warfare" presentation:
+// The bright parts of the image get downsampled into a mip chain with a
+// 13-tap filter and then upsampled back up with a 3x3 tent filter, adding
+// each level onto the next bigger one.
+
+#include "common/post_process.wgsl"
+#include "common/post_process_settings.wgsl"
+
+// --- Bindings ---
+
+@group(1) @binding(0)
+var<uniform> settings: PostProcessSettings;
+
+// --- Filters ---
+
+// 13-tap downsample filter, `source_texture` must be twice the target size
+fn downsample(uv: vec2<f32>) -> vec3<f32> {
+    let texel = 1.0 / vec2<f32>(textureDimensions(source_texture));
+
+    let a = textureSampleLevel(source_texture, source_sampler, uv + texel * vec2<f32>(-2.0, -2.0), 0.0).rgb;
+    let b = textureSampleLevel(source_texture, source_sampler, uv + texel * vec2<f32>(0.0, -2.0), 0.0).rgb;
+    let c = textureSampleLevel(source_texture, source_sampler, uv + texel * vec2<f32>(2.0, -2.0), 0.0).rgb;
+    let d = textureSampleLevel(source_texture, source_sampler, uv + texel * vec2<f32>(-2.0, 0.0), 0.0).rgb;
+    let e = textureSampleLevel(source_texture, source_sampler, uv, 0.0).rgb;
+    let f = textureSampleLevel(source_texture, source_sampler, uv + texel * vec2<f32>(2.0, 0.0), 0.0).rgb;
+    let g = textureSampleLevel(source_texture, source_sampler, uv + texel * vec2<f32>(-2.0, 2.0), 0.0).rgb;
+    let h = textureSampleLevel(source_texture, source_sampler, uv + texel * vec2<f32>(0.0, 2.0), 0.0).rgb;
+    let i = textureSampleLevel(source_texture, source_sampler, uv + texel * vec2<f32>(2.0, 2.0), 0.0).rgb;
+    let j = textureSampleLevel(source_texture, source_sampler, uv + texel * vec2<f32>(-1.0, -1.0), 0.0).rgb;
+    let k = textureSampleLevel(source_texture, source_sampler, uv + texel * vec2<f32>(1.0, -1.0), 0.0).rgb;
+    let l = textureSampleLevel(source_texture, source_sampler, uv + texel * vec2<f32>(-1.0, 1.0), 0.0).rgb;
+    let m = textureSampleLevel(source_texture, source_sampler, uv + texel * vec2<f32>(1.0, 1.0), 0.0).rgb;
+
+    return e * 0.125
+        + (a + c + g + i) * 0.03125
+        + (b + d + f + h) * 0.0625
+        + (j + k + l + m) * 0.125;
+}
+
+// 3x3 tent upsample filter, `source_texture` is half the target size
+fn upsample(uv: vec2<f32>) -> vec3<f32> {
+    let texel = 1.0 / vec2<f32>(textureDimensions(source_texture));
+
+    let a = textureSampleLevel(source_texture, source_sampler, uv + texel * vec2<f32>(-1.0, -1.0), 0.0).rgb;
+    let b = textureSampleLevel(source_texture, source_sampler, uv + texel * vec2<f32>(0.0, -1.0), 0.0).rgb;
+    let c = textureSampleLevel(source_texture, source_sampler, uv + texel * vec2<f32>(1.0, -1.0), 0.0).rgb;
+    let d = textureSampleLevel(source_texture, source_sampler, uv + texel * vec2<f32>(-1.0, 0.0), 0.0).rgb;
+    let e = textureSampleLevel(source_texture, source_sampler, uv, 0.0).rgb;
+    let f = textureSampleLevel(source_texture, source_sampler, uv + texel * vec2<f32>(1.0, 0.0), 0.0).rgb;
+    let g = textureSampleLevel(source_texture, source_sampler, uv + texel * vec2<f32>(-1.0, 1.0), 0.0).rgb;
+    let h = textureSampleLevel(source_texture, source_sampler, uv + texel * vec2<f32>(0.0, 1.0), 0.0).rgb;
+    let i = textureSampleLevel(source_texture, source_sampler, uv + texel * vec2<f32>(1.0, 1.0), 0.0).rgb;
+
+    return (e * 4.0 + (b + d + f + h) * 2.0 + (a + c + g + i)) / 16.0;
+}
+
+// Soft knee threshold, keeps everything above the threshold and fades
+// in the parts within `bloom_knee` below it
+fn threshold(color: vec3<f32>) -> vec3<f32> {
+    let brightness = max(color.r, max(color.g, color.b));
+    let knee = max(settings.bloom_knee, 0.00001);
+
+    var soft = clamp(brightness - settings.bloom_threshold + knee, 0.0, 2.0 * knee);
+    soft = soft * soft / (4.0 * knee);
+
+    let contribution = max(soft, brightness - settings.bloom_threshold) / max(brightness, 0.00001);
+    return color * contribution;
+}
+
+// --- Passes ---
+
+// Scene -> first mip level
+@fragment
+fn fs_prefilter(in: VertexOutput) -> @location(0) vec4<f32> {
+    // Guard against single very bright pixels (NaN/Inf) spreading everywhere
+    let color = min(downsample(in.uv), vec3<f32>(65000.0));
+    return vec4<f32>(threshold(color), 1.0);
+}
+
+// Mip level N -> N + 1
+@fragment
+fn fs_downsample(in: VertexOutput) -> @location(0) vec4<f32> {
+    return vec4<f32>(downsample(in.uv), 1.0);
+}
+
+// Mip level N + 1 -> N, additively blended
+@fragment
+fn fs_upsample(in: VertexOutput) -> @location(0) vec4<f32> {
+    return vec4<f32>(upsample(in.uv), 1.0);
+}
@@ -0,0 +1,239 @@
+// Lighting of lit materials.
+// The material shader has to define a `Surface` struct and
+// `shade_light` (see `direct_lighting`).
+
+#include "common/mesh.wgsl"
+
+// --- Clusters ---
+
+// Index of the cluster a fragment lies in.
+// Must match the cluster layout of `light_clustering.wgsl`.
+fn cluster_index(frag_coord: vec2<f32>, world_position: vec3<f32>) -> u32 {
+    let grid_size = clustering.grid_size;
+
+    let tile = vec2<u32>(clamp(
+        frag_coord / clustering.screen_size * vec2<f32>(grid_size.xy),
+        vec2<f32>(0.0),
+        vec2<f32>(grid_size.xy - 1u),
+    ));
+
+    let depth = -(clustering.view_matrix * vec4<f32>(world_position, 1.0)).z;
+    let slice = log(max(depth, clustering.z_near) / clustering.z_near)
+        / log(clustering.z_far / clustering.z_near)
+        * f32(grid_size.z);
+    let z = u32(clamp(slice, 0.0, f32(grid_size.z - 1u)));
+
+    return tile.x + tile.y * grid_size.x + z * grid_size.x * grid_size.y;
+}
+
+// --- Shadows ---
+
+// Returns 1.0 if the fragment is fully lit and 0.0 if it's fully in shadow.
+// Filtered with PCF.
+fn shadow_factor(
+    shadow_layer: i32,
+    world_position: vec3<f32>,
+    world_normal: vec3<f32>,
+) -> f32 {
+    if shadow_layer < 0 {
+        return 1.0;
+    }
+
+    let position = world_position + world_normal * shadow_casters.normal_bias;
+    let clip_position = shadow_casters.view_projection_matrices[shadow_layer] * vec4<f32>(position, 1.0);
+    if clip_position.w <= 0.0 {
+        return 1.0;
+    }
+
+    // Everything outside of the shadow map is considered lit
+    let ndc_position = clip_position.xyz / clip_position.w;
+    if any(abs(ndc_position.xy) > vec2<f32>(1.0)) || ndc_position.z > 1.0 {
+        return 1.0;
+    }
+
+    let uv = ndc_position.xy * vec2<f32>(0.5, -0.5) + 0.5;
+    let depth = ndc_position.z - shadow_casters.depth_bias;
+    let radius = shadow_casters.pcf_radius;
+
+    var lit = 0.0;
+    var samples = 0.0;
+    for (var x = -radius; x <= radius; x++) {
+        for (var y = -radius; y <= radius; y++) {
+            let offset = vec2<f32>(f32(x), f32(y)) * shadow_casters.texel_size;
+            lit += textureSampleCompareLevel(t_shadow_maps, s_shadow_maps, uv + offset, shadow_layer, depth);
+            samples += 1.0;
+        }
+    }
+
+    return lit / samples;
+}
+
+// Point lights use six layers, one per cube face (+X, -X, +Y, -Y, +Z, -Z).
+// Picks the layer of the face the fragment lies in.
+fn point_light_shadow_layer(shadow_layer: i32, light_to_fragment: vec3<f32>) -> i32 {
+    if shadow_layer < 0 {
+        return -1;
+    }
+
+    let distance = abs(light_to_fragment);
+    if distance.x >= distance.y && distance.x >= distance.z {
+        return shadow_layer + select(1, 0, light_to_fragment.x > 0.0);
+    }
+    if distance.y >= distance.z {
+        return shadow_layer + select(3, 2, light_to_fragment.y > 0.0);
+    }
+    return shadow_layer + select(5, 4, light_to_fragment.z > 0.0);
+}
+
+// --- Lighting ---
+
+// Inverse-square falloff, smoothly windowed to reach zero at `range`
+fn attenuation(distance: f32, range: f32) -> f32 {
+    let window = saturate(1.0 - pow(distance / range, 4.0));
+    return window * window / max(distance * distance, 0.0001);
+}
+
+// --- Environment ---
+
+const PI = 3.14159265359;
+
+// Reflectance of dielectrics at normal incidence
+const F0 = vec3<f32>(0.04, 0.04, 0.04);
+
+struct EnvironmentLighting {
+    // Has yet to be scaled by the albedo
+    diffuse: vec3<f32>,
+    specular: vec3<f32>,
+}
+
+fn fresnel_schlick_roughness(cos_theta: f32, f0: vec3<f32>, roughness: f32) -> vec3<f32> {
+    return f0 + (max(vec3<f32>(1.0 - roughness), f0) - f0)
+        * pow(clamp(1.0 - cos_theta, 0.0, 1.0), 5.0);
+}
+
+// Image-based ambient lighting from the environment map
+fn environment_lighting(
+    normal: vec3<f32>,
+    view_dir: vec3<f32>,
+    f0: vec3<f32>,
+    roughness: f32,
+) -> EnvironmentLighting {
+    let n_dot_v = max(dot(normal, view_dir), 0.0);
+    let fresnel = fresnel_schlick_roughness(n_dot_v, f0, roughness);
+
+    let irradiance = textureSample(t_irradiance, s_environment, normal).rgb;
+
+    let reflection = reflect(-view_dir, normal);
+    let prefiltered = textureSampleLevel(
+        t_prefiltered,
+        s_environment,
+        reflection,
+        roughness * environment.max_mip_level,
+    ).rgb;
+    let brdf = textureSample(t_brdf_lut, s_environment, vec2<f32>(n_dot_v, roughness)).rg;
+
+    var out: EnvironmentLighting;
+    out.diffuse = (1.0 - fresnel) * irradiance * environment.intensity;
+    out.specular = prefiltered * (fresnel * brdf.x + brdf.y) * environment.intensity;
+    return out;
+}
+
+// Sums up the point, directional and spot lights reaching the fragment.
+// Each light is shaded by the `shade_light` function of the material shader.
+fn direct_lighting(in: VertexOutput, surface: Surface) -> vec3<f32> {
+    var light_color = vec3<f32>(0.0);
+
+    // Only the point & spot lights of the fragments cluster are shaded
+    let cluster = cluster_index(in.clip_position.xy, in.world_position);
+    let cluster_light_count = cluster_lights[cluster];
+    let first_slot = cluster * clustering.max_lights_per_cluster;
+
+    // Point Lights
+    for (var i = 0u; i < cluster_light_count.point_count; i++) {
+        let point_light = point_lights.lights[light_indices[first_slot + i]];
+        if point_light.enabled == 0u {
+            continue;
+        }
+
+        let light_vector = point_light.position - in.world_position;
+        let distance = length(light_vector);
+        if distance >= point_light.range {
+            continue;
+        }
+
+        let light_dir = light_vector / distance;
+        let intensity = point_light.strength * attenuation(distance, point_light.range);
+        let shadow_layer = point_light_shadow_layer(
+            point_light.shadow_layer,
+            in.world_position - point_light.position,
+        );
+        let shadow = shadow_factor(shadow_layer, in.world_position, normalize(in.world_normal));
+
+        light_color += shadow * shade_light(
+            point_light.color * intensity,
+            light_dir,
+            surface,
+        );
+    }
+
+    // Directional Lights
+    for (var i = 0u; i < directional_lights.count; i++) {
+        let directional_light = directional_lights.lights[i];
+        if directional_light.enabled == 0u {
+            continue;
+        }
+
+        let light_dir = -normalize(directional_light.direction);
+        let shadow = shadow_factor(
+            directional_light.shadow_layer,
+            in.world_position,
+            normalize(in.world_normal),
+        );
+
+        light_color += shadow * shade_light(
+            directional_light.color * directional_light.strength,
+            light_dir,
+            surface,
+        );
+    }
+
+    // Spot Lights
+    for (var i = 0u; i < cluster_light_count.spot_count; i++) {
+        let spot_light = spot_lights.lights[light_indices[first_slot + cluster_light_count.point_count + i]];
+        if spot_light.enabled == 0u {
+            continue;
+        }
+
+        let light_vector = spot_light.position - in.world_position;
+        let distance = length(light_vector);
+        if distance >= spot_light.range {
+            continue;
+        }
+
+        let light_dir = light_vector / distance;
+
+        // Full strength inside the inner cone, fading out towards the outer cone
+        let theta = dot(-light_dir, normalize(spot_light.direction));
+        let cone_factor = smoothstep(spot_light.outer_cone_cos, spot_light.inner_cone_cos, theta);
+        if cone_factor <= 0.0 {
+            continue;
+        }
+
+        let shadow = shadow_factor(
+            spot_light.shadow_layer,
+            in.world_position,
+            normalize(in.world_normal),
+        );
+
+        light_color += shadow * shade_light(
+            spot_light.color
+                * spot_light.strength
+                * attenuation(distance, spot_light.range)
+                * cone_factor,
+            light_dir,
+            surface,
+        );
+    }
+
+    return light_color;
+}
@@ -0,0 +1,45 @@
+// Light & light clustering structures, shared by the main pass and the light clustering.
+// `PointLight`, `DirectionalLight` & `SpotLight` are generated from `UPointLight` etc.
+// of the Rust side, see `GeneratedShaders`.
+// `enabled` is 0 or 1, a `shadow_layer` of -1 means no shadows.
+
+#include "generated/lights.wgsl"
+
+struct PointLights {
+    count: u32,
+    lights: array<PointLight>,
+}
+
+struct DirectionalLights {
+    count: u32,
+    lights: array<DirectionalLight>,
+}
+
+struct SpotLights {
+    count: u32,
+    lights: array<SpotLight>,
+}
+
+struct LightClusteringUniform {
+    view_matrix: mat4x4<f32>,
+    inverse_projection_matrix: mat4x4<f32>,
+    screen_size: vec2<f32>,
+    z_near: f32,
+    z_far: f32,
+    grid_size: vec3<u32>,
+    max_lights_per_cluster: u32,
+}
+
+struct ClusterLights {
+    point_count: u32,
+    spot_count: u32,
+}
+
+struct ShadowCasters {
+    depth_bias: f32,
+    normal_bias: f32,
+    pcf_radius: i32,
+    texel_size: f32,
+    // One per shadow map layer
+    view_projection_matrices: array<mat4x4<f32>>,
+}
@@ -0,0 +1,152 @@
+// Vertex shader & bindings of groups 1 to 6, shared by all material shaders.
+// A material shader includes this (or `common/lighting.wgsl`) and defines
+// its group 0 bindings and the `fs_main` fragment entry point.
+
+#include "common/lights.wgsl"
+#include "common/vertex.wgsl"
+
+// --- Structures ---
+
+struct CameraUniform {
+    position: vec4<f32>,
+    view_projection_matrix: mat4x4<f32>,
+}
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) texture_coordinates: vec2<f32>,
+    @location(1) world_position: vec3<f32>,
+    @location(2) world_normal: vec3<f32>,
+    @location(3) world_tangent: vec3<f32>,
+    @location(4) world_bitangent: vec3<f32>,
+    @location(5) tint: vec4<f32>,
+    @location(6) user_data: vec4<f32>,
+    @location(7) color: vec4<f32>,
+};
+
+struct AmbientLight {
+    color: vec3<f32>,
+    strength: f32,
+};
+
+struct Environment {
+    intensity: f32,
+    // Mip level of the prefiltered map for a roughness of 1.0
+    max_mip_level: f32,
+    // 0 = flat ambient light instead
+    enabled: u32,
+}
+
+// --- Bindings ---
+
+@group(1) @binding(0)
+var<uniform> camera: CameraUniform;
+
+@group(2) @binding(0) 
+var<uniform> ambient_light: AmbientLight;
+
+// Point Lights
+@group(3) @binding(0)
+var<storage, read> point_lights: PointLights;
+
+// Directional Lights
+@group(3) @binding(1)
+var<storage, read> directional_lights: DirectionalLights;
+
+// Spot Lights
+@group(3) @binding(2)
+var<storage, read> spot_lights: SpotLights;
+
+// Shadow Maps
+@group(4) @binding(0)
+var t_shadow_maps: texture_depth_2d_array;
+
+@group(4) @binding(1)
+var s_shadow_maps: sampler_comparison;
+
+@group(4) @binding(2)
+var<storage, read> shadow_casters: ShadowCasters;
+
+// Light Clusters
+@group(5) @binding(0)
+var<uniform> clustering: LightClusteringUniform;
+
+@group(5) @binding(1)
+var<storage, read> cluster_lights: array<ClusterLights>;
+
+// Point light indices first, followed by spot light indices
+@group(5) @binding(2)
+var<storage, read> light_indices: array<u32>;
+
+// Environment Map
+@group(6) @binding(0)
+var t_irradiance: texture_cube<f32>;
+
+@group(6) @binding(1)
+var t_prefiltered: texture_cube<f32>;
+
+@group(6) @binding(2)
+var t_brdf_lut: texture_2d<f32>;
+
+@group(6) @binding(3)
+var s_environment: sampler;
+
+@group(6) @binding(4)
+var<uniform> environment: Environment;
+
+// --- Vertex ---
+
+@vertex
+fn vs_main(
+    vertex_point: VertexPoint,
+    instance: InstanceUniform,
+) -> VertexOutput {
+    let model_space_matrix = mat4x4<f32>(
+        instance.model_space_matrix_0,
+        instance.model_space_matrix_1,
+        instance.model_space_matrix_2,
+        instance.model_space_matrix_3,
+    );
+
+    let normal_matrix = mat3x3<f32>(
+        instance.normal_space_matrix_0,
+        instance.normal_space_matrix_1,
+        instance.normal_space_matrix_2,
+    );
+
+    // Tangent & bitangent lie along the surface, so unlike the normal they
+    // follow the model matrix itself
+    let tangent_matrix = mat3x3<f32>(
+        model_space_matrix[0].xyz,
+        model_space_matrix[1].xyz,
+        model_space_matrix[2].xyz,
+    );
+
+    let world_position = model_space_matrix * vec4<f32>(vertex_point.position_coordinates, 1.0);
+
+    var out: VertexOutput;
+    out.clip_position = camera.view_projection_matrix * world_position;
+    out.texture_coordinates = vertex_point.texture_coordinates;
+    out.world_position = world_position.xyz;
+    // Lighting is done in world space, as the light count is only known
+    // in the fragment stage
+    out.world_normal = normalize(normal_matrix * vertex_point.normal_coordinates);
+    out.world_tangent = normalize(tangent_matrix * vertex_point.tangent);
+    out.world_bitangent = normalize(tangent_matrix * vertex_point.bitangent);
+    out.tint = instance.tint;
+    out.user_data = instance.user_data;
+    out.color = vertex_point.color;
+    return out;
+}
+
+// --- Fragment ---
+
+// Maps a normal from the normal map (tangent space) into world space
+fn surface_normal(in: VertexOutput, tangent_normal: vec3<f32>) -> vec3<f32> {
+    let tangent_matrix = mat3x3<f32>(
+        normalize(in.world_tangent),
+        normalize(in.world_bitangent),
+        normalize(in.world_normal),
+    );
+    return normalize(tangent_matrix * tangent_normal);
+}
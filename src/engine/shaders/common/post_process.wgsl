@@ -0,0 +1,44 @@
+// Shared by all post-processing passes.
+// Gets included before the fragment shader source of custom passes.
+
+// --- Structures ---
+
+struct PostProcessGlobals {
+    // Size of the screen in pixels
+    resolution: vec2<f32>,
+    // Seconds since the post-processor got created
+    time: f32,
+    frame: u32,
+}
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+}
+
+// --- Bindings ---
+
+// Output of the previous pass
+@group(0) @binding(0)
+var source_texture: texture_2d<f32>;
+
+@group(0) @binding(1)
+var source_sampler: sampler;
+
+@group(0) @binding(2)
+var<uniform> globals: PostProcessGlobals;
+
+// --- Vertex ---
+
+// Fullscreen triangle, no vertex buffers needed
+@vertex
+fn vs_main(@builtin(vertex_index) vertex_index: u32) -> VertexOutput {
+    let uv = vec2<f32>(f32((vertex_index << 1u) & 2u), f32(vertex_index & 2u));
+
+    var out: VertexOutput;
+    out.clip_position = vec4<f32>(uv * vec2<f32>(2.0, -2.0) + vec2<f32>(-1.0, 1.0), 0.0, 1.0);
+    out.uv = uv;
+    return out;
+}
+
+// --- Post-Processing Pass ---
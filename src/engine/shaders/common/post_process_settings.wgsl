@@ -0,0 +1,12 @@
+// Settings of the built-in bloom & tonemapping passes.
+
+// --- Structures ---
+
+struct PostProcessSettings {
+    exposure: f32,
+    bloom_threshold: f32,
+    bloom_knee: f32,
+    bloom_intensity: f32,
+    // 0 = None, 1 = Reinhard, 2 = ACES
+    tonemapper: u32,
+}
@@ -0,0 +1,25 @@
+// Vertex & instance buffer inputs of the standard meshes, see `VertexPoint`
+// & `StandardInstance` on the Rust side.
+
+// --- Structures ---
+
+struct VertexPoint {
+    @location(0) position_coordinates: vec3<f32>,
+    @location(1) texture_coordinates: vec2<f32>,
+    @location(2) normal_coordinates: vec3<f32>,
+    @location(3) tangent: vec3<f32>,
+    @location(4) bitangent: vec3<f32>,
+    @location(14) color: vec4<f32>,
+}
+
+struct InstanceUniform {
+    @location(5) model_space_matrix_0: vec4<f32>,
+    @location(6) model_space_matrix_1: vec4<f32>,
+    @location(7) model_space_matrix_2: vec4<f32>,
+    @location(8) model_space_matrix_3: vec4<f32>,
+    @location(9) normal_space_matrix_0: vec3<f32>,
+    @location(10) normal_space_matrix_1: vec3<f32>,
+    @location(11) normal_space_matrix_2: vec3<f32>,
+    @location(12) tint: vec4<f32>,
+    @location(13) user_data: vec4<f32>,
+}
@@ -0,0 +1,185 @@
+// Precomputes the image-based lighting maps of an environment map:
+// - Irradiance (diffuse) cubemap
+// - Prefiltered (specular) cubemap, one roughness per mip level
+// - BRDF integration lookup table (independent of the environment)
+//
+// Based on "Real Shading in Unreal Engine 4" by Brian Karis.
+
+// --- Structures ---
+
+struct BakeParameters {
+    roughness: f32,
+    sample_count: u32,
+    // Width & height of the output (mip level)
+    size: u32,
+    _padding: u32,
+}
+
+const PI: f32 = 3.14159265359;
+
+// --- Bindings ---
+
+@group(0) @binding(0)
+var source_texture: texture_cube<f32>;
+
+@group(0) @binding(1)
+var source_sampler: sampler;
+
+// Cube faces as layers, or a single layer for the BRDF lookup table
+@group(0) @binding(2)
+var output_texture: texture_storage_2d_array<rgba16float, write>;
+
+@group(0) @binding(3)
+var<uniform> parameters: BakeParameters;
+
+// --- Helpers ---
+
+// Must match `CubeTexture::face_direction`
+fn face_direction(face: u32, texel: vec2<u32>) -> vec3<f32> {
+    let st = (vec2<f32>(texel) + 0.5) / f32(parameters.size) * 2.0 - 1.0;
+    let s = st.x;
+    let t = st.y;
+
+    switch face {
+        case 0u: { return normalize(vec3<f32>(1.0, -t, -s)); }
+        case 1u: { return normalize(vec3<f32>(-1.0, -t, s)); }
+        case 2u: { return normalize(vec3<f32>(s, 1.0, t)); }
+        case 3u: { return normalize(vec3<f32>(s, -1.0, -t)); }
+        case 4u: { return normalize(vec3<f32>(s, -t, 1.0)); }
+        default: { return normalize(vec3<f32>(-s, -t, -1.0)); }
+    }
+}
+
+// Low-discrepancy sequence for evenly spread samples
+fn hammersley(i: u32, count: u32) -> vec2<f32> {
+    var bits = i;
+    bits = (bits << 16u) | (bits >> 16u);
+    bits = ((bits & 0x55555555u) << 1u) | ((bits & 0xAAAAAAAAu) >> 1u);
+    bits = ((bits & 0x33333333u) << 2u) | ((bits & 0xCCCCCCCCu) >> 2u);
+    bits = ((bits & 0x0F0F0F0Fu) << 4u) | ((bits & 0xF0F0F0F0u) >> 4u);
+    bits = ((bits & 0x00FF00FFu) << 8u) | ((bits & 0xFF00FF00u) >> 8u);
+    return vec2<f32>(f32(i) / f32(count), f32(bits) * 2.3283064365386963e-10);
+}
+
+// Half vector around `normal`, distributed like the GGX lobe of `roughness`
+fn importance_sample_ggx(xi: vec2<f32>, normal: vec3<f32>, roughness: f32) -> vec3<f32> {
+    let a = roughness * roughness;
+
+    let phi = 2.0 * PI * xi.x;
+    let cos_theta = sqrt((1.0 - xi.y) / (1.0 + (a * a - 1.0) * xi.y));
+    let sin_theta = sqrt(1.0 - cos_theta * cos_theta);
+    let h = vec3<f32>(cos(phi) * sin_theta, sin(phi) * sin_theta, cos_theta);
+
+    let up = select(vec3<f32>(0.0, 0.0, 1.0), vec3<f32>(1.0, 0.0, 0.0), abs(normal.z) > 0.999);
+    let tangent = normalize(cross(up, normal));
+    let bitangent = cross(normal, tangent);
+
+    return normalize(tangent * h.x + bitangent * h.y + normal * h.z);
+}
+
+fn geometry_schlick_ggx(n_dot_v: f32, roughness: f32) -> f32 {
+    // Remapped for IBL
+    let k = roughness * roughness / 2.0;
+    return n_dot_v / (n_dot_v * (1.0 - k) + k);
+}
+
+fn geometry_smith(n_dot_v: f32, n_dot_l: f32, roughness: f32) -> f32 {
+    return geometry_schlick_ggx(n_dot_v, roughness) * geometry_schlick_ggx(n_dot_l, roughness);
+}
+
+// --- Passes ---
+
+// Cosine weighted average of the hemisphere around each direction
+@compute @workgroup_size(8, 8, 1)
+fn cs_irradiance(@builtin(global_invocation_id) id: vec3<u32>) {
+    if id.x >= parameters.size || id.y >= parameters.size {
+        return;
+    }
+
+    let normal = face_direction(id.z, id.xy);
+    let up = select(vec3<f32>(0.0, 1.0, 0.0), vec3<f32>(1.0, 0.0, 0.0), abs(normal.y) > 0.999);
+    let right = normalize(cross(up, normal));
+    let tangent_up = cross(normal, right);
+
+    let sample_delta = 0.05;
+    var irradiance = vec3<f32>(0.0);
+    var samples = 0.0;
+    for (var phi = 0.0; phi < 2.0 * PI; phi += sample_delta) {
+        for (var theta = 0.0; theta < 0.5 * PI; theta += sample_delta) {
+            let tangent_sample = vec3<f32>(sin(theta) * cos(phi), sin(theta) * sin(phi), cos(theta));
+            let direction = tangent_sample.x * right + tangent_sample.y * tangent_up + tangent_sample.z * normal;
+
+            irradiance += textureSampleLevel(source_texture, source_sampler, direction, 0.0).rgb
+                * cos(theta) * sin(theta);
+            samples += 1.0;
+        }
+    }
+
+    textureStore(output_texture, id.xy, id.z, vec4<f32>(PI * irradiance / samples, 1.0));
+}
+
+// Environment convolved with the GGX lobe of `parameters.roughness`
+@compute @workgroup_size(8, 8, 1)
+fn cs_prefilter(@builtin(global_invocation_id) id: vec3<u32>) {
+    if id.x >= parameters.size || id.y >= parameters.size {
+        return;
+    }
+
+    // Assumes the view direction equals the reflection direction
+    let normal = face_direction(id.z, id.xy);
+    let view = normal;
+
+    var color = vec3<f32>(0.0);
+    var weight = 0.0;
+    for (var i = 0u; i < parameters.sample_count; i++) {
+        let xi = hammersley(i, parameters.sample_count);
+        let half_vector = importance_sample_ggx(xi, normal, parameters.roughness);
+        let light = normalize(2.0 * dot(view, half_vector) * half_vector - view);
+
+        let n_dot_l = dot(normal, light);
+        if n_dot_l > 0.0 {
+            color += textureSampleLevel(source_texture, source_sampler, light, 0.0).rgb * n_dot_l;
+            weight += n_dot_l;
+        }
+    }
+
+    textureStore(output_texture, id.xy, id.z, vec4<f32>(color / max(weight, 0.0001), 1.0));
+}
+
+// Scale (r) & bias (g) applied to F0 of the specular BRDF,
+// indexed by n_dot_v (x) and roughness (y)
+@compute @workgroup_size(8, 8, 1)
+fn cs_brdf(@builtin(global_invocation_id) id: vec3<u32>) {
+    if id.x >= parameters.size || id.y >= parameters.size {
+        return;
+    }
+
+    let n_dot_v = (f32(id.x) + 0.5) / f32(parameters.size);
+    let roughness = (f32(id.y) + 0.5) / f32(parameters.size);
+
+    let view = vec3<f32>(sqrt(1.0 - n_dot_v * n_dot_v), 0.0, n_dot_v);
+    let normal = vec3<f32>(0.0, 0.0, 1.0);
+
+    var scale = 0.0;
+    var bias = 0.0;
+    for (var i = 0u; i < parameters.sample_count; i++) {
+        let xi = hammersley(i, parameters.sample_count);
+        let half_vector = importance_sample_ggx(xi, normal, roughness);
+        let light = normalize(2.0 * dot(view, half_vector) * half_vector - view);
+
+        let n_dot_l = max(light.z, 0.0);
+        let n_dot_h = max(half_vector.z, 0.0);
+        let v_dot_h = max(dot(view, half_vector), 0.0);
+
+        if n_dot_l > 0.0 {
+            let visibility = geometry_smith(n_dot_v, n_dot_l, roughness) * v_dot_h / (n_dot_h * n_dot_v);
+            let fresnel = pow(1.0 - v_dot_h, 5.0);
+
+            scale += (1.0 - fresnel) * visibility;
+            bias += fresnel * visibility;
+        }
+    }
+
+    let sample_count = f32(parameters.sample_count);
+    textureStore(output_texture, id.xy, 0, vec4<f32>(scale / sample_count, bias / sample_count, 0.0, 1.0));
+}
@@ -0,0 +1,71 @@
+// Fast approximate anti-aliasing, based on FXAA by Timothy Lottes.
+// Finds edges by the local luma contrast and blurs along them.
+
+#include "common/post_process.wgsl"
+
+// Skip pixels whose local contrast is below this (relative to the maximum luma)
+const EDGE_THRESHOLD: f32 = 0.125;
+// Skip pixels whose local contrast is below this (absolute, avoids processing dark areas)
+const EDGE_THRESHOLD_MIN: f32 = 0.0312;
+const REDUCE_MIN: f32 = 0.0078125; // 1.0 / 128.0
+const REDUCE_MUL: f32 = 0.125; // 1.0 / 8.0
+// Maximum length of the blur along an edge in pixels
+const SPAN_MAX: f32 = 8.0;
+
+// Perceived brightness.
+// The square root roughly converts the linear color back into gamma space,
+// in which FXAA's thresholds are defined.
+fn luma(color: vec3<f32>) -> f32 {
+    return sqrt(dot(color, vec3<f32>(0.299, 0.587, 0.114)));
+}
+
+fn sample(uv: vec2<f32>) -> vec4<f32> {
+    return textureSampleLevel(source_texture, source_sampler, uv, 0.0);
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let texel = 1.0 / vec2<f32>(textureDimensions(source_texture));
+
+    let center = sample(in.uv);
+    let luma_m = luma(center.rgb);
+    let luma_nw = luma(sample(in.uv + vec2<f32>(-1.0, -1.0) * texel).rgb);
+    let luma_ne = luma(sample(in.uv + vec2<f32>(1.0, -1.0) * texel).rgb);
+    let luma_sw = luma(sample(in.uv + vec2<f32>(-1.0, 1.0) * texel).rgb);
+    let luma_se = luma(sample(in.uv + vec2<f32>(1.0, 1.0) * texel).rgb);
+
+    let luma_min = min(luma_m, min(min(luma_nw, luma_ne), min(luma_sw, luma_se)));
+    let luma_max = max(luma_m, max(max(luma_nw, luma_ne), max(luma_sw, luma_se)));
+
+    // Not an edge
+    if luma_max - luma_min < max(EDGE_THRESHOLD_MIN, luma_max * EDGE_THRESHOLD) {
+        return center;
+    }
+
+    // Direction perpendicular to the luma gradient, i.e. along the edge
+    var direction = vec2<f32>(
+        -((luma_nw + luma_ne) - (luma_sw + luma_se)),
+        (luma_nw + luma_sw) - (luma_ne + luma_se),
+    );
+
+    let direction_reduce = max((luma_nw + luma_ne + luma_sw + luma_se) * 0.25 * REDUCE_MUL, REDUCE_MIN);
+    let inverse_direction_min = 1.0 / (min(abs(direction.x), abs(direction.y)) + direction_reduce);
+    direction = clamp(direction * inverse_direction_min, vec2<f32>(-SPAN_MAX), vec2<f32>(SPAN_MAX)) * texel;
+
+    // Two samples close to the center, two further out along the edge
+    let color_a = 0.5 * (
+        sample(in.uv + direction * (1.0 / 3.0 - 0.5)).rgb +
+        sample(in.uv + direction * (2.0 / 3.0 - 0.5)).rgb
+    );
+    let color_b = color_a * 0.5 + 0.25 * (
+        sample(in.uv - direction * 0.5).rgb +
+        sample(in.uv + direction * 0.5).rgb
+    );
+
+    // The wider blur went past the edge
+    let luma_b = luma(color_b);
+    if luma_b < luma_min || luma_b > luma_max {
+        return vec4<f32>(color_a, center.a);
+    }
+    return vec4<f32>(color_b, center.a);
+}
@@ -0,0 +1,91 @@
+// --- Structures ---
+
+struct FrustumUniform {
+    planes: array<vec4<f32>, 6>,
+}
+
+struct MeshCullingUniform {
+    // xyz = center, w = radius (model space)
+    bounding_sphere: vec4<f32>,
+    instance_count: u32,
+    // Amount of f32's a single `InstanceUniform` consists of
+    instance_stride: u32,
+}
+
+// Layout of `wgpu::util::DrawIndexedIndirect`
+struct DrawIndexedIndirect {
+    index_count: u32,
+    instance_count: atomic<u32>,
+    first_index: u32,
+    base_vertex: i32,
+    first_instance: u32,
+}
+
+// --- Bindings ---
+
+@group(0) @binding(0)
+var<uniform> frustum: FrustumUniform;
+
+@group(1) @binding(0)
+var<uniform> mesh: MeshCullingUniform;
+
+@group(1) @binding(1)
+var<storage, read> instances: array<f32>;
+
+@group(1) @binding(2)
+var<storage, read_write> visible_instances: array<f32>;
+
+@group(1) @binding(3)
+var<storage, read_write> draw_arguments: DrawIndexedIndirect;
+
+// --- Compute ---
+
+fn model_space_matrix(index: u32) -> mat4x4<f32> {
+    let offset = index * mesh.instance_stride;
+    return mat4x4<f32>(
+        vec4<f32>(instances[offset + 0u], instances[offset + 1u], instances[offset + 2u], instances[offset + 3u]),
+        vec4<f32>(instances[offset + 4u], instances[offset + 5u], instances[offset + 6u], instances[offset + 7u]),
+        vec4<f32>(instances[offset + 8u], instances[offset + 9u], instances[offset + 10u], instances[offset + 11u]),
+        vec4<f32>(instances[offset + 12u], instances[offset + 13u], instances[offset + 14u], instances[offset + 15u]),
+    );
+}
+
+fn is_visible(center: vec3<f32>, radius: f32) -> bool {
+    for (var i = 0u; i < 6u; i++) {
+        let plane = frustum.planes[i];
+        if dot(plane.xyz, center) + plane.w < -radius {
+            return false;
+        }
+    }
+    return true;
+}
+
+@compute @workgroup_size(64)
+fn cs_main(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    let index = global_id.x;
+    if index >= mesh.instance_count {
+        return;
+    }
+
+    // Move the bounding sphere into world space.
+    // The radius gets scaled by the largest axis scale to stay conservative.
+    let model = model_space_matrix(index);
+    let center = (model * vec4<f32>(mesh.bounding_sphere.xyz, 1.0)).xyz;
+    let max_scale = sqrt(max(
+        dot(model[0].xyz, model[0].xyz),
+        max(dot(model[1].xyz, model[1].xyz), dot(model[2].xyz, model[2].xyz))
+    ));
+    let radius = mesh.bounding_sphere.w * max_scale;
+
+    if !is_visible(center, radius) {
+        return;
+    }
+
+    // Compact into the visible instance buffer
+    let slot = atomicAdd(&draw_arguments.instance_count, 1u);
+    let source = index * mesh.instance_stride;
+    let destination = slot * mesh.instance_stride;
+    for (var i = 0u; i < mesh.instance_stride; i++) {
+        visible_instances[destination + i] = instances[source + i];
+    }
+}
@@ -0,0 +1,115 @@
+// Bins point and spot lights into view space clusters.
+// The view frustum is split into tiles on screen and exponential slices
+// along the view depth. Each cluster gets a list of the lights affecting it,
+// thus fragments only have to shade the lights of their cluster.
+
+// --- Structures ---
+
+#include "common/lights.wgsl"
+
+// --- Bindings ---
+
+@group(0) @binding(0)
+var<uniform> clustering: LightClusteringUniform;
+
+@group(0) @binding(1)
+var<storage, read_write> cluster_lights: array<ClusterLights>;
+
+// `max_lights_per_cluster` slots per cluster.
+// Point light indices first, followed by spot light indices.
+@group(0) @binding(2)
+var<storage, read_write> light_indices: array<u32>;
+
+@group(1) @binding(0)
+var<storage, read> point_lights: PointLights;
+
+@group(1) @binding(2)
+var<storage, read> spot_lights: SpotLights;
+
+// --- Compute ---
+
+// View space position on the given tile corner at the given view depth
+fn tile_corner(ndc: vec2<f32>, depth: f32) -> vec3<f32> {
+    let view_position = clustering.inverse_projection_matrix * vec4<f32>(ndc, 1.0, 1.0);
+    let ray = view_position.xyz / view_position.w;
+    // Looking down -Z
+    return ray * (depth / -ray.z);
+}
+
+fn intersects_sphere(aabb_min: vec3<f32>, aabb_max: vec3<f32>, center: vec3<f32>, radius: f32) -> bool {
+    let closest = clamp(center, aabb_min, aabb_max);
+    let offset = closest - center;
+    return dot(offset, offset) <= radius * radius;
+}
+
+@compute @workgroup_size(4, 4, 4)
+fn cs_main(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    let grid_size = clustering.grid_size;
+    if any(global_id >= grid_size) {
+        return;
+    }
+
+    let cluster_index = global_id.x
+        + global_id.y * grid_size.x
+        + global_id.z * grid_size.x * grid_size.y;
+
+    // Tile bounds in NDC, tile (0, 0) is the top left one
+    let tile_size = 2.0 / vec2<f32>(grid_size.xy);
+    let ndc_min = vec2<f32>(-1.0 + f32(global_id.x) * tile_size.x, 1.0 - f32(global_id.y + 1u) * tile_size.y);
+    let ndc_max = ndc_min + tile_size;
+
+    // Exponential depth slices
+    let depth_ratio = clustering.z_far / clustering.z_near;
+    let depth_near = clustering.z_near * pow(depth_ratio, f32(global_id.z) / f32(grid_size.z));
+    let depth_far = clustering.z_near * pow(depth_ratio, f32(global_id.z + 1u) / f32(grid_size.z));
+
+    var corners = array<vec3<f32>, 8>(
+        tile_corner(ndc_min, depth_near),
+        tile_corner(vec2<f32>(ndc_max.x, ndc_min.y), depth_near),
+        tile_corner(vec2<f32>(ndc_min.x, ndc_max.y), depth_near),
+        tile_corner(ndc_max, depth_near),
+        tile_corner(ndc_min, depth_far),
+        tile_corner(vec2<f32>(ndc_max.x, ndc_min.y), depth_far),
+        tile_corner(vec2<f32>(ndc_min.x, ndc_max.y), depth_far),
+        tile_corner(ndc_max, depth_far),
+    );
+    var aabb_min = corners[0];
+    var aabb_max = corners[0];
+    for (var i = 1; i < 8; i++) {
+        aabb_min = min(aabb_min, corners[i]);
+        aabb_max = max(aabb_max, corners[i]);
+    }
+
+    let first_slot = cluster_index * clustering.max_lights_per_cluster;
+    var count = 0u;
+
+    for (var i = 0u; i < point_lights.count && count < clustering.max_lights_per_cluster; i++) {
+        let point_light = point_lights.lights[i];
+        if point_light.enabled == 0u {
+            continue;
+        }
+
+        let center = (clustering.view_matrix * vec4<f32>(point_light.position, 1.0)).xyz;
+        if intersects_sphere(aabb_min, aabb_max, center, point_light.range) {
+            light_indices[first_slot + count] = i;
+            count++;
+        }
+    }
+    let point_count = count;
+
+    // Spot lights are tested by their bounding sphere, which is conservative
+    for (var i = 0u; i < spot_lights.count && count < clustering.max_lights_per_cluster; i++) {
+        let spot_light = spot_lights.lights[i];
+        if spot_light.enabled == 0u {
+            continue;
+        }
+
+        let center = (clustering.view_matrix * vec4<f32>(spot_light.position, 1.0)).xyz;
+        if intersects_sphere(aabb_min, aabb_max, center, spot_light.range) {
+            light_indices[first_slot + count] = i;
+            count++;
+        }
+    }
+
+    cluster_lights[cluster_index] = ClusterLights(point_count, count - point_count);
+}
@@ -0,0 +1,158 @@
+// Metallic-roughness shading (Cook-Torrance) of the `PbrMaterial`,
+// following the glTF 2.0 material model.
+
+#include "common/lighting.wgsl"
+
+struct Material {
+    base_color_factor: vec4<f32>,
+    emissive_factor: vec3<f32>,
+    metallic_factor: f32,
+    roughness_factor: f32,
+    normal_scale: f32,
+    occlusion_strength: f32,
+    // Lower base color alphas get discarded if `ALPHA_MASK` is defined
+    alpha_cutoff: f32,
+}
+
+struct Surface {
+    normal: vec3<f32>,
+    view_dir: vec3<f32>,
+    albedo: vec3<f32>,
+    metallic: f32,
+    roughness: f32,
+    // Reflectance at normal incidence
+    f0: vec3<f32>,
+}
+
+@group(0) @binding(0)
+var t_base_color: texture_2d<f32>;
+
+@group(0) @binding(1)
+var s_base_color: sampler;
+
+@group(0) @binding(2)
+var t_normal: texture_2d<f32>;
+
+@group(0) @binding(3)
+var s_normal: sampler;
+
+@group(0) @binding(4)
+var<uniform> material: Material;
+
+// Roughness in G, metallic in B
+@group(0) @binding(5)
+var t_metallic_roughness: texture_2d<f32>;
+
+@group(0) @binding(6)
+var s_metallic_roughness: sampler;
+
+@group(0) @binding(7)
+var t_occlusion: texture_2d<f32>;
+
+@group(0) @binding(8)
+var s_occlusion: sampler;
+
+@group(0) @binding(9)
+var t_emissive: texture_2d<f32>;
+
+@group(0) @binding(10)
+var s_emissive: sampler;
+
+// GGX / Trowbridge-Reitz normal distribution
+fn distribution_ggx(n_dot_h: f32, roughness: f32) -> f32 {
+    let alpha = roughness * roughness;
+    let alpha_squared = alpha * alpha;
+    let denominator = n_dot_h * n_dot_h * (alpha_squared - 1.0) + 1.0;
+    return alpha_squared / (PI * denominator * denominator);
+}
+
+// Schlick-GGX geometry term of a single direction
+fn geometry_schlick_ggx(n_dot_x: f32, roughness: f32) -> f32 {
+    let k = (roughness + 1.0) * (roughness + 1.0) / 8.0;
+    return n_dot_x / (n_dot_x * (1.0 - k) + k);
+}
+
+fn fresnel_schlick(cos_theta: f32, f0: vec3<f32>) -> vec3<f32> {
+    return f0 + (1.0 - f0) * pow(clamp(1.0 - cos_theta, 0.0, 1.0), 5.0);
+}
+
+// Cook-Torrance specular + Lambert diffuse of a single light.
+// `light_dir` points from the fragment towards the light.
+fn shade_light(
+    light_color: vec3<f32>,
+    light_dir: vec3<f32>,
+    surface: Surface,
+) -> vec3<f32> {
+    let n_dot_l = max(dot(surface.normal, light_dir), 0.0);
+    if n_dot_l <= 0.0 {
+        return vec3<f32>(0.0);
+    }
+
+    let half_dir = normalize(surface.view_dir + light_dir);
+    let n_dot_v = max(dot(surface.normal, surface.view_dir), 0.0001);
+    let n_dot_h = max(dot(surface.normal, half_dir), 0.0);
+
+    let distribution = distribution_ggx(n_dot_h, surface.roughness);
+    let geometry = geometry_schlick_ggx(n_dot_v, surface.roughness)
+        * geometry_schlick_ggx(n_dot_l, surface.roughness);
+    let fresnel = fresnel_schlick(max(dot(half_dir, surface.view_dir), 0.0), surface.f0);
+
+    let specular = distribution * geometry * fresnel / (4.0 * n_dot_v * n_dot_l + 0.0001);
+    // Metals have no diffuse reflection
+    let diffuse = (1.0 - fresnel) * (1.0 - surface.metallic) * surface.albedo / PI;
+
+    return (diffuse + specular) * light_color * n_dot_l;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let base_color = textureSample(t_base_color, s_base_color, in.texture_coordinates)
+        * material.base_color_factor
+        * in.tint;
+    let normal_map = textureSample(t_normal, s_normal, in.texture_coordinates);
+    let metallic_roughness = textureSample(
+        t_metallic_roughness,
+        s_metallic_roughness,
+        in.texture_coordinates,
+    );
+    let occlusion_map = textureSample(t_occlusion, s_occlusion, in.texture_coordinates);
+    let emissive_map = textureSample(t_emissive, s_emissive, in.texture_coordinates);
+
+    #ifdef ALPHA_MASK
+    if base_color.a < material.alpha_cutoff {
+        discard;
+    }
+    #endif
+
+    let tangent_normal = (normal_map.xyz * 2.0 - 1.0)
+        * vec3<f32>(material.normal_scale, material.normal_scale, 1.0);
+
+    var surface: Surface;
+    surface.normal = surface_normal(in, tangent_normal);
+    surface.view_dir = normalize(camera.position.xyz - in.world_position);
+    surface.albedo = base_color.rgb;
+    surface.metallic = saturate(metallic_roughness.b * material.metallic_factor);
+    // A perfectly smooth surface would reflect lights as infinitely small points
+    surface.roughness = clamp(metallic_roughness.g * material.roughness_factor, 0.04, 1.0);
+    surface.f0 = mix(F0, surface.albedo, surface.metallic);
+
+    // Ambient Light, from the environment map if there is one
+    var ambient_color = ambient_light.color * ambient_light.strength * surface.albedo;
+    if environment.enabled != 0u {
+        let environment_color = environment_lighting(
+            surface.normal,
+            surface.view_dir,
+            surface.f0,
+            surface.roughness,
+        );
+        ambient_color = environment_color.diffuse * (1.0 - surface.metallic) * surface.albedo
+            + environment_color.specular;
+    }
+    let occlusion = mix(1.0, occlusion_map.r, material.occlusion_strength);
+
+    let light_color = direct_lighting(in, surface);
+    let emissive_color = emissive_map.rgb * material.emissive_factor;
+
+    let result = ambient_color * occlusion + light_color + emissive_color;
+    return vec4<f32>(result, base_color.a);
+}
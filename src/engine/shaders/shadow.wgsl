@@ -0,0 +1,34 @@
+// Depth-only pass, renders the scene from a lights point of view
+// into a single shadow map layer.
+
+#include "common/vertex.wgsl"
+
+// --- Structures ---
+
+struct ShadowPassUniform {
+    view_projection_matrix: mat4x4<f32>,
+}
+
+// --- Bindings ---
+
+@group(0) @binding(0)
+var<uniform> shadow_pass: ShadowPassUniform;
+
+// --- Vertex ---
+
+@vertex
+fn vs_main(
+    vertex_point: VertexPoint,
+    instance: InstanceUniform,
+) -> @builtin(position) vec4<f32> {
+    let model_space_matrix = mat4x4<f32>(
+        instance.model_space_matrix_0,
+        instance.model_space_matrix_1,
+        instance.model_space_matrix_2,
+        instance.model_space_matrix_3,
+    );
+
+    return shadow_pass.view_projection_matrix
+        * model_space_matrix
+        * vec4<f32>(vertex_point.position_coordinates, 1.0);
+}
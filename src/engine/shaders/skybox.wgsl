@@ -0,0 +1,46 @@
+// --- Structures ---
+
+struct SkyboxUniform {
+    inverse_view_projection_matrix: mat4x4<f32>,
+}
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) screen_position: vec2<f32>,
+}
+
+// --- Bindings ---
+
+@group(0) @binding(0)
+var skybox_texture: texture_cube<f32>;
+
+@group(0) @binding(1)
+var skybox_sampler: sampler;
+
+@group(0) @binding(2)
+var<uniform> skybox: SkyboxUniform;
+
+// --- Vertex ---
+
+// Fullscreen triangle on the far plane (depth = 1.0),
+// so it only shows where no geometry was drawn
+@vertex
+fn vs_main(@builtin(vertex_index) vertex_index: u32) -> VertexOutput {
+    let uv = vec2<f32>(f32((vertex_index << 1u) & 2u), f32(vertex_index & 2u));
+    let screen_position = uv * vec2<f32>(2.0, -2.0) + vec2<f32>(-1.0, 1.0);
+
+    var out: VertexOutput;
+    out.clip_position = vec4<f32>(screen_position, 1.0, 1.0);
+    out.screen_position = screen_position;
+    return out;
+}
+
+// --- Fragment ---
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let world_position = skybox.inverse_view_projection_matrix * vec4<f32>(in.screen_position, 1.0, 1.0);
+    let direction = normalize(world_position.xyz / world_position.w);
+
+    return vec4<f32>(textureSample(skybox_texture, skybox_sampler, direction).rgb, 1.0);
+}
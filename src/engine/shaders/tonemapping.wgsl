@@ -0,0 +1,49 @@
+#include "common/post_process.wgsl"
+#include "common/post_process_settings.wgsl"
+
+// --- Bindings ---
+
+@group(1) @binding(0)
+var bloom_texture: texture_2d<f32>;
+
+@group(1) @binding(1)
+var<uniform> settings: PostProcessSettings;
+
+// --- Tonemapping ---
+
+fn reinhard(color: vec3<f32>) -> vec3<f32> {
+    return color / (vec3<f32>(1.0) + color);
+}
+
+// Narkowicz 2015, "ACES Filmic Tone Mapping Curve"
+fn aces(color: vec3<f32>) -> vec3<f32> {
+    let a = 2.51;
+    let b = 0.03;
+    let c = 2.43;
+    let d = 0.59;
+    let e = 0.14;
+    return clamp((color * (a * color + b)) / (color * (c * color + d) + e), vec3<f32>(0.0), vec3<f32>(1.0));
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let hdr = textureSampleLevel(source_texture, source_sampler, in.uv, 0.0);
+    let bloom = textureSampleLevel(bloom_texture, source_sampler, in.uv, 0.0).rgb;
+
+    let color = (hdr.rgb + bloom * settings.bloom_intensity) * settings.exposure;
+
+    var mapped: vec3<f32>;
+    switch settings.tonemapper {
+        case 1u: {
+            mapped = reinhard(color);
+        }
+        case 2u: {
+            mapped = aces(color);
+        }
+        default: {
+            mapped = clamp(color, vec3<f32>(0.0), vec3<f32>(1.0));
+        }
+    }
+
+    return vec4<f32>(mapped, hdr.a);
+}
@@ -0,0 +1,83 @@
+// Cel shading of the `ToonMaterial`: lighting is quantized into flat bands,
+// with a hard specular highlight and a rim light.
+
+#include "common/lighting.wgsl"
+
+struct Material {
+    color: vec4<f32>,
+    band_count: f32,
+    // Exponent of the specular highlight, 0.0 = no highlight
+    shininess: f32,
+    rim_strength: f32,
+    // Lower alphas get discarded if `ALPHA_MASK` is defined
+    alpha_cutoff: f32,
+}
+
+struct Surface {
+    normal: vec3<f32>,
+    view_dir: vec3<f32>,
+}
+
+@group(0) @binding(0)
+var t_color: texture_2d<f32>;
+
+@group(0) @binding(1)
+var s_color: sampler;
+
+@group(0) @binding(2)
+var<uniform> material: Material;
+
+fn quantize(value: f32) -> f32 {
+    let band_count = max(material.band_count, 1.0);
+    return ceil(saturate(value) * band_count) / band_count;
+}
+
+// Banded diffuse + hard specular of a single light.
+// `light_dir` points from the fragment towards the light.
+fn shade_light(
+    light_color: vec3<f32>,
+    light_dir: vec3<f32>,
+    surface: Surface,
+) -> vec3<f32> {
+    let diffuse_strength = quantize(dot(surface.normal, light_dir));
+
+    var specular_strength = 0.0;
+    if material.shininess > 0.0 {
+        let half_dir = normalize(surface.view_dir + light_dir);
+        let highlight = pow(max(dot(surface.normal, half_dir), 0.0), material.shininess);
+        specular_strength = step(0.5, highlight);
+    }
+
+    return light_color * (diffuse_strength + specular_strength);
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let object_color = textureSample(t_color, s_color, in.texture_coordinates)
+        * material.color
+        * in.tint;
+    #ifdef ALPHA_MASK
+    if object_color.a < material.alpha_cutoff {
+        discard;
+    }
+    #endif
+
+    var surface: Surface;
+    surface.normal = normalize(in.world_normal);
+    surface.view_dir = normalize(camera.position.xyz - in.world_position);
+
+    // Flat ambient, toon shading doesn't use reflections of the environment
+    var ambient_color = ambient_light.color * ambient_light.strength;
+    if environment.enabled != 0u {
+        ambient_color = environment_lighting(surface.normal, surface.view_dir, F0, 1.0).diffuse;
+    }
+
+    let light_color = direct_lighting(in, surface);
+
+    // Outline-like highlight along the silhouette
+    let rim = pow(1.0 - max(dot(surface.normal, surface.view_dir), 0.0), 4.0);
+    let rim_color = vec3<f32>(step(0.5, rim) * material.rim_strength);
+
+    let result = (ambient_color + light_color + rim_color) * object_color.rgb;
+    return vec4<f32>(result, object_color.a);
+}
@@ -0,0 +1,30 @@
+// Unlit shading of the `UnlitMaterial`, ignores all lights.
+
+#include "common/mesh.wgsl"
+
+struct Material {
+    color: vec4<f32>,
+    // Lower alphas get discarded if `ALPHA_MASK` is defined
+    alpha_cutoff: f32,
+}
+
+@group(0) @binding(0)
+var t_color: texture_2d<f32>;
+
+@group(0) @binding(1)
+var s_color: sampler;
+
+@group(0) @binding(2)
+var<uniform> material: Material;
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let color = textureSample(t_color, s_color, in.texture_coordinates) * material.color * in.tint;
+    #ifdef ALPHA_MASK
+    if color.a < material.alpha_cutoff {
+        discard;
+    }
+    #endif
+
+    return color;
+}
@@ -0,0 +1,23 @@
+// Shading of the `VertexColorMaterial`, shows the vertex colors as they are.
+
+#include "common/mesh.wgsl"
+
+struct Material {
+    // Lower alphas get discarded if `ALPHA_MASK` is defined
+    alpha_cutoff: f32,
+}
+
+@group(0) @binding(0)
+var<uniform> material: Material;
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let color = in.color * in.tint;
+    #ifdef ALPHA_MASK
+    if color.a < material.alpha_cutoff {
+        discard;
+    }
+    #endif
+
+    return color;
+}
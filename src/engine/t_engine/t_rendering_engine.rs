@@ -1,9 +1,9 @@
 use wgpu::{
-    CompositeAlphaMode, PresentMode, RenderPipeline, Surface, SurfaceConfiguration, SurfaceTexture,
-    TextureFormat, TextureView,
+    CompositeAlphaMode, PresentMode, Surface, SurfaceConfiguration, SurfaceTexture, TextureFormat,
+    TextureView,
 };
 
-use crate::engine::{DepthTexture, EngineError, EngineResult, TextureHelper};
+use crate::engine::{EngineError, EngineResult, TextureHelper};
 
 use super::TComputingEngine;
 
@@ -55,8 +55,4 @@ pub trait TRenderingEngine: TComputingEngine {
     fn surface_texture_view(&self) -> EngineResult<TextureView> {
         Ok(self.surface_texture()?.make_texture_view())
     }
-
-    fn depth_texture(&self) -> Option<&DepthTexture>;
-
-    fn render_pipeline(&self) -> &RenderPipeline;
 }
@@ -0,0 +1,11 @@
+use cgmath::Vector3;
+
+/// A single line queued by [`super::DebugDraw`] for the current frame
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DebugLine {
+    pub start: Vector3<f32>,
+    pub end: Vector3<f32>,
+    pub color: Vector3<f32>,
+    /// Hidden behind the scene's geometry if true, drawn on top otherwise
+    pub depth_test: bool,
+}
@@ -0,0 +1,269 @@
+#[cfg(debug_assertions)]
+use std::sync::Mutex;
+
+use cgmath::{InnerSpace, Matrix4, SquareMatrix, Vector3, Vector4, Zero};
+
+use crate::engine::{
+    BoundingBox, Camera, Light, LightStorage, TDirectionalLight, TPointLight, TSpotLight,
+};
+
+mod debug_line;
+pub use debug_line::*;
+
+#[cfg(debug_assertions)]
+static LINES: Mutex<Vec<DebugLine>> = Mutex::new(Vec::new());
+
+/// Immediate-mode debug drawing, e.g. from [`crate::app::TEntity::update`].
+///
+/// Lines only live for the frame they were queued in and have to be
+/// queued again every frame. They are drawn by the
+/// [`crate::engine::DebugLinesNode`] after the scene, before post-processing.
+///
+/// Release builds drop everything queued here.
+///
+/// ```ignore
+/// DebugDraw::DEPTH_TESTED.aabb(&bounding_box, Vector3::new(0.0, 1.0, 0.0));
+/// DebugDraw::OVERLAY.axes(Vector3::zero(), 1.0);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct DebugDraw {
+    depth_test: bool,
+}
+
+impl DebugDraw {
+    /// Whether anything queued gets drawn at all
+    pub const ENABLED: bool = cfg!(debug_assertions);
+
+    /// Hidden behind the scene's geometry
+    pub const DEPTH_TESTED: Self = Self { depth_test: true };
+
+    /// Drawn on top of everything
+    pub const OVERLAY: Self = Self { depth_test: false };
+
+    /// Segments of circles & spheres
+    const CIRCLE_SEGMENTS: usize = 24;
+
+    /// Radius of the sphere marking a light's position
+    const LIGHT_MARKER_RADIUS: f32 = 0.1;
+
+    /// Color of disabled lights
+    const DISABLED_LIGHT_COLOR: Vector3<f32> = Vector3::new(0.4, 0.4, 0.4);
+
+    pub fn line(&self, start: Vector3<f32>, end: Vector3<f32>, color: Vector3<f32>) {
+        self.push(DebugLine {
+            start,
+            end,
+            color,
+            depth_test: self.depth_test,
+        });
+    }
+
+    /// Connects each point to the next one
+    pub fn line_strip(&self, points: &[Vector3<f32>], color: Vector3<f32>) {
+        points.windows(2).for_each(|x| self.line(x[0], x[1], color));
+    }
+
+    pub fn aabb(&self, bounding_box: &BoundingBox, color: Vector3<f32>) {
+        self.box_edges(bounding_box.corners(), color);
+    }
+
+    pub fn circle(
+        &self,
+        center: Vector3<f32>,
+        normal: Vector3<f32>,
+        radius: f32,
+        color: Vector3<f32>,
+    ) {
+        let (u, v) = Self::perpendicular(normal);
+
+        let points: Vec<Vector3<f32>> = (0..=Self::CIRCLE_SEGMENTS)
+            .map(|i| {
+                let angle = i as f32 / Self::CIRCLE_SEGMENTS as f32 * std::f32::consts::TAU;
+                center + (u * angle.cos() + v * angle.sin()) * radius
+            })
+            .collect();
+        self.line_strip(&points, color);
+    }
+
+    /// Drawn as one circle around each axis
+    pub fn sphere(&self, center: Vector3<f32>, radius: f32, color: Vector3<f32>) {
+        self.circle(center, Vector3::unit_x(), radius, color);
+        self.circle(center, Vector3::unit_y(), radius, color);
+        self.circle(center, Vector3::unit_z(), radius, color);
+    }
+
+    /// Line with an arrowhead at its end
+    pub fn arrow(&self, start: Vector3<f32>, end: Vector3<f32>, color: Vector3<f32>) {
+        self.line(start, end, color);
+
+        let direction = end - start;
+        let length = direction.magnitude();
+        if length <= f32::EPSILON {
+            return;
+        }
+
+        let head_length = length * 0.2;
+        let head_base = end - direction / length * head_length;
+        let (u, v) = Self::perpendicular(direction);
+        for offset in [u, -u, v, -v] {
+            self.line(end, head_base + offset * head_length * 0.5, color);
+        }
+    }
+
+    /// X, Y & Z axis as red, green & blue arrows
+    pub fn axes(&self, origin: Vector3<f32>, length: f32) {
+        self.arrow(
+            origin,
+            origin + Vector3::unit_x() * length,
+            Vector3::new(1.0, 0.0, 0.0),
+        );
+        self.arrow(
+            origin,
+            origin + Vector3::unit_y() * length,
+            Vector3::new(0.0, 1.0, 0.0),
+        );
+        self.arrow(
+            origin,
+            origin + Vector3::unit_z() * length,
+            Vector3::new(0.0, 0.0, 1.0),
+        );
+    }
+
+    /// Frustum of the view projection matrix, e.g. of a camera or a shadow map
+    pub fn frustum(&self, view_projection_matrix: Matrix4<f32>, color: Vector3<f32>) {
+        let Some(inverse) = view_projection_matrix.invert() else {
+            return;
+        };
+
+        // Same order as `BoundingBox::corners`, in normalized device coordinates
+        let corners = std::array::from_fn(|i| {
+            let x = if i & 1 == 0 { -1.0 } else { 1.0 };
+            let y = if i & 2 == 0 { -1.0 } else { 1.0 };
+            let z = if i & 4 == 0 { 0.0 } else { 1.0 };
+
+            let corner = inverse * Vector4::new(x, y, z, 1.0);
+            corner.truncate() / corner.w
+        });
+        self.box_edges(corners, color);
+    }
+
+    pub fn camera(&self, camera: &Camera, color: Vector3<f32>) {
+        self.frustum(camera.calculate_view_projection_matrix(), color);
+    }
+
+    /// Position & range
+    pub fn point_light(&self, point_light: &dyn TPointLight) {
+        let color = Self::light_color(point_light.color(), point_light.enabled());
+
+        self.sphere(point_light.position(), Self::LIGHT_MARKER_RADIUS, color);
+        self.sphere(point_light.position(), point_light.range(), color * 0.5);
+    }
+
+    /// Position & outer cone up to the range
+    pub fn spot_light(&self, spot_light: &dyn TSpotLight) {
+        let color = Self::light_color(spot_light.color(), spot_light.enabled());
+        let position = spot_light.position();
+        let direction = spot_light.direction().normalize();
+
+        self.sphere(position, Self::LIGHT_MARKER_RADIUS, color);
+
+        let cone_center = position + direction * spot_light.range();
+        let cone_radius = spot_light.range() * spot_light.outer_cone_angle().0.tan();
+        self.circle(cone_center, direction, cone_radius, color * 0.5);
+
+        let (u, v) = Self::perpendicular(direction);
+        for offset in [u, -u, v, -v] {
+            self.line(position, cone_center + offset * cone_radius, color * 0.5);
+        }
+    }
+
+    /// Arrow along the direction, starting at the given position
+    /// as directional lights don't have one
+    pub fn directional_light(
+        &self,
+        directional_light: &dyn TDirectionalLight,
+        position: Vector3<f32>,
+    ) {
+        let color = Self::light_color(directional_light.color(), directional_light.enabled());
+
+        self.arrow(
+            position,
+            position + directional_light.direction().normalize(),
+            color,
+        );
+    }
+
+    pub fn light(&self, light: &Light) {
+        match light {
+            Light::Point(x) => self.point_light(x),
+            Light::Spot(x) => self.spot_light(x),
+            Light::Directional(x) => self.directional_light(x, Vector3::zero()),
+        }
+    }
+
+    /// World & entity lights, directional ones at the origin
+    pub fn lights(&self, light_storage: &LightStorage) {
+        light_storage
+            .point_lights()
+            .iter()
+            .for_each(|x| self.point_light(x));
+        light_storage
+            .spot_lights()
+            .iter()
+            .for_each(|x| self.spot_light(x));
+        light_storage
+            .directional_lights()
+            .iter()
+            .for_each(|x| self.directional_light(x, Vector3::zero()));
+        light_storage
+            .entity_lights()
+            .iter()
+            .for_each(|x| self.light(x));
+    }
+
+    /// Removes all lines queued since the last call
+    #[cfg(debug_assertions)]
+    pub fn take_lines() -> Vec<DebugLine> {
+        std::mem::take(&mut *LINES.lock().unwrap())
+    }
+
+    #[cfg(debug_assertions)]
+    fn push(&self, line: DebugLine) {
+        LINES.lock().unwrap().push(line);
+    }
+
+    #[cfg(not(debug_assertions))]
+    fn push(&self, _line: DebugLine) {}
+
+    /// The 12 edges between corners ordered like `BoundingBox::corners`
+    fn box_edges(&self, corners: [Vector3<f32>; 8], color: Vector3<f32>) {
+        for i in 0..corners.len() {
+            for axis in [1, 2, 4] {
+                if i & axis == 0 {
+                    self.line(corners[i], corners[i | axis], color);
+                }
+            }
+        }
+    }
+
+    /// Two unit vectors perpendicular to the direction & each other
+    fn perpendicular(direction: Vector3<f32>) -> (Vector3<f32>, Vector3<f32>) {
+        let direction = direction.normalize();
+        let helper = if direction.y.abs() < 0.99 {
+            Vector3::unit_y()
+        } else {
+            Vector3::unit_x()
+        };
+
+        let u = direction.cross(helper).normalize();
+        (u, direction.cross(u))
+    }
+
+    fn light_color(color: Vector3<f32>, enabled: bool) -> Vector3<f32> {
+        if enabled {
+            color
+        } else {
+            Self::DISABLED_LIGHT_COLOR
+        }
+    }
+}
@@ -0,0 +1,290 @@
+use std::{mem::size_of, num::NonZeroU64};
+
+use wgpu::{
+    util::BufferInitDescriptor, util::DeviceExt, BindGroup, BindGroupDescriptor, BindGroupEntry,
+    BindGroupLayout, BindGroupLayoutDescriptor, BindGroupLayoutEntry, BindingResource, BindingType,
+    BufferBindingType, BufferUsages, CommandEncoderDescriptor, ComputePassDescriptor,
+    ComputePipeline, ComputePipelineDescriptor, ErrorFilter, PipelineLayoutDescriptor,
+    SamplerBindingType, ShaderStages, StorageTextureAccess, TextureSampleType, TextureView,
+    TextureViewDescriptor, TextureViewDimension,
+};
+
+use crate::engine::{
+    CubeTexture, EngineError, EngineResult, EnvironmentMap, LogicalDevice, ShaderPreprocessor,
+    TTexture, TextureHelper,
+};
+
+mod u_bake_parameters;
+pub use u_bake_parameters::*;
+
+/// A single compute dispatch writing into one output (mip level)
+struct BakeDispatch<'a> {
+    pipeline: &'a ComputePipeline,
+    output_view: TextureView,
+    size: u32,
+    layers: u32,
+    roughness: f32,
+    sample_count: u32,
+}
+
+/// Precomputes the image-based lighting maps of an [`EnvironmentMap`]
+/// from an HDR environment cubemap with compute shaders.
+///
+/// Created for each bake, so shader changes apply to the next baked
+/// environment map. Already baked maps aren't hot-reloaded.
+pub struct EnvironmentMapBaker {
+    irradiance_pipeline: ComputePipeline,
+    prefilter_pipeline: ComputePipeline,
+    brdf_pipeline: ComputePipeline,
+}
+
+impl EnvironmentMapBaker {
+    /// Samples per texel of the prefiltered map
+    pub const PREFILTER_SAMPLE_COUNT: u32 = 512;
+
+    /// Samples per texel of the BRDF lookup table
+    pub const BRDF_SAMPLE_COUNT: u32 = 512;
+
+    pub const WORKGROUP_SIZE: [u32; 2] = [8, 8];
+
+    pub const BIND_GROUP_LAYOUT_DESCRIPTOR: BindGroupLayoutDescriptor<'static> =
+        BindGroupLayoutDescriptor {
+            label: Some("Environment Map Baking Bind Group Layout"),
+            entries: &[
+                BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: ShaderStages::COMPUTE,
+                    ty: BindingType::Texture {
+                        sample_type: TextureSampleType::Float { filterable: true },
+                        view_dimension: TextureViewDimension::Cube,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: ShaderStages::COMPUTE,
+                    ty: BindingType::Sampler(SamplerBindingType::Filtering),
+                    count: None,
+                },
+                BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: ShaderStages::COMPUTE,
+                    ty: BindingType::StorageTexture {
+                        access: StorageTextureAccess::WriteOnly,
+                        format: CubeTexture::TEXTURE_FORMAT,
+                        view_dimension: TextureViewDimension::D2Array,
+                    },
+                    count: None,
+                },
+                BindGroupLayoutEntry {
+                    binding: 3,
+                    visibility: ShaderStages::COMPUTE,
+                    ty: BindingType::Buffer {
+                        ty: BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: NonZeroU64::new(size_of::<UBakeParameters>() as u64),
+                    },
+                    count: None,
+                },
+            ],
+        };
+
+    pub const SHADER_PATH: &'static str = "environment_baking.wgsl";
+
+    pub fn new(logical_device: &LogicalDevice) -> EngineResult<Self> {
+        let entry_points = ["cs_irradiance", "cs_prefilter", "cs_brdf"];
+
+        let shader = ShaderPreprocessor::new().process_file(Self::SHADER_PATH)?;
+        let reflection = shader.reflect()?;
+        reflection
+            .validate_bind_group_layouts(&entry_points, &[&Self::BIND_GROUP_LAYOUT_DESCRIPTOR])?;
+        let shader = reflection.create_shader_module(logical_device, "Environment Baking Shader");
+
+        logical_device
+            .device()
+            .push_error_scope(ErrorFilter::Validation);
+
+        let pipeline_layout =
+            logical_device
+                .device()
+                .create_pipeline_layout(&PipelineLayoutDescriptor {
+                    label: Some("Environment Map Baking Pipeline Layout"),
+                    bind_group_layouts: &[&Self::bind_group_layout(logical_device)],
+                    push_constant_ranges: &[],
+                });
+
+        let make_pipeline = |label, entry_point| {
+            logical_device
+                .device()
+                .create_compute_pipeline(&ComputePipelineDescriptor {
+                    label: Some(label),
+                    layout: Some(&pipeline_layout),
+                    module: &shader,
+                    entry_point,
+                })
+        };
+
+        let environment_map_baker = Self {
+            irradiance_pipeline: make_pipeline("Irradiance Baking Pipeline", entry_points[0]),
+            prefilter_pipeline: make_pipeline("Prefilter Baking Pipeline", entry_points[1]),
+            brdf_pipeline: make_pipeline("BRDF Baking Pipeline", entry_points[2]),
+        };
+
+        if let Some(error) = pollster::block_on(logical_device.device().pop_error_scope()) {
+            return Err(EngineError::ShaderError(format!(
+                "Environment map baking pipelines: {}",
+                error
+            )));
+        }
+
+        Ok(environment_map_baker)
+    }
+
+    pub fn bind_group_layout(logical_device: &LogicalDevice) -> BindGroupLayout {
+        logical_device
+            .device()
+            .create_bind_group_layout(&Self::BIND_GROUP_LAYOUT_DESCRIPTOR)
+    }
+
+    /// Fills the maps of the environment map from the source cubemap.
+    /// Gets submitted immediately.
+    pub fn bake(
+        &self,
+        logical_device: &LogicalDevice,
+        source: &CubeTexture,
+        environment_map: &EnvironmentMap,
+    ) {
+        let irradiance = environment_map.irradiance_texture();
+        let mut dispatches = vec![BakeDispatch {
+            pipeline: &self.irradiance_pipeline,
+            output_view: Self::mip_view(irradiance, 0),
+            size: irradiance.face_size(),
+            layers: CubeTexture::FACE_COUNT,
+            roughness: 0.0,
+            sample_count: 0,
+        }];
+
+        // One roughness per mip level, from 0.0 (mirror) to 1.0
+        let prefiltered = environment_map.prefiltered_texture();
+        let mip_level_count = prefiltered.texture().mip_level_count();
+        for mip in 0..mip_level_count {
+            dispatches.push(BakeDispatch {
+                pipeline: &self.prefilter_pipeline,
+                output_view: Self::mip_view(prefiltered, mip),
+                size: (prefiltered.face_size() >> mip).max(1),
+                layers: CubeTexture::FACE_COUNT,
+                roughness: mip as f32 / (mip_level_count - 1).max(1) as f32,
+                sample_count: Self::PREFILTER_SAMPLE_COUNT,
+            });
+        }
+
+        let brdf_lut = environment_map.brdf_lut();
+        dispatches.push(BakeDispatch {
+            pipeline: &self.brdf_pipeline,
+            output_view: brdf_lut
+                .texture()
+                .make_texture_view_descriptor(&TextureViewDescriptor {
+                    label: Some("BRDF LUT Bake View"),
+                    dimension: Some(TextureViewDimension::D2Array),
+                    ..Default::default()
+                }),
+            size: brdf_lut.texture().width(),
+            layers: 1,
+            roughness: 0.0,
+            sample_count: Self::BRDF_SAMPLE_COUNT,
+        });
+
+        let bind_groups = dispatches
+            .iter()
+            .map(|x| {
+                self.make_bind_group(
+                    logical_device,
+                    source,
+                    &x.output_view,
+                    UBakeParameters::new(x.roughness, x.sample_count, x.size),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let mut command_encoder =
+            logical_device
+                .device()
+                .create_command_encoder(&CommandEncoderDescriptor {
+                    label: Some("Environment Map Baking Command Encoder"),
+                });
+        {
+            let mut compute_pass = command_encoder.begin_compute_pass(&ComputePassDescriptor {
+                label: Some("Environment Map Baking Pass"),
+            });
+
+            for (dispatch, bind_group) in dispatches.iter().zip(&bind_groups) {
+                compute_pass.set_pipeline(dispatch.pipeline);
+                compute_pass.set_bind_group(0, bind_group, &[]);
+                compute_pass.dispatch_workgroups(
+                    dispatch.size.div_ceil(Self::WORKGROUP_SIZE[0]),
+                    dispatch.size.div_ceil(Self::WORKGROUP_SIZE[1]),
+                    dispatch.layers,
+                );
+            }
+        }
+
+        logical_device
+            .queue()
+            .submit(std::iter::once(command_encoder.finish()));
+    }
+
+    /// A single mip level of all faces, for writing via storage texture
+    fn mip_view(cube_texture: &CubeTexture, mip_level: u32) -> TextureView {
+        cube_texture
+            .texture()
+            .make_texture_view_descriptor(&TextureViewDescriptor {
+                label: Some("Environment Map Bake View"),
+                dimension: Some(TextureViewDimension::D2Array),
+                base_mip_level: mip_level,
+                mip_level_count: Some(1),
+                ..Default::default()
+            })
+    }
+
+    fn make_bind_group(
+        &self,
+        logical_device: &LogicalDevice,
+        source: &CubeTexture,
+        output_view: &TextureView,
+        parameters: UBakeParameters,
+    ) -> BindGroup {
+        let parameters_buffer = logical_device
+            .device()
+            .create_buffer_init(&BufferInitDescriptor {
+                label: Some("Environment Map Bake Parameters Buffer"),
+                contents: bytemuck::cast_slice(&[parameters]),
+                usage: BufferUsages::UNIFORM,
+            });
+
+        logical_device
+            .device()
+            .create_bind_group(&BindGroupDescriptor {
+                label: Some("Environment Map Baking Bind Group"),
+                layout: &Self::bind_group_layout(logical_device),
+                entries: &[
+                    BindGroupEntry {
+                        binding: 0,
+                        resource: BindingResource::TextureView(source.view()),
+                    },
+                    BindGroupEntry {
+                        binding: 1,
+                        resource: BindingResource::Sampler(source.sampler()),
+                    },
+                    BindGroupEntry {
+                        binding: 2,
+                        resource: BindingResource::TextureView(output_view),
+                    },
+                    BindGroupEntry {
+                        binding: 3,
+                        resource: parameters_buffer.as_entire_binding(),
+                    },
+                ],
+            })
+    }
+}
@@ -0,0 +1,21 @@
+use bytemuck::{Pod, Zeroable};
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Pod, Zeroable)]
+pub struct UBakeParameters {
+    roughness: f32,
+    sample_count: u32,
+    size: u32,
+    _padding: u32,
+}
+
+impl UBakeParameters {
+    pub fn new(roughness: f32, sample_count: u32, size: u32) -> Self {
+        Self {
+            roughness,
+            sample_count,
+            size,
+            _padding: 0,
+        }
+    }
+}
@@ -0,0 +1,187 @@
+use std::{mem::size_of, num::NonZeroU64};
+
+use wgpu::{
+    BindGroup, BindGroupDescriptor, BindGroupEntry, BindGroupLayout, BindGroupLayoutDescriptor,
+    BindGroupLayoutEntry, BindingType, Buffer, BufferBindingType, BufferUsages, ComputePass,
+    ComputePipeline, ComputePipelineDescriptor, ErrorFilter, PipelineLayoutDescriptor,
+    ShaderStages,
+};
+
+use crate::engine::{
+    BufferHelper, EngineError, EngineResult, Frustum, GpuCullingData, LogicalDevice,
+    PreprocessedShader, ShaderPreprocessor, TMesh, UFrustum,
+};
+
+/// Culls the instances of meshes on the GPU.
+///
+/// Each mesh with [`GpuCullingData`] gets a compute dispatch that tests
+/// every instance against the frustum, compacts the visible instances
+/// into the visible instance buffer and writes the arguments for a
+/// `draw_indexed_indirect` call.
+/// This keeps the CPU time flat, no matter how many instances there are.
+pub struct InstanceCuller {
+    compute_pipeline: ComputePipeline,
+    frustum_buffer: Buffer,
+    frustum_bind_group: BindGroup,
+}
+
+impl InstanceCuller {
+    pub const WORKGROUP_SIZE: u32 = 64;
+
+    pub const SHADER_PATH: &'static str = "instance_culling.wgsl";
+
+    pub const FRUSTUM_BIND_GROUP_LAYOUT_DESCRIPTOR: BindGroupLayoutDescriptor<'static> =
+        BindGroupLayoutDescriptor {
+            label: Some("Frustum Bind Group Layout"),
+            entries: &[BindGroupLayoutEntry {
+                binding: 0,
+                visibility: ShaderStages::COMPUTE,
+                ty: BindingType::Buffer {
+                    ty: BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: NonZeroU64::new(size_of::<UFrustum>() as u64),
+                },
+                count: None,
+            }],
+        };
+
+    pub fn new(logical_device: &LogicalDevice) -> Self {
+        let frustum_buffer = logical_device.make_buffer(
+            Some("Frustum Buffer"),
+            &[UFrustum::empty()],
+            BufferUsages::UNIFORM | BufferUsages::COPY_DST,
+        );
+
+        let frustum_bind_group_layout = Self::frustum_bind_group_layout(logical_device);
+        let frustum_bind_group = logical_device
+            .device()
+            .create_bind_group(&BindGroupDescriptor {
+                label: Some("Frustum Bind Group"),
+                layout: &frustum_bind_group_layout,
+                entries: &[BindGroupEntry {
+                    binding: 0,
+                    resource: frustum_buffer.as_entire_binding(),
+                }],
+            });
+
+        let compute_pipeline = ShaderPreprocessor::new()
+            .process_file(Self::SHADER_PATH)
+            .and_then(|x| Self::make_compute_pipeline(logical_device, &x))
+            .expect("Built-in instance culling shader is invalid");
+
+        Self {
+            compute_pipeline,
+            frustum_buffer,
+            frustum_bind_group,
+        }
+    }
+
+    /// Recreates the compute pipeline if its shader depends on one of the changed shaders.
+    /// Keeps the old pipeline if the new one fails to compile.
+    #[cfg(debug_assertions)]
+    pub fn reload_shader(
+        &mut self,
+        logical_device: &LogicalDevice,
+        changed_shaders: &[String],
+    ) -> EngineResult<()> {
+        let shader = ShaderPreprocessor::new().process_file(Self::SHADER_PATH)?;
+        if changed_shaders.iter().any(|x| shader.depends_on(x)) {
+            self.compute_pipeline = Self::make_compute_pipeline(logical_device, &shader)?;
+            log::info!("Reloaded instance culling pipeline");
+        }
+
+        Ok(())
+    }
+
+    fn make_compute_pipeline(
+        logical_device: &LogicalDevice,
+        shader: &PreprocessedShader,
+    ) -> EngineResult<ComputePipeline> {
+        let reflection = shader.reflect()?;
+        reflection.validate_bind_group_layouts(
+            &["cs_main"],
+            &[
+                &Self::FRUSTUM_BIND_GROUP_LAYOUT_DESCRIPTOR,
+                &GpuCullingData::BIND_GROUP_LAYOUT_DESCRIPTOR,
+            ],
+        )?;
+        let shader = reflection.create_shader_module(logical_device, "Instance Culling Shader");
+
+        logical_device
+            .device()
+            .push_error_scope(ErrorFilter::Validation);
+
+        let pipeline_layout =
+            logical_device
+                .device()
+                .create_pipeline_layout(&PipelineLayoutDescriptor {
+                    label: Some("Instance Culling Pipeline Layout"),
+                    bind_group_layouts: &[
+                        &Self::frustum_bind_group_layout(logical_device),
+                        &GpuCullingData::bind_group_layout(logical_device),
+                    ],
+                    push_constant_ranges: &[],
+                });
+
+        let compute_pipeline =
+            logical_device
+                .device()
+                .create_compute_pipeline(&ComputePipelineDescriptor {
+                    label: Some("Instance Culling Pipeline"),
+                    layout: Some(&pipeline_layout),
+                    module: &shader,
+                    entry_point: "cs_main",
+                });
+
+        if let Some(error) = pollster::block_on(logical_device.device().pop_error_scope()) {
+            return Err(EngineError::ShaderError(format!(
+                "Instance culling pipeline: {}",
+                error
+            )));
+        }
+
+        Ok(compute_pipeline)
+    }
+
+    pub fn frustum_bind_group_layout(logical_device: &LogicalDevice) -> BindGroupLayout {
+        logical_device
+            .device()
+            .create_bind_group_layout(&Self::FRUSTUM_BIND_GROUP_LAYOUT_DESCRIPTOR)
+    }
+
+    pub fn update_frustum(&self, logical_device: &LogicalDevice, frustum: &Frustum) {
+        logical_device.queue().write_buffer(
+            &self.frustum_buffer,
+            0,
+            bytemuck::cast_slice(&[UFrustum::from_frustum(frustum)]),
+        );
+    }
+
+    /// Resets the indirect arguments of the given meshes.
+    /// Must be called before [`Self::cull`] is recorded.
+    pub fn prepare(&self, logical_device: &LogicalDevice, meshes: &[&dyn TMesh]) {
+        meshes
+            .iter()
+            .filter_map(|x| x.gpu_culling_data())
+            .for_each(|x| x.reset(logical_device));
+    }
+
+    /// Records the culling dispatches for the given meshes.
+    /// Meshes without [`GpuCullingData`] are skipped.
+    pub fn cull<'a>(&'a self, compute_pass: &mut ComputePass<'a>, meshes: &[&'a dyn TMesh]) {
+        compute_pass.set_pipeline(&self.compute_pipeline);
+        compute_pass.set_bind_group(0, &self.frustum_bind_group, &[]);
+
+        meshes
+            .iter()
+            .filter_map(|x| x.gpu_culling_data())
+            .for_each(|x| {
+                compute_pass.set_bind_group(1, x.bind_group(), &[]);
+                compute_pass.dispatch_workgroups(
+                    x.instance_count().div_ceil(Self::WORKGROUP_SIZE),
+                    1,
+                    1,
+                );
+            });
+    }
+}
@@ -0,0 +1,300 @@
+use std::{mem::size_of, num::NonZeroU64};
+
+use wgpu::{
+    BindGroup, BindGroupDescriptor, BindGroupEntry, BindGroupLayout, BindGroupLayoutDescriptor,
+    BindGroupLayoutEntry, BindingType, Buffer, BufferAddress, BufferBindingType, BufferDescriptor,
+    BufferUsages, ComputePass, ComputePipeline, ComputePipelineDescriptor, ErrorFilter,
+    PipelineLayoutDescriptor, ShaderStages,
+};
+
+use crate::engine::{
+    Camera, EngineError, EngineResult, LightStorage, LogicalDevice, PreprocessedShader,
+    ShaderPreprocessor,
+};
+
+mod u_light_clustering;
+pub use u_light_clustering::*;
+
+/// Clustered forward shading.
+///
+/// Splits the view frustum into a grid of clusters (tiles on screen times
+/// exponential depth slices) and bins the point and spot lights into them
+/// on the GPU.
+/// Fragments then only shade the lights of their own cluster instead of
+/// every light in the world.
+/// Directional lights affect everything and aren't clustered.
+pub struct LightClusterer {
+    compute_pipeline: ComputePipeline,
+    clustering_buffer: Buffer,
+    compute_bind_group: BindGroup,
+    bind_group: BindGroup,
+}
+
+impl LightClusterer {
+    /// Clusters along X (screen width), Y (screen height) and Z (view depth)
+    pub const GRID_SIZE: [u32; 3] = [16, 9, 24];
+    pub const CLUSTER_COUNT: u32 = Self::GRID_SIZE[0] * Self::GRID_SIZE[1] * Self::GRID_SIZE[2];
+
+    /// Lights exceeding this amount in a single cluster are dropped
+    pub const MAX_LIGHTS_PER_CLUSTER: u32 = 128;
+
+    pub const WORKGROUP_SIZE: [u32; 3] = [4, 4, 4];
+
+    pub const SHADER_PATH: &'static str = "light_clustering.wgsl";
+
+    /// Layout used by the clustering compute pass
+    pub const COMPUTE_BIND_GROUP_LAYOUT_DESCRIPTOR: BindGroupLayoutDescriptor<'static> =
+        BindGroupLayoutDescriptor {
+            label: Some("Light Clustering Compute Bind Group Layout"),
+            entries: &[
+                BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: ShaderStages::COMPUTE,
+                    ty: BindingType::Buffer {
+                        ty: BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: NonZeroU64::new(size_of::<ULightClustering>() as u64),
+                    },
+                    count: None,
+                },
+                BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: ShaderStages::COMPUTE,
+                    ty: BindingType::Buffer {
+                        ty: BufferBindingType::Storage { read_only: false },
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: ShaderStages::COMPUTE,
+                    ty: BindingType::Buffer {
+                        ty: BufferBindingType::Storage { read_only: false },
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+            ],
+        };
+
+    /// Layout used by the main pass to look up the lights of a cluster
+    pub const BIND_GROUP_LAYOUT_DESCRIPTOR: BindGroupLayoutDescriptor<'static> =
+        BindGroupLayoutDescriptor {
+            label: Some("Light Clusters Bind Group Layout"),
+            entries: &[
+                BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Buffer {
+                        ty: BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: NonZeroU64::new(size_of::<ULightClustering>() as u64),
+                    },
+                    count: None,
+                },
+                BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Buffer {
+                        ty: BufferBindingType::Storage { read_only: true },
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Buffer {
+                        ty: BufferBindingType::Storage { read_only: true },
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+            ],
+        };
+
+    pub fn new(logical_device: &LogicalDevice) -> Self {
+        let clustering_buffer = logical_device.device().create_buffer(&BufferDescriptor {
+            label: Some("Light Clustering Buffer"),
+            size: size_of::<ULightClustering>() as BufferAddress,
+            usage: BufferUsages::UNIFORM | BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        // Point & spot light count per cluster
+        let cluster_lights_buffer = logical_device.device().create_buffer(&BufferDescriptor {
+            label: Some("Cluster Lights Buffer"),
+            size: (Self::CLUSTER_COUNT as usize * size_of::<[u32; 2]>()) as BufferAddress,
+            usage: BufferUsages::STORAGE,
+            mapped_at_creation: false,
+        });
+        let light_indices_buffer = logical_device.device().create_buffer(&BufferDescriptor {
+            label: Some("Cluster Light Indices Buffer"),
+            size: (Self::CLUSTER_COUNT as usize
+                * Self::MAX_LIGHTS_PER_CLUSTER as usize
+                * size_of::<u32>()) as BufferAddress,
+            usage: BufferUsages::STORAGE,
+            mapped_at_creation: false,
+        });
+
+        let entries = [
+            BindGroupEntry {
+                binding: 0,
+                resource: clustering_buffer.as_entire_binding(),
+            },
+            BindGroupEntry {
+                binding: 1,
+                resource: cluster_lights_buffer.as_entire_binding(),
+            },
+            BindGroupEntry {
+                binding: 2,
+                resource: light_indices_buffer.as_entire_binding(),
+            },
+        ];
+
+        let compute_bind_group_layout = Self::compute_bind_group_layout(logical_device);
+        let compute_bind_group = logical_device
+            .device()
+            .create_bind_group(&BindGroupDescriptor {
+                label: Some("Light Clustering Compute Bind Group"),
+                layout: &compute_bind_group_layout,
+                entries: &entries,
+            });
+        let bind_group = logical_device
+            .device()
+            .create_bind_group(&BindGroupDescriptor {
+                label: Some("Light Clusters Bind Group"),
+                layout: &Self::bind_group_layout(logical_device),
+                entries: &entries,
+            });
+
+        let compute_pipeline = ShaderPreprocessor::new()
+            .process_file(Self::SHADER_PATH)
+            .and_then(|x| Self::make_compute_pipeline(logical_device, &x))
+            .expect("Built-in light clustering shader is invalid");
+
+        Self {
+            compute_pipeline,
+            clustering_buffer,
+            compute_bind_group,
+            bind_group,
+        }
+    }
+
+    /// Recreates the compute pipeline if its shader depends on one of the changed shaders.
+    /// Keeps the old pipeline if the new one fails to compile.
+    #[cfg(debug_assertions)]
+    pub fn reload_shader(
+        &mut self,
+        logical_device: &LogicalDevice,
+        changed_shaders: &[String],
+    ) -> EngineResult<()> {
+        let shader = ShaderPreprocessor::new().process_file(Self::SHADER_PATH)?;
+        if changed_shaders.iter().any(|x| shader.depends_on(x)) {
+            self.compute_pipeline = Self::make_compute_pipeline(logical_device, &shader)?;
+            log::info!("Reloaded light clustering pipeline");
+        }
+
+        Ok(())
+    }
+
+    fn make_compute_pipeline(
+        logical_device: &LogicalDevice,
+        shader: &PreprocessedShader,
+    ) -> EngineResult<ComputePipeline> {
+        let reflection = shader.reflect()?;
+        reflection.validate_bind_group_layouts(
+            &["cs_main"],
+            &[
+                &Self::COMPUTE_BIND_GROUP_LAYOUT_DESCRIPTOR,
+                &LightStorage::BIND_GROUP_LAYOUT_DESCRIPTOR,
+            ],
+        )?;
+        let shader = reflection.create_shader_module(logical_device, "Light Clustering Shader");
+
+        logical_device
+            .device()
+            .push_error_scope(ErrorFilter::Validation);
+
+        let pipeline_layout =
+            logical_device
+                .device()
+                .create_pipeline_layout(&PipelineLayoutDescriptor {
+                    label: Some("Light Clustering Pipeline Layout"),
+                    bind_group_layouts: &[
+                        &Self::compute_bind_group_layout(logical_device),
+                        &LightStorage::bind_group_layout(logical_device),
+                    ],
+                    push_constant_ranges: &[],
+                });
+
+        let compute_pipeline =
+            logical_device
+                .device()
+                .create_compute_pipeline(&ComputePipelineDescriptor {
+                    label: Some("Light Clustering Pipeline"),
+                    layout: Some(&pipeline_layout),
+                    module: &shader,
+                    entry_point: "cs_main",
+                });
+
+        if let Some(error) = pollster::block_on(logical_device.device().pop_error_scope()) {
+            return Err(EngineError::ShaderError(format!(
+                "Light clustering pipeline: {}",
+                error
+            )));
+        }
+
+        Ok(compute_pipeline)
+    }
+
+    pub fn compute_bind_group_layout(logical_device: &LogicalDevice) -> BindGroupLayout {
+        logical_device
+            .device()
+            .create_bind_group_layout(&Self::COMPUTE_BIND_GROUP_LAYOUT_DESCRIPTOR)
+    }
+
+    pub fn bind_group_layout(logical_device: &LogicalDevice) -> BindGroupLayout {
+        logical_device
+            .device()
+            .create_bind_group_layout(&Self::BIND_GROUP_LAYOUT_DESCRIPTOR)
+    }
+
+    /// Uploads the camera and screen size the clusters are built from
+    pub fn update(&self, logical_device: &LogicalDevice, camera: &Camera, width: u32, height: u32) {
+        logical_device.queue().write_buffer(
+            &self.clustering_buffer,
+            0,
+            bytemuck::cast_slice(&[ULightClustering::new(
+                camera,
+                [width as f32, height as f32],
+                Self::GRID_SIZE,
+                Self::MAX_LIGHTS_PER_CLUSTER,
+            )]),
+        );
+    }
+
+    /// Records the clustering dispatch
+    pub fn cluster<'a>(
+        &'a self,
+        compute_pass: &mut ComputePass<'a>,
+        light_storage: &'a LightStorage,
+    ) {
+        compute_pass.set_pipeline(&self.compute_pipeline);
+        compute_pass.set_bind_group(0, &self.compute_bind_group, &[]);
+        compute_pass.set_bind_group(1, light_storage.bind_group(), &[]);
+        compute_pass.dispatch_workgroups(
+            Self::GRID_SIZE[0].div_ceil(Self::WORKGROUP_SIZE[0]),
+            Self::GRID_SIZE[1].div_ceil(Self::WORKGROUP_SIZE[1]),
+            Self::GRID_SIZE[2].div_ceil(Self::WORKGROUP_SIZE[2]),
+        );
+    }
+
+    pub fn bind_group(&self) -> &BindGroup {
+        &self.bind_group
+    }
+}
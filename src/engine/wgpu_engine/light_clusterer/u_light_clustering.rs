@@ -0,0 +1,41 @@
+use bytemuck::{Pod, Zeroable};
+use cgmath::{Matrix4, SquareMatrix};
+
+use crate::engine::Camera;
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Pod, Zeroable)]
+pub struct ULightClustering {
+    view_matrix: [[f32; 4]; 4],
+    inverse_projection_matrix: [[f32; 4]; 4],
+    screen_size: [f32; 2],
+    z_near: f32,
+    z_far: f32,
+    grid_size: [u32; 3],
+    max_lights_per_cluster: u32,
+}
+
+impl ULightClustering {
+    pub fn new(
+        camera: &Camera,
+        screen_size: [f32; 2],
+        grid_size: [u32; 3],
+        max_lights_per_cluster: u32,
+    ) -> Self {
+        let projection = camera.projection();
+        let inverse_projection_matrix = projection
+            .calculate_matrix()
+            .invert()
+            .unwrap_or_else(Matrix4::identity);
+
+        Self {
+            view_matrix: camera.calculate_matrix().into(),
+            inverse_projection_matrix: inverse_projection_matrix.into(),
+            screen_size,
+            z_near: projection.znear(),
+            z_far: projection.zfar(),
+            grid_size,
+            max_lights_per_cluster,
+        }
+    }
+}
@@ -3,3 +3,27 @@ pub use wgpu_computing_engine::*;
 
 mod wgpu_rendering_engine;
 pub use wgpu_rendering_engine::*;
+
+mod instance_culler;
+pub use instance_culler::*;
+
+mod shadow_renderer;
+pub use shadow_renderer::*;
+
+mod light_clusterer;
+pub use light_clusterer::*;
+
+mod post_processor;
+pub use post_processor::*;
+
+mod skybox_renderer;
+pub use skybox_renderer::*;
+
+mod environment_map_baker;
+pub use environment_map_baker::*;
+
+mod render_graph;
+pub use render_graph::*;
+
+mod debug_draw;
+pub use debug_draw::*;
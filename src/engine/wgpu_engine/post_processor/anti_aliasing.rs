@@ -0,0 +1,10 @@
+/// Screen-space anti-aliasing, applied to the final LDR image.
+/// Independent of (and combinable with) MSAA.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AntiAliasing {
+    #[default]
+    None,
+
+    /// Fast approximate anti-aliasing
+    Fxaa,
+}
@@ -0,0 +1,591 @@
+use std::{mem::size_of, num::NonZeroU64, time::Instant};
+
+use wgpu::{
+    BindGroup, BindGroupDescriptor, BindGroupEntry, BindGroupLayout, BindGroupLayoutDescriptor,
+    BindGroupLayoutEntry, BindingType, BlendComponent, BlendFactor, BlendOperation, BlendState,
+    Buffer, BufferAddress, BufferBindingType, BufferDescriptor, BufferUsages, Color,
+    ColorTargetState, ColorWrites, CommandEncoder, FragmentState, LoadOp, MultisampleState,
+    Operations, PipelineLayoutDescriptor, PrimitiveState, RenderPassColorAttachment,
+    RenderPassDescriptor, RenderPipeline, RenderPipelineDescriptor, SamplerBindingType,
+    ShaderModule, ShaderStages, Texture, TextureFormat, TextureSampleType, TextureView,
+    TextureViewDimension, VertexState,
+};
+
+use crate::engine::{EngineResult, LogicalDevice, TTexture};
+
+mod tonemapper;
+pub use tonemapper::*;
+
+mod anti_aliasing;
+pub use anti_aliasing::*;
+
+mod post_process_settings;
+pub use post_process_settings::*;
+
+mod u_post_process_settings;
+pub use u_post_process_settings::*;
+
+mod u_post_process_globals;
+pub use u_post_process_globals::*;
+
+mod post_process_stage;
+pub use post_process_stage::*;
+
+mod post_process_pass_descriptor;
+pub use post_process_pass_descriptor::*;
+
+mod post_process_pass;
+pub use post_process_pass::*;
+
+mod post_process_change;
+pub use post_process_change::*;
+
+mod post_process_targets;
+pub use post_process_targets::*;
+
+mod post_process_pipelines;
+pub use post_process_pipelines::*;
+
+/// Post-processing chain.
+///
+/// The scene gets rendered into an HDR texture (see [`Self::scene_view`]),
+/// which then runs through:
+/// 1. Custom passes of [`PostProcessStage::Hdr`]
+/// 2. Bloom (threshold, downsample & upsample mip chain)
+/// 3. Exposure, bloom composition & tonemapping into an LDR texture
+/// 4. Custom passes of [`PostProcessStage::Ldr`]
+/// 5. Anti-aliasing (e.g. FXAA), see [`AntiAliasing`]
+/// 6. A final blit onto the surface
+pub struct PostProcessor {
+    settings: PostProcessSettings,
+    ldr_texture_format: TextureFormat,
+    width: u32,
+    height: u32,
+    start_time: Instant,
+    frame: u32,
+    globals_buffer: Buffer,
+    settings_buffer: Buffer,
+    settings_bind_group: BindGroup,
+    targets: PostProcessTargets,
+    pipelines: PostProcessPipelines,
+    passes: Vec<PostProcessPass>,
+}
+
+impl PostProcessor {
+    /// Format the scene and all HDR passes render into
+    pub const HDR_TEXTURE_FORMAT: TextureFormat = TextureFormat::Rgba16Float;
+
+    /// Fullscreen vertex shader and bindings shared by all passes,
+    /// included before the source of custom passes
+    pub const COMMON_SHADER_PATH: &'static str = "common/post_process.wgsl";
+
+    /// Group 0 of every pass: the output of the previous pass and globals
+    pub const BIND_GROUP_LAYOUT_DESCRIPTOR: BindGroupLayoutDescriptor<'static> =
+        BindGroupLayoutDescriptor {
+            label: Some("Post-Processing Bind Group Layout"),
+            entries: &[
+                BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Texture {
+                        sample_type: TextureSampleType::Float { filterable: true },
+                        view_dimension: TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Sampler(SamplerBindingType::Filtering),
+                    count: None,
+                },
+                BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Buffer {
+                        ty: BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: NonZeroU64::new(size_of::<UPostProcessGlobals>() as u64),
+                    },
+                    count: None,
+                },
+            ],
+        };
+
+    /// Group 1 of the bloom passes
+    pub const SETTINGS_BIND_GROUP_LAYOUT_DESCRIPTOR: BindGroupLayoutDescriptor<'static> =
+        BindGroupLayoutDescriptor {
+            label: Some("Post-Processing Settings Bind Group Layout"),
+            entries: &[BindGroupLayoutEntry {
+                binding: 0,
+                visibility: ShaderStages::FRAGMENT,
+                ty: BindingType::Buffer {
+                    ty: BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: NonZeroU64::new(size_of::<UPostProcessSettings>() as u64),
+                },
+                count: None,
+            }],
+        };
+
+    /// Group 1 of the tonemapping pass
+    pub const TONEMAPPING_BIND_GROUP_LAYOUT_DESCRIPTOR: BindGroupLayoutDescriptor<'static> =
+        BindGroupLayoutDescriptor {
+            label: Some("Tonemapping Bind Group Layout"),
+            entries: &[
+                BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Texture {
+                        sample_type: TextureSampleType::Float { filterable: true },
+                        view_dimension: TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Buffer {
+                        ty: BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: NonZeroU64::new(size_of::<UPostProcessSettings>() as u64),
+                    },
+                    count: None,
+                },
+            ],
+        };
+
+    /// Adds the source onto the target
+    const ADDITIVE_BLEND_STATE: BlendState = BlendState {
+        color: BlendComponent {
+            src_factor: BlendFactor::One,
+            dst_factor: BlendFactor::One,
+            operation: BlendOperation::Add,
+        },
+        alpha: BlendComponent::OVER,
+    };
+
+    pub fn new(
+        logical_device: &LogicalDevice,
+        ldr_texture_format: TextureFormat,
+        width: u32,
+        height: u32,
+        settings: PostProcessSettings,
+    ) -> EngineResult<Self> {
+        let globals_buffer = logical_device.device().create_buffer(&BufferDescriptor {
+            label: Some("Post-Processing Globals Buffer"),
+            size: size_of::<UPostProcessGlobals>() as BufferAddress,
+            usage: BufferUsages::UNIFORM | BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let settings_buffer = logical_device.device().create_buffer(&BufferDescriptor {
+            label: Some("Post-Processing Settings Buffer"),
+            size: size_of::<UPostProcessSettings>() as BufferAddress,
+            usage: BufferUsages::UNIFORM | BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        logical_device.queue().write_buffer(
+            &settings_buffer,
+            0,
+            bytemuck::cast_slice(&[UPostProcessSettings::from_post_process_settings(&settings)]),
+        );
+
+        let settings_bind_group = logical_device
+            .device()
+            .create_bind_group(&BindGroupDescriptor {
+                label: Some("Post-Processing Settings Bind Group"),
+                layout: &Self::settings_bind_group_layout(logical_device),
+                entries: &[BindGroupEntry {
+                    binding: 0,
+                    resource: settings_buffer.as_entire_binding(),
+                }],
+            });
+
+        let targets = PostProcessTargets::new(
+            logical_device,
+            width,
+            height,
+            ldr_texture_format,
+            settings.bloom_mip_count,
+            &globals_buffer,
+            &settings_buffer,
+        )?;
+
+        let pipelines = PostProcessPipelines::new(logical_device, ldr_texture_format)?;
+
+        Ok(Self {
+            settings,
+            ldr_texture_format,
+            width,
+            height,
+            start_time: Instant::now(),
+            frame: 0,
+            globals_buffer,
+            settings_buffer,
+            settings_bind_group,
+            targets,
+            pipelines,
+            passes: vec![],
+        })
+    }
+
+    pub fn bind_group_layout(logical_device: &LogicalDevice) -> BindGroupLayout {
+        logical_device
+            .device()
+            .create_bind_group_layout(&Self::BIND_GROUP_LAYOUT_DESCRIPTOR)
+    }
+
+    pub fn settings_bind_group_layout(logical_device: &LogicalDevice) -> BindGroupLayout {
+        logical_device
+            .device()
+            .create_bind_group_layout(&Self::SETTINGS_BIND_GROUP_LAYOUT_DESCRIPTOR)
+    }
+
+    pub fn tonemapping_bind_group_layout(logical_device: &LogicalDevice) -> BindGroupLayout {
+        logical_device
+            .device()
+            .create_bind_group_layout(&Self::TONEMAPPING_BIND_GROUP_LAYOUT_DESCRIPTOR)
+    }
+
+    /// Makes a pipeline drawing a single fullscreen triangle
+    /// with the vertex shader of [`Self::COMMON_SHADER_PATH`]
+    pub fn make_fullscreen_pipeline(
+        logical_device: &LogicalDevice,
+        label: &str,
+        shader: &ShaderModule,
+        fragment_entry_point: &str,
+        bind_group_layouts: &[&BindGroupLayout],
+        target_format: TextureFormat,
+        blend: Option<BlendState>,
+    ) -> RenderPipeline {
+        let pipeline_layout =
+            logical_device
+                .device()
+                .create_pipeline_layout(&PipelineLayoutDescriptor {
+                    label: Some(label),
+                    bind_group_layouts,
+                    push_constant_ranges: &[],
+                });
+
+        logical_device
+            .device()
+            .create_render_pipeline(&RenderPipelineDescriptor {
+                label: Some(label),
+                layout: Some(&pipeline_layout),
+                vertex: VertexState {
+                    module: shader,
+                    entry_point: "vs_main",
+                    buffers: &[],
+                },
+                fragment: Some(FragmentState {
+                    module: shader,
+                    entry_point: fragment_entry_point,
+                    targets: &[Some(ColorTargetState {
+                        format: target_format,
+                        blend,
+                        write_mask: ColorWrites::ALL,
+                    })],
+                }),
+                primitive: PrimitiveState::default(),
+                depth_stencil: None,
+                multisample: MultisampleState::default(),
+                multiview: None,
+            })
+    }
+
+    /// Recreates all size dependent textures
+    pub fn resize(
+        &mut self,
+        logical_device: &LogicalDevice,
+        width: u32,
+        height: u32,
+    ) -> EngineResult<()> {
+        self.targets = PostProcessTargets::new(
+            logical_device,
+            width,
+            height,
+            self.ldr_texture_format,
+            self.settings.bloom_mip_count,
+            &self.globals_buffer,
+            &self.settings_buffer,
+        )?;
+        self.width = width;
+        self.height = height;
+
+        Ok(())
+    }
+
+    pub fn settings(&self) -> &PostProcessSettings {
+        &self.settings
+    }
+
+    pub fn set_settings(
+        &mut self,
+        logical_device: &LogicalDevice,
+        settings: PostProcessSettings,
+    ) -> EngineResult<()> {
+        let bloom_mip_count_changed = settings.bloom_mip_count != self.settings.bloom_mip_count;
+        self.settings = settings;
+
+        logical_device.queue().write_buffer(
+            &self.settings_buffer,
+            0,
+            bytemuck::cast_slice(&[UPostProcessSettings::from_post_process_settings(&settings)]),
+        );
+
+        if bloom_mip_count_changed {
+            self.resize(logical_device, self.width, self.height)?;
+        }
+
+        Ok(())
+    }
+
+    /// Compiles and adds a custom pass after all existing passes of its stage
+    pub fn add_pass(
+        &mut self,
+        logical_device: &LogicalDevice,
+        descriptor: &PostProcessPassDescriptor,
+    ) -> EngineResult<()> {
+        let target_format = match descriptor.stage {
+            PostProcessStage::Hdr => Self::HDR_TEXTURE_FORMAT,
+            PostProcessStage::Ldr => self.ldr_texture_format,
+        };
+
+        self.passes.push(PostProcessPass::new(
+            logical_device,
+            descriptor,
+            target_format,
+        )?);
+
+        Ok(())
+    }
+
+    /// Removes all custom passes with the given label
+    pub fn remove_pass(&mut self, label: &str) {
+        self.passes.retain(|x| x.label() != label);
+    }
+
+    pub fn passes(&self) -> &[PostProcessPass] {
+        &self.passes
+    }
+
+    /// Recreates the built-in pipelines & custom passes depending on one of the changed
+    /// shaders. Whatever fails to compile keeps its old version, the error gets logged.
+    #[cfg(debug_assertions)]
+    pub fn reload_shaders(&mut self, logical_device: &LogicalDevice, changed_shaders: &[String]) {
+        let pipelines = PostProcessPipelines::depend_on(changed_shaders).and_then(|x| {
+            x.then(|| PostProcessPipelines::new(logical_device, self.ldr_texture_format))
+                .transpose()
+        });
+        match pipelines {
+            Ok(Some(pipelines)) => {
+                self.pipelines = pipelines;
+                log::info!("Reloaded post-processing pipelines");
+            }
+            Ok(None) => {}
+            Err(e) => log::error!(
+                "Failed to reload post-processing pipelines, keeping the old ones: {:?}",
+                e
+            ),
+        }
+
+        for pass in &mut self.passes {
+            if let Err(e) = pass.reload(logical_device, changed_shaders) {
+                log::error!(
+                    "Failed to reload post-processing pass '{}', keeping the old one: {:?}",
+                    pass.label(),
+                    e
+                );
+            }
+        }
+    }
+
+    pub fn apply_post_process_change(
+        &mut self,
+        logical_device: &LogicalDevice,
+        post_process_change: PostProcessChange,
+    ) -> EngineResult<()> {
+        match post_process_change {
+            PostProcessChange::Settings(settings) => self.set_settings(logical_device, settings),
+            PostProcessChange::AddPass(descriptor) => self.add_pass(logical_device, &descriptor),
+            PostProcessChange::RemovePass(label) => {
+                self.remove_pass(&label);
+                Ok(())
+            }
+        }
+    }
+
+    /// HDR texture the scene gets rendered (or resolved) into
+    pub fn scene_view(&self) -> &TextureView {
+        self.targets.hdr_textures()[0].view()
+    }
+
+    /// Texture of [`Self::scene_view`], imported into the [`crate::engine::RenderGraph`]
+    pub fn scene_texture(&self) -> &Texture {
+        self.targets.hdr_textures()[0].texture()
+    }
+
+    /// Runs the whole chain, ending with the result on the surface
+    pub fn render(
+        &mut self,
+        logical_device: &LogicalDevice,
+        command_encoder: &mut CommandEncoder,
+        surface_texture_view: &TextureView,
+    ) {
+        logical_device.queue().write_buffer(
+            &self.globals_buffer,
+            0,
+            bytemuck::cast_slice(&[UPostProcessGlobals {
+                resolution: [self.width as f32, self.height as f32],
+                time: self.start_time.elapsed().as_secs_f32(),
+                frame: self.frame,
+            }]),
+        );
+        self.frame = self.frame.wrapping_add(1);
+
+        let targets = &self.targets;
+
+        // Custom HDR passes
+        let mut hdr_index = 0;
+        for pass in self
+            .passes
+            .iter()
+            .filter(|x| x.stage() == PostProcessStage::Hdr)
+        {
+            Self::fullscreen_pass(
+                command_encoder,
+                pass.label(),
+                pass.render_pipeline(),
+                targets.hdr_textures()[1 - hdr_index].view(),
+                LoadOp::Clear(Color::BLACK),
+                &[&targets.hdr_bind_groups()[hdr_index]],
+            );
+            hdr_index = 1 - hdr_index;
+        }
+
+        // Bloom
+        if self.settings.bloom_enabled {
+            let mip_views = targets.bloom_mip_views();
+            let mip_bind_groups = targets.bloom_bind_groups();
+
+            Self::fullscreen_pass(
+                command_encoder,
+                "Bloom Prefilter Pass",
+                &self.pipelines.bloom_prefilter,
+                &mip_views[0],
+                LoadOp::Clear(Color::BLACK),
+                &[
+                    &targets.hdr_bind_groups()[hdr_index],
+                    &self.settings_bind_group,
+                ],
+            );
+
+            for mip in 1..mip_views.len() {
+                Self::fullscreen_pass(
+                    command_encoder,
+                    "Bloom Downsample Pass",
+                    &self.pipelines.bloom_downsample,
+                    &mip_views[mip],
+                    LoadOp::Clear(Color::BLACK),
+                    &[&mip_bind_groups[mip - 1], &self.settings_bind_group],
+                );
+            }
+
+            for mip in (1..mip_views.len()).rev() {
+                Self::fullscreen_pass(
+                    command_encoder,
+                    "Bloom Upsample Pass",
+                    &self.pipelines.bloom_upsample,
+                    &mip_views[mip - 1],
+                    LoadOp::Load,
+                    &[&mip_bind_groups[mip], &self.settings_bind_group],
+                );
+            }
+        }
+
+        // Exposure, bloom & tonemapping
+        Self::fullscreen_pass(
+            command_encoder,
+            "Tonemapping Pass",
+            &self.pipelines.tonemapping,
+            targets.ldr_textures()[0].view(),
+            LoadOp::Clear(Color::BLACK),
+            &[
+                &targets.hdr_bind_groups()[hdr_index],
+                targets.tonemapping_bind_group(),
+            ],
+        );
+
+        // Custom LDR passes
+        let mut ldr_index = 0;
+        for pass in self
+            .passes
+            .iter()
+            .filter(|x| x.stage() == PostProcessStage::Ldr)
+        {
+            Self::fullscreen_pass(
+                command_encoder,
+                pass.label(),
+                pass.render_pipeline(),
+                targets.ldr_textures()[1 - ldr_index].view(),
+                LoadOp::Clear(Color::BLACK),
+                &[&targets.ldr_bind_groups()[ldr_index]],
+            );
+            ldr_index = 1 - ldr_index;
+        }
+
+        // Anti-aliasing
+        match self.settings.anti_aliasing {
+            AntiAliasing::None => (),
+            AntiAliasing::Fxaa => {
+                Self::fullscreen_pass(
+                    command_encoder,
+                    "FXAA Pass",
+                    &self.pipelines.fxaa,
+                    targets.ldr_textures()[1 - ldr_index].view(),
+                    LoadOp::Clear(Color::BLACK),
+                    &[&targets.ldr_bind_groups()[ldr_index]],
+                );
+                ldr_index = 1 - ldr_index;
+            }
+        }
+
+        // Onto the surface
+        Self::fullscreen_pass(
+            command_encoder,
+            "Blit Pass",
+            &self.pipelines.blit,
+            surface_texture_view,
+            LoadOp::Clear(Color::BLACK),
+            &[&targets.ldr_bind_groups()[ldr_index]],
+        );
+    }
+
+    fn fullscreen_pass(
+        command_encoder: &mut CommandEncoder,
+        label: &str,
+        render_pipeline: &RenderPipeline,
+        target: &TextureView,
+        load: LoadOp<Color>,
+        bind_groups: &[&BindGroup],
+    ) {
+        let mut render_pass = command_encoder.begin_render_pass(&RenderPassDescriptor {
+            label: Some(label),
+            color_attachments: &[Some(RenderPassColorAttachment {
+                view: target,
+                resolve_target: None,
+                ops: Operations { load, store: true },
+            })],
+            depth_stencil_attachment: None,
+        });
+
+        render_pass.set_pipeline(render_pipeline);
+        for (index, bind_group) in bind_groups.iter().enumerate() {
+            render_pass.set_bind_group(index as u32, bind_group, &[]);
+        }
+        render_pass.draw(0..3, 0..1);
+    }
+}
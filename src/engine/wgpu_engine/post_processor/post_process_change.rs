@@ -0,0 +1,14 @@
+use super::{PostProcessPassDescriptor, PostProcessSettings};
+
+/// Changes to the post-processing chain
+#[derive(Debug, Clone, PartialEq)]
+pub enum PostProcessChange {
+    /// Replaces the settings of the built-in passes
+    Settings(PostProcessSettings),
+
+    /// Adds a custom pass after all existing passes of its stage
+    AddPass(PostProcessPassDescriptor),
+
+    /// Removes all custom passes with the given label
+    RemovePass(String),
+}
@@ -0,0 +1,96 @@
+use wgpu::{ErrorFilter, RenderPipeline, TextureFormat};
+
+use crate::engine::{
+    EngineError, EngineResult, LogicalDevice, PreprocessedShader, ShaderPreprocessor,
+};
+
+use super::{PostProcessPassDescriptor, PostProcessStage, PostProcessor};
+
+/// A compiled custom post-processing pass
+pub struct PostProcessPass {
+    descriptor: PostProcessPassDescriptor,
+    /// Only needed to recompile the pass
+    #[cfg(debug_assertions)]
+    target_format: TextureFormat,
+    render_pipeline: RenderPipeline,
+}
+
+impl PostProcessPass {
+    /// Compiles the pass for the given target format.
+    /// Invalid WGSL results in an [`EngineError::ShaderError`] instead of a panic.
+    pub fn new(
+        logical_device: &LogicalDevice,
+        descriptor: &PostProcessPassDescriptor,
+        target_format: TextureFormat,
+    ) -> EngineResult<Self> {
+        let shader = Self::preprocess(descriptor)?;
+        let reflection = shader.reflect()?;
+        reflection.validate_bind_group_layouts(
+            &["vs_main", "fs_main"],
+            &[&PostProcessor::BIND_GROUP_LAYOUT_DESCRIPTOR],
+        )?;
+        let shader = reflection.create_shader_module(logical_device, &descriptor.label);
+
+        logical_device
+            .device()
+            .push_error_scope(ErrorFilter::Validation);
+        let render_pipeline = PostProcessor::make_fullscreen_pipeline(
+            logical_device,
+            &descriptor.label,
+            &shader,
+            "fs_main",
+            &[&PostProcessor::bind_group_layout(logical_device)],
+            target_format,
+            None,
+        );
+
+        if let Some(error) = pollster::block_on(logical_device.device().pop_error_scope()) {
+            return Err(EngineError::ShaderError(format!(
+                "Post-processing pass '{}': {}",
+                descriptor.label, error
+            )));
+        }
+
+        Ok(Self {
+            descriptor: descriptor.clone(),
+            #[cfg(debug_assertions)]
+            target_format,
+            render_pipeline,
+        })
+    }
+
+    /// Recompiles the pass if its source includes one of the changed shaders.
+    /// Keeps the old pipeline if the new one fails to compile.
+    #[cfg(debug_assertions)]
+    pub fn reload(
+        &mut self,
+        logical_device: &LogicalDevice,
+        changed_shaders: &[String],
+    ) -> EngineResult<()> {
+        let shader = Self::preprocess(&self.descriptor)?;
+        if changed_shaders.iter().any(|x| shader.depends_on(x)) {
+            *self = Self::new(logical_device, &self.descriptor, self.target_format)?;
+            log::info!("Reloaded post-processing pass '{}'", self.label());
+        }
+
+        Ok(())
+    }
+
+    pub fn label(&self) -> &str {
+        &self.descriptor.label
+    }
+
+    pub fn stage(&self) -> PostProcessStage {
+        self.descriptor.stage
+    }
+
+    pub fn render_pipeline(&self) -> &RenderPipeline {
+        &self.render_pipeline
+    }
+
+    fn preprocess(descriptor: &PostProcessPassDescriptor) -> EngineResult<PreprocessedShader> {
+        ShaderPreprocessor::new()
+            .with_prelude(PostProcessor::COMMON_SHADER_PATH)
+            .process_source(&descriptor.label, &descriptor.source)
+    }
+}
@@ -0,0 +1,32 @@
+use super::PostProcessStage;
+
+/// Describes a custom post-processing pass.
+///
+/// `source` is WGSL code containing a fragment entry point `fs_main`
+/// taking a `VertexOutput` and returning a `@location(0) vec4<f32>`.
+/// `common/post_process.wgsl` gets included before it, which provides the
+/// fullscreen vertex shader and the following bindings:
+/// - `source_texture`: output of the previous pass
+/// - `source_sampler`: linear, clamped sampler
+/// - `globals`: screen resolution, time and frame counter
+#[derive(Debug, Clone, PartialEq)]
+pub struct PostProcessPassDescriptor {
+    /// Used to identify the pass, e.g. for removing it
+    pub label: String,
+    pub stage: PostProcessStage,
+    pub source: String,
+}
+
+impl PostProcessPassDescriptor {
+    pub fn new<L, S>(label: L, stage: PostProcessStage, source: S) -> Self
+    where
+        L: Into<String>,
+        S: Into<String>,
+    {
+        Self {
+            label: label.into(),
+            stage,
+            source: source.into(),
+        }
+    }
+}
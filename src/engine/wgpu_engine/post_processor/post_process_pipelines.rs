@@ -0,0 +1,176 @@
+use wgpu::{BindGroupLayoutDescriptor, ErrorFilter, RenderPipeline, ShaderModule, TextureFormat};
+
+use crate::engine::{EngineError, EngineResult, LogicalDevice, ShaderPreprocessor};
+
+use super::PostProcessor;
+
+/// Render pipelines of the built-in passes of the [`PostProcessor`]
+pub struct PostProcessPipelines {
+    pub bloom_prefilter: RenderPipeline,
+    pub bloom_downsample: RenderPipeline,
+    pub bloom_upsample: RenderPipeline,
+    pub tonemapping: RenderPipeline,
+    pub fxaa: RenderPipeline,
+    pub blit: RenderPipeline,
+}
+
+impl PostProcessPipelines {
+    /// Shaders of the built-in passes, which include [`PostProcessor::COMMON_SHADER_PATH`] themselves
+    pub const SHADER_PATHS: [&'static str; 4] =
+        ["bloom.wgsl", "tonemapping.wgsl", "fxaa.wgsl", "blit.wgsl"];
+
+    /// Invalid shaders result in an [`EngineError::ShaderError`] instead of a panic
+    pub fn new(
+        logical_device: &LogicalDevice,
+        ldr_texture_format: TextureFormat,
+    ) -> EngineResult<Self> {
+        // Made before the error scope, their errors are mapped onto the original files
+        let bloom_shader = Self::make_shader(
+            logical_device,
+            "Bloom Shader",
+            "bloom.wgsl",
+            &["vs_main", "fs_prefilter", "fs_downsample", "fs_upsample"],
+            &[
+                &PostProcessor::BIND_GROUP_LAYOUT_DESCRIPTOR,
+                &PostProcessor::SETTINGS_BIND_GROUP_LAYOUT_DESCRIPTOR,
+            ],
+        )?;
+        let tonemapping_shader = Self::make_shader(
+            logical_device,
+            "Tonemapping Shader",
+            "tonemapping.wgsl",
+            &["vs_main", "fs_main"],
+            &[
+                &PostProcessor::BIND_GROUP_LAYOUT_DESCRIPTOR,
+                &PostProcessor::TONEMAPPING_BIND_GROUP_LAYOUT_DESCRIPTOR,
+            ],
+        )?;
+        let fxaa_shader = Self::make_shader(
+            logical_device,
+            "FXAA Shader",
+            "fxaa.wgsl",
+            &["vs_main", "fs_main"],
+            &[&PostProcessor::BIND_GROUP_LAYOUT_DESCRIPTOR],
+        )?;
+        let blit_shader = Self::make_shader(
+            logical_device,
+            "Blit Shader",
+            "blit.wgsl",
+            &["vs_main", "fs_main"],
+            &[&PostProcessor::BIND_GROUP_LAYOUT_DESCRIPTOR],
+        )?;
+
+        logical_device
+            .device()
+            .push_error_scope(ErrorFilter::Validation);
+
+        let bloom_bind_group_layouts = [
+            &PostProcessor::bind_group_layout(logical_device),
+            &PostProcessor::settings_bind_group_layout(logical_device),
+        ];
+        let bloom_prefilter = PostProcessor::make_fullscreen_pipeline(
+            logical_device,
+            "Bloom Prefilter Pipeline",
+            &bloom_shader,
+            "fs_prefilter",
+            &bloom_bind_group_layouts,
+            PostProcessor::HDR_TEXTURE_FORMAT,
+            None,
+        );
+        let bloom_downsample = PostProcessor::make_fullscreen_pipeline(
+            logical_device,
+            "Bloom Downsample Pipeline",
+            &bloom_shader,
+            "fs_downsample",
+            &bloom_bind_group_layouts,
+            PostProcessor::HDR_TEXTURE_FORMAT,
+            None,
+        );
+        let bloom_upsample = PostProcessor::make_fullscreen_pipeline(
+            logical_device,
+            "Bloom Upsample Pipeline",
+            &bloom_shader,
+            "fs_upsample",
+            &bloom_bind_group_layouts,
+            PostProcessor::HDR_TEXTURE_FORMAT,
+            Some(PostProcessor::ADDITIVE_BLEND_STATE),
+        );
+
+        let tonemapping = PostProcessor::make_fullscreen_pipeline(
+            logical_device,
+            "Tonemapping Pipeline",
+            &tonemapping_shader,
+            "fs_main",
+            &[
+                &PostProcessor::bind_group_layout(logical_device),
+                &PostProcessor::tonemapping_bind_group_layout(logical_device),
+            ],
+            ldr_texture_format,
+            None,
+        );
+
+        let fxaa = PostProcessor::make_fullscreen_pipeline(
+            logical_device,
+            "FXAA Pipeline",
+            &fxaa_shader,
+            "fs_main",
+            &[&PostProcessor::bind_group_layout(logical_device)],
+            ldr_texture_format,
+            None,
+        );
+
+        let blit = PostProcessor::make_fullscreen_pipeline(
+            logical_device,
+            "Blit Pipeline",
+            &blit_shader,
+            "fs_main",
+            &[&PostProcessor::bind_group_layout(logical_device)],
+            ldr_texture_format,
+            None,
+        );
+
+        if let Some(error) = pollster::block_on(logical_device.device().pop_error_scope()) {
+            return Err(EngineError::ShaderError(format!(
+                "Post-processing pipelines: {}",
+                error
+            )));
+        }
+
+        Ok(Self {
+            bloom_prefilter,
+            bloom_downsample,
+            bloom_upsample,
+            tonemapping,
+            fxaa,
+            blit,
+        })
+    }
+
+    /// Checks the bindings of the entry points against the layouts first
+    fn make_shader(
+        logical_device: &LogicalDevice,
+        label: &str,
+        path: &str,
+        entry_points: &[&str],
+        bind_group_layouts: &[&BindGroupLayoutDescriptor],
+    ) -> EngineResult<ShaderModule> {
+        let shader = ShaderPreprocessor::new().process_file(path)?;
+        let reflection = shader.reflect()?;
+        reflection.validate_bind_group_layouts(entry_points, bind_group_layouts)?;
+
+        Ok(reflection.create_shader_module(logical_device, label))
+    }
+
+    /// Whether one of the built-in shaders depends on one of the changed shaders
+    #[cfg(debug_assertions)]
+    pub fn depend_on(changed_shaders: &[String]) -> EngineResult<bool> {
+        for path in Self::SHADER_PATHS {
+            let shader = ShaderPreprocessor::new().process_file(path)?;
+            if changed_shaders.iter().any(|x| shader.depends_on(x)) {
+                return Ok(true);
+            }
+        }
+
+        Ok(false)
+    }
+}
@@ -0,0 +1,43 @@
+use super::{AntiAliasing, Tonemapper};
+
+/// Settings of the built-in post-processing passes
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PostProcessSettings {
+    /// Multiplier applied to the HDR color before tonemapping
+    pub exposure: f32,
+
+    pub tonemapper: Tonemapper,
+
+    pub bloom_enabled: bool,
+
+    /// Brightness above which parts of the image start to bloom
+    pub bloom_threshold: f32,
+
+    /// Range below the threshold in which bloom gets faded in
+    pub bloom_knee: f32,
+
+    /// Strength of the bloom added onto the image
+    pub bloom_intensity: f32,
+
+    /// Maximum amount of bloom downsampling steps, each halving the size.
+    /// More steps result in a wider bloom.
+    pub bloom_mip_count: u32,
+
+    /// Applied after all other passes, right before presenting
+    pub anti_aliasing: AntiAliasing,
+}
+
+impl Default for PostProcessSettings {
+    fn default() -> Self {
+        Self {
+            exposure: 1.0,
+            tonemapper: Tonemapper::default(),
+            bloom_enabled: true,
+            bloom_threshold: 1.0,
+            bloom_knee: 0.5,
+            bloom_intensity: 0.05,
+            bloom_mip_count: 6,
+            anti_aliasing: AntiAliasing::default(),
+        }
+    }
+}
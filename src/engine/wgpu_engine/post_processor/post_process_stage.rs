@@ -0,0 +1,9 @@
+/// Where in the post-processing chain a custom pass runs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PostProcessStage {
+    /// Before bloom and tonemapping, on linear HDR colors
+    Hdr,
+
+    /// After tonemapping, on LDR colors in the surface format
+    Ldr,
+}
@@ -0,0 +1,172 @@
+use wgpu::{
+    BindGroup, BindGroupDescriptor, BindGroupEntry, BindingResource, Buffer, Extent3d,
+    TextureFormat, TextureView,
+};
+
+use crate::engine::{EngineResult, LogicalDevice, RenderTargetTexture, TTexture};
+
+use super::PostProcessor;
+
+/// Size dependent textures and bind groups of the [`PostProcessor`]
+pub struct PostProcessTargets {
+    hdr_textures: [RenderTargetTexture; 2],
+    hdr_bind_groups: [BindGroup; 2],
+    ldr_textures: [RenderTargetTexture; 2],
+    ldr_bind_groups: [BindGroup; 2],
+    bloom_texture: RenderTargetTexture,
+    bloom_mip_views: Vec<TextureView>,
+    bloom_bind_groups: Vec<BindGroup>,
+    tonemapping_bind_group: BindGroup,
+}
+
+impl PostProcessTargets {
+    pub fn new(
+        logical_device: &LogicalDevice,
+        width: u32,
+        height: u32,
+        ldr_texture_format: TextureFormat,
+        bloom_mip_count: u32,
+        globals_buffer: &Buffer,
+        settings_buffer: &Buffer,
+    ) -> EngineResult<Self> {
+        let size = Extent3d {
+            width: width.max(1),
+            height: height.max(1),
+            depth_or_array_layers: 1,
+        };
+
+        // HDR & LDR ping-pong textures, passes read from one and write into the other
+        let make_textures = |format, label| -> EngineResult<[RenderTargetTexture; 2]> {
+            Ok([
+                RenderTargetTexture::from_empty(logical_device, size, format, Some(label))?,
+                RenderTargetTexture::from_empty(logical_device, size, format, Some(label))?,
+            ])
+        };
+        let hdr_textures = make_textures(PostProcessor::HDR_TEXTURE_FORMAT, "HDR Texture")?;
+        let ldr_textures = make_textures(ldr_texture_format, "LDR Texture")?;
+
+        let make_bind_group = |view: &TextureView, texture: &RenderTargetTexture| {
+            Self::make_bind_group(logical_device, view, texture, globals_buffer)
+        };
+        let hdr_bind_groups = [
+            make_bind_group(hdr_textures[0].view(), &hdr_textures[0]),
+            make_bind_group(hdr_textures[1].view(), &hdr_textures[1]),
+        ];
+        let ldr_bind_groups = [
+            make_bind_group(ldr_textures[0].view(), &ldr_textures[0]),
+            make_bind_group(ldr_textures[1].view(), &ldr_textures[1]),
+        ];
+
+        // Bloom mip chain, starting at half the screen size.
+        // Stops before the smallest side would fall below two pixels.
+        let max_mip_count = (size.width.min(size.height) / 2).max(1).ilog2();
+        let bloom_mip_count = bloom_mip_count.clamp(1, max_mip_count.max(1));
+        let bloom_texture = RenderTargetTexture::from_empty_mipmapped(
+            logical_device,
+            Extent3d {
+                width: (size.width / 2).max(1),
+                height: (size.height / 2).max(1),
+                depth_or_array_layers: 1,
+            },
+            PostProcessor::HDR_TEXTURE_FORMAT,
+            bloom_mip_count,
+            Some("Bloom Texture"),
+        )?;
+        let bloom_mip_views = (0..bloom_mip_count)
+            .map(|x| bloom_texture.mip_view(x))
+            .collect::<Vec<_>>();
+        let bloom_bind_groups = bloom_mip_views
+            .iter()
+            .map(|x| make_bind_group(x, &bloom_texture))
+            .collect();
+
+        let tonemapping_bind_group =
+            logical_device
+                .device()
+                .create_bind_group(&BindGroupDescriptor {
+                    label: Some("Tonemapping Bind Group"),
+                    layout: &PostProcessor::tonemapping_bind_group_layout(logical_device),
+                    entries: &[
+                        BindGroupEntry {
+                            binding: 0,
+                            resource: BindingResource::TextureView(&bloom_mip_views[0]),
+                        },
+                        BindGroupEntry {
+                            binding: 1,
+                            resource: settings_buffer.as_entire_binding(),
+                        },
+                    ],
+                });
+
+        Ok(Self {
+            hdr_textures,
+            hdr_bind_groups,
+            ldr_textures,
+            ldr_bind_groups,
+            bloom_texture,
+            bloom_mip_views,
+            bloom_bind_groups,
+            tonemapping_bind_group,
+        })
+    }
+
+    fn make_bind_group(
+        logical_device: &LogicalDevice,
+        view: &TextureView,
+        texture: &RenderTargetTexture,
+        globals_buffer: &Buffer,
+    ) -> BindGroup {
+        logical_device
+            .device()
+            .create_bind_group(&BindGroupDescriptor {
+                label: Some("Post-Processing Bind Group"),
+                layout: &PostProcessor::bind_group_layout(logical_device),
+                entries: &[
+                    BindGroupEntry {
+                        binding: 0,
+                        resource: BindingResource::TextureView(view),
+                    },
+                    BindGroupEntry {
+                        binding: 1,
+                        resource: BindingResource::Sampler(texture.sampler()),
+                    },
+                    BindGroupEntry {
+                        binding: 2,
+                        resource: globals_buffer.as_entire_binding(),
+                    },
+                ],
+            })
+    }
+
+    pub fn hdr_textures(&self) -> &[RenderTargetTexture; 2] {
+        &self.hdr_textures
+    }
+
+    pub fn hdr_bind_groups(&self) -> &[BindGroup; 2] {
+        &self.hdr_bind_groups
+    }
+
+    pub fn ldr_textures(&self) -> &[RenderTargetTexture; 2] {
+        &self.ldr_textures
+    }
+
+    pub fn ldr_bind_groups(&self) -> &[BindGroup; 2] {
+        &self.ldr_bind_groups
+    }
+
+    pub fn bloom_texture(&self) -> &RenderTargetTexture {
+        &self.bloom_texture
+    }
+
+    pub fn bloom_mip_views(&self) -> &[TextureView] {
+        &self.bloom_mip_views
+    }
+
+    pub fn bloom_bind_groups(&self) -> &[BindGroup] {
+        &self.bloom_bind_groups
+    }
+
+    pub fn tonemapping_bind_group(&self) -> &BindGroup {
+        &self.tonemapping_bind_group
+    }
+}
@@ -0,0 +1,24 @@
+/// Maps HDR colors into the displayable LDR range
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Tonemapper {
+    /// Colors are clamped to [0, 1]
+    None,
+
+    /// `color / (1 + color)`
+    Reinhard,
+
+    /// Fitted ACES filmic curve
+    #[default]
+    Aces,
+}
+
+impl Tonemapper {
+    /// Identifier used by the tonemapping shader
+    pub fn shader_id(&self) -> u32 {
+        match self {
+            Tonemapper::None => 0,
+            Tonemapper::Reinhard => 1,
+            Tonemapper::Aces => 2,
+        }
+    }
+}
@@ -0,0 +1,10 @@
+use bytemuck::{Pod, Zeroable};
+
+/// Available to every post-processing pass as `globals`
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Pod, Zeroable)]
+pub struct UPostProcessGlobals {
+    pub resolution: [f32; 2],
+    pub time: f32,
+    pub frame: u32,
+}
@@ -0,0 +1,31 @@
+use bytemuck::{Pod, Zeroable};
+
+use super::PostProcessSettings;
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Pod, Zeroable)]
+pub struct UPostProcessSettings {
+    exposure: f32,
+    bloom_threshold: f32,
+    bloom_knee: f32,
+    bloom_intensity: f32,
+    tonemapper: u32,
+    _padding: [u32; 3],
+}
+
+impl UPostProcessSettings {
+    pub fn from_post_process_settings(post_process_settings: &PostProcessSettings) -> Self {
+        Self {
+            exposure: post_process_settings.exposure,
+            bloom_threshold: post_process_settings.bloom_threshold,
+            bloom_knee: post_process_settings.bloom_knee,
+            bloom_intensity: if post_process_settings.bloom_enabled {
+                post_process_settings.bloom_intensity
+            } else {
+                0.0
+            },
+            tonemapper: post_process_settings.tonemapper.shader_id(),
+            _padding: [0; 3],
+        }
+    }
+}
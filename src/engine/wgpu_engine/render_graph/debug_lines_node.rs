@@ -0,0 +1,347 @@
+use std::mem::size_of;
+
+use bytemuck::{Pod, Zeroable};
+use cgmath::Vector3;
+use wgpu::{
+    BlendState, Buffer, BufferAddress, BufferDescriptor, BufferUsages, ColorTargetState,
+    ColorWrites, CompareFunction, DepthBiasState, DepthStencilState, ErrorFilter, FragmentState,
+    LoadOp, MultisampleState, Operations, PipelineLayoutDescriptor, PrimitiveState,
+    PrimitiveTopology, RenderPassColorAttachment, RenderPassDepthStencilAttachment,
+    RenderPassDescriptor, RenderPipeline, RenderPipelineDescriptor, ShaderModule, StencilState,
+    VertexAttribute, VertexBufferLayout, VertexFormat, VertexState, VertexStepMode,
+};
+
+use crate::engine::{
+    Camera, DebugDraw, DebugLine, DepthTexture, EngineError, EngineResult, LogicalDevice,
+    PostProcessor, PreprocessedShader, ShaderPreprocessor, TComputingEngine, WGPURenderingEngine,
+};
+
+use super::{RenderNodeContext, RenderNodeDeclaration, RenderResource, TRenderNode};
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Pod, Zeroable)]
+struct DebugVertex {
+    position: [f32; 3],
+    color: [f32; 3],
+}
+
+impl DebugVertex {
+    const VERTEX_BUFFER_LAYOUT: VertexBufferLayout<'static> = VertexBufferLayout {
+        array_stride: size_of::<Self>() as BufferAddress,
+        step_mode: VertexStepMode::Vertex,
+        attributes: &[
+            VertexAttribute {
+                offset: 0,
+                shader_location: 0,
+                format: VertexFormat::Float32x3,
+            },
+            VertexAttribute {
+                offset: size_of::<[f32; 3]>() as BufferAddress,
+                shader_location: 1,
+                format: VertexFormat::Float32x3,
+            },
+        ],
+    };
+
+    fn new(position: Vector3<f32>, color: Vector3<f32>) -> Self {
+        Self {
+            position: position.into(),
+            color: color.into(),
+        }
+    }
+}
+
+/// Pipelines for one sample count
+struct DebugLinePipelines {
+    sample_count: u32,
+    depth_tested: RenderPipeline,
+    overlay: RenderPipeline,
+}
+
+impl DebugLinePipelines {
+    fn new(
+        logical_device: &LogicalDevice,
+        shader: &PreprocessedShader,
+        sample_count: u32,
+    ) -> EngineResult<Self> {
+        let reflection = shader.reflect()?;
+        reflection.validate_vertex_buffers("vs_main", &[DebugVertex::VERTEX_BUFFER_LAYOUT])?;
+        reflection.validate_bind_group_layouts(
+            &["vs_main", "fs_main"],
+            &[&Camera::BIND_GROUP_LAYOUT_DESCRIPTOR],
+        )?;
+        let shader = reflection.create_shader_module(logical_device, "Debug Lines Shader");
+
+        logical_device
+            .device()
+            .push_error_scope(ErrorFilter::Validation);
+
+        let pipelines = Self {
+            sample_count,
+            depth_tested: Self::make_render_pipeline(logical_device, &shader, sample_count, true),
+            overlay: Self::make_render_pipeline(logical_device, &shader, sample_count, false),
+        };
+
+        if let Some(error) = pollster::block_on(logical_device.device().pop_error_scope()) {
+            return Err(EngineError::ShaderError(format!(
+                "Debug lines pipelines: {}",
+                error
+            )));
+        }
+
+        Ok(pipelines)
+    }
+
+    fn make_render_pipeline(
+        logical_device: &LogicalDevice,
+        shader: &ShaderModule,
+        sample_count: u32,
+        depth_test: bool,
+    ) -> RenderPipeline {
+        let pipeline_layout =
+            logical_device
+                .device()
+                .create_pipeline_layout(&PipelineLayoutDescriptor {
+                    label: Some("Debug Lines Pipeline Layout"),
+                    bind_group_layouts: &[&Camera::bind_group_layout(logical_device)],
+                    push_constant_ranges: &[],
+                });
+
+        logical_device
+            .device()
+            .create_render_pipeline(&RenderPipelineDescriptor {
+                label: Some("Debug Lines Pipeline"),
+                layout: Some(&pipeline_layout),
+                vertex: VertexState {
+                    module: shader,
+                    entry_point: "vs_main",
+                    buffers: &[DebugVertex::VERTEX_BUFFER_LAYOUT],
+                },
+                fragment: Some(FragmentState {
+                    module: shader,
+                    entry_point: "fs_main",
+                    targets: &[Some(ColorTargetState {
+                        format: PostProcessor::HDR_TEXTURE_FORMAT,
+                        blend: Some(BlendState::REPLACE),
+                        write_mask: ColorWrites::ALL,
+                    })],
+                }),
+                primitive: PrimitiveState {
+                    topology: PrimitiveTopology::LineList,
+                    ..Default::default()
+                },
+                depth_stencil: Some(DepthStencilState {
+                    format: DepthTexture::TEXTURE_FORMAT,
+                    // Lines never occlude the scene or each other
+                    depth_write_enabled: false,
+                    depth_compare: if depth_test {
+                        CompareFunction::LessEqual
+                    } else {
+                        CompareFunction::Always
+                    },
+                    stencil: StencilState::default(),
+                    bias: DepthBiasState::default(),
+                }),
+                multisample: MultisampleState {
+                    count: sample_count,
+                    mask: !0,
+                    alpha_to_coverage_enabled: false,
+                },
+                multiview: None,
+            })
+    }
+}
+
+/// Draws the lines queued through [`DebugDraw`] over the scene,
+/// depth tested ones first & overlay ones on top.
+///
+/// Only part of [`super::RenderGraph::standard`] in debug builds.
+#[derive(Default)]
+pub struct DebugLinesNode {
+    pipelines: Option<DebugLinePipelines>,
+    vertex_buffer: Option<Buffer>,
+}
+
+impl DebugLinesNode {
+    pub const LABEL: &'static str = "Debug Lines";
+
+    pub const SHADER_PATH: &'static str = "debug_lines.wgsl";
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Uploads the vertices, growing the buffer if they don't fit
+    fn update_vertex_buffer(&mut self, logical_device: &LogicalDevice, vertices: &[DebugVertex]) {
+        let data: &[u8] = bytemuck::cast_slice(vertices);
+
+        if self
+            .vertex_buffer
+            .as_ref()
+            .is_none_or(|x| x.size() < data.len() as BufferAddress)
+        {
+            self.vertex_buffer = Some(logical_device.device().create_buffer(&BufferDescriptor {
+                label: Some("Debug Lines Vertex Buffer"),
+                size: (data.len() as BufferAddress).next_power_of_two(),
+                usage: BufferUsages::VERTEX | BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            }));
+        }
+
+        if let Some(vertex_buffer) = &self.vertex_buffer {
+            logical_device.queue().write_buffer(vertex_buffer, 0, data);
+        }
+    }
+}
+
+impl TRenderNode for DebugLinesNode {
+    fn label(&self) -> &str {
+        Self::LABEL
+    }
+
+    fn declare(&self, engine: &WGPURenderingEngine) -> RenderNodeDeclaration {
+        let declaration = RenderNodeDeclaration::new()
+            .with_read(RenderResource::DEPTH)
+            .with_read(RenderResource::SCENE)
+            .with_write(RenderResource::SCENE);
+
+        if engine.sample_count() > 1 {
+            declaration
+                .with_read(RenderResource::SCENE_MULTISAMPLED)
+                .with_write(RenderResource::SCENE_MULTISAMPLED)
+        } else {
+            declaration
+        }
+    }
+
+    fn execute(&mut self, context: &mut RenderNodeContext<'_>) -> EngineResult<()> {
+        // Always taken, so lines don't pile up while nothing gets drawn
+        let lines = DebugDraw::take_lines();
+        if lines.is_empty() {
+            return Ok(());
+        }
+
+        let engine = &*context.engine;
+        let targets = context.targets;
+
+        let sample_count = engine.sample_count();
+        if self
+            .pipelines
+            .as_ref()
+            .is_none_or(|x| x.sample_count != sample_count)
+        {
+            let shader = ShaderPreprocessor::new().process_file(Self::SHADER_PATH)?;
+            self.pipelines = Some(DebugLinePipelines::new(
+                engine.logical_device(),
+                &shader,
+                sample_count,
+            )?);
+        }
+
+        // Depth tested lines first, overlay lines after
+        let (depth_tested, overlay): (Vec<&DebugLine>, Vec<&DebugLine>) =
+            lines.iter().partition(|x| x.depth_test);
+        let vertices: Vec<DebugVertex> = depth_tested
+            .iter()
+            .chain(&overlay)
+            .flat_map(|x| {
+                [
+                    DebugVertex::new(x.start, x.color),
+                    DebugVertex::new(x.end, x.color),
+                ]
+            })
+            .collect();
+        self.update_vertex_buffer(engine.logical_device(), &vertices);
+
+        let (Some(pipelines), Some(vertex_buffer)) = (&self.pipelines, &self.vertex_buffer) else {
+            return Ok(());
+        };
+
+        let scene_view = targets.required_view(RenderResource::SCENE)?;
+        let depth_view = targets.required_view(RenderResource::DEPTH)?;
+
+        let mut render_pass = context
+            .command_encoder
+            .begin_render_pass(&RenderPassDescriptor {
+                label: Some("Debug Lines Render Pass"),
+                color_attachments: &[Some(
+                    match targets.view(RenderResource::SCENE_MULTISAMPLED) {
+                        // Draw over the kept multisampled scene and resolve again
+                        Some(multisample_view) => RenderPassColorAttachment {
+                            view: multisample_view,
+                            resolve_target: Some(scene_view),
+                            ops: Operations {
+                                load: LoadOp::Load,
+                                store: true,
+                            },
+                        },
+                        None => RenderPassColorAttachment {
+                            view: scene_view,
+                            resolve_target: None,
+                            ops: Operations {
+                                load: LoadOp::Load,
+                                store: true,
+                            },
+                        },
+                    },
+                )],
+                depth_stencil_attachment: Some(RenderPassDepthStencilAttachment {
+                    view: depth_view,
+                    depth_ops: Some(Operations {
+                        load: LoadOp::Load,
+                        store: true,
+                    }),
+                    stencil_ops: None,
+                }),
+            });
+
+        render_pass.set_bind_group(0, context.frame.camera().bind_group(), &[]);
+        render_pass.set_vertex_buffer(0, vertex_buffer.slice(..));
+
+        let depth_tested_vertex_count = depth_tested.len() as u32 * 2;
+        let vertex_count = vertices.len() as u32;
+
+        if depth_tested_vertex_count > 0 {
+            render_pass.set_pipeline(&pipelines.depth_tested);
+            render_pass.draw(0..depth_tested_vertex_count, 0..1);
+            context.statistics.draw_calls += 1;
+        }
+
+        if vertex_count > depth_tested_vertex_count {
+            render_pass.set_pipeline(&pipelines.overlay);
+            render_pass.draw(depth_tested_vertex_count..vertex_count, 0..1);
+            context.statistics.draw_calls += 1;
+        }
+
+        Ok(())
+    }
+
+    /// Keeps the old pipelines if the new ones fail to compile
+    #[cfg(debug_assertions)]
+    fn reload_shaders(&mut self, logical_device: &LogicalDevice, changed_shaders: &[String]) {
+        let Some(pipelines) = &self.pipelines else {
+            return;
+        };
+
+        let result = ShaderPreprocessor::new()
+            .process_file(Self::SHADER_PATH)
+            .and_then(|shader| {
+                if !changed_shaders.iter().any(|x| shader.depends_on(x)) {
+                    return Ok(None);
+                }
+                DebugLinePipelines::new(logical_device, &shader, pipelines.sample_count).map(Some)
+            });
+
+        match result {
+            Ok(Some(pipelines)) => {
+                self.pipelines = Some(pipelines);
+                log::info!("Reloaded debug lines pipelines");
+            }
+            Ok(None) => {}
+            Err(e) => log::error!(
+                "Failed to reload debug lines pipelines, keeping the old ones: {:?}",
+                e
+            ),
+        }
+    }
+}
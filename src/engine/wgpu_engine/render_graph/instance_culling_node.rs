@@ -0,0 +1,45 @@
+use wgpu::ComputePassDescriptor;
+
+use crate::engine::{EngineResult, TComputingEngine, WGPURenderingEngine};
+
+use super::{RenderNodeContext, RenderNodeDeclaration, RenderResource, TRenderNode};
+
+/// Culls the instances of the heavily instanced meshes on the GPU,
+/// see [`crate::engine::InstanceCuller`]
+pub struct InstanceCullingNode;
+
+impl InstanceCullingNode {
+    pub const LABEL: &'static str = "Instance Culling";
+}
+
+impl TRenderNode for InstanceCullingNode {
+    fn label(&self) -> &str {
+        Self::LABEL
+    }
+
+    fn declare(&self, _engine: &WGPURenderingEngine) -> RenderNodeDeclaration {
+        RenderNodeDeclaration::new().with_write(RenderResource::CULLED_INSTANCES)
+    }
+
+    fn execute(&mut self, context: &mut RenderNodeContext<'_>) -> EngineResult<()> {
+        let frame = context.frame;
+        let engine = &*context.engine;
+
+        let instance_culler = engine.instance_culler();
+        instance_culler.update_frustum(engine.logical_device(), frame.frustum());
+        instance_culler.prepare(engine.logical_device(), frame.gpu_culled_meshes());
+
+        if !frame.gpu_culled_meshes().is_empty() {
+            let mut compute_pass =
+                context
+                    .command_encoder
+                    .begin_compute_pass(&ComputePassDescriptor {
+                        label: Some("Instance Culling Pass"),
+                    });
+
+            instance_culler.cull(&mut compute_pass, frame.gpu_culled_meshes());
+        }
+
+        Ok(())
+    }
+}
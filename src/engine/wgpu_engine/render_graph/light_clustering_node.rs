@@ -0,0 +1,45 @@
+use wgpu::ComputePassDescriptor;
+
+use crate::engine::{EngineResult, TComputingEngine, TRenderingEngine, WGPURenderingEngine};
+
+use super::{RenderNodeContext, RenderNodeDeclaration, RenderResource, TRenderNode};
+
+/// Bins point & spot lights into clusters, see [`crate::engine::LightClusterer`]
+pub struct LightClusteringNode;
+
+impl LightClusteringNode {
+    pub const LABEL: &'static str = "Light Clustering";
+}
+
+impl TRenderNode for LightClusteringNode {
+    fn label(&self) -> &str {
+        Self::LABEL
+    }
+
+    fn declare(&self, _engine: &WGPURenderingEngine) -> RenderNodeDeclaration {
+        RenderNodeDeclaration::new().with_write(RenderResource::LIGHT_CLUSTERS)
+    }
+
+    fn execute(&mut self, context: &mut RenderNodeContext<'_>) -> EngineResult<()> {
+        let frame = context.frame;
+        let engine = &*context.engine;
+
+        let light_clusterer = engine.light_clusterer();
+        light_clusterer.update(
+            engine.logical_device(),
+            frame.camera(),
+            engine.surface_configuration().width,
+            engine.surface_configuration().height,
+        );
+
+        let mut compute_pass = context
+            .command_encoder
+            .begin_compute_pass(&ComputePassDescriptor {
+                label: Some("Light Clustering Pass"),
+            });
+
+        light_clusterer.cluster(&mut compute_pass, frame.light_storage());
+
+        Ok(())
+    }
+}
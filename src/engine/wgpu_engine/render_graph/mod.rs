@@ -0,0 +1,392 @@
+use std::collections::HashMap;
+
+use wgpu::{CommandEncoder, TextureView};
+
+#[cfg(debug_assertions)]
+use crate::engine::LogicalDevice;
+use crate::engine::{
+    EngineError, EngineResult, TComputingEngine, TRenderingEngine, WGPURenderingEngine,
+};
+
+mod render_resource;
+pub use render_resource::*;
+
+mod render_target_descriptor;
+pub use render_target_descriptor::*;
+
+mod render_targets;
+pub use render_targets::*;
+
+mod render_node_declaration;
+pub use render_node_declaration::*;
+
+mod render_node_context;
+pub use render_node_context::*;
+
+mod t_render_node;
+pub use t_render_node::*;
+
+mod render_frame;
+pub use render_frame::*;
+
+#[cfg(debug_assertions)]
+mod render_statistics;
+#[cfg(debug_assertions)]
+pub use render_statistics::*;
+
+mod render_graph_change;
+pub use render_graph_change::*;
+
+mod instance_culling_node;
+pub use instance_culling_node::*;
+
+mod light_clustering_node;
+pub use light_clustering_node::*;
+
+mod shadow_node;
+pub use shadow_node::*;
+
+mod scene_node;
+pub use scene_node::*;
+
+mod post_processing_node;
+pub use post_processing_node::*;
+
+#[cfg(debug_assertions)]
+mod debug_lines_node;
+#[cfg(debug_assertions)]
+pub use debug_lines_node::*;
+
+/// Passes of a frame and the resources they exchange.
+///
+/// Each [`TRenderNode`] declares which resources it reads & writes
+/// (see [`RenderNodeDeclaration`]). Every frame the graph:
+/// 1. Orders the nodes by these dependencies
+/// 2. Allocates the transient render targets, sharing textures between
+///    targets whose lifetimes don't overlap & recreating surface sized
+///    ones after a resize
+/// 3. Executes the nodes into a single command encoder
+///
+/// The engine starts with [`Self::standard`].
+#[derive(Default)]
+pub struct RenderGraph {
+    nodes: Vec<BoxedRenderNode>,
+    targets: RenderTargets,
+}
+
+impl RenderGraph {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Culling, light clustering, shadows, the scene & post-processing.
+    /// Debug builds also draw the [`crate::engine::DebugDraw`] lines.
+    pub fn standard() -> Self {
+        let render_graph = Self::new()
+            .with_node(Box::new(InstanceCullingNode))
+            .with_node(Box::new(LightClusteringNode))
+            .with_node(Box::new(ShadowNode))
+            .with_node(Box::new(SceneNode))
+            .with_node(Box::new(PostProcessingNode));
+
+        #[cfg(debug_assertions)]
+        let render_graph = render_graph.with_node(Box::new(DebugLinesNode::new()));
+
+        render_graph
+    }
+
+    pub fn with_node(mut self, node: BoxedRenderNode) -> Self {
+        self.add_node(node);
+        self
+    }
+
+    /// Nodes get ordered by their resources, see [`RenderNodeDeclaration`].
+    /// Nodes conflicting in the same way run in the order they were added.
+    pub fn add_node(&mut self, node: BoxedRenderNode) {
+        self.nodes.push(node);
+    }
+
+    /// Removes all nodes with the given label
+    pub fn remove_node(&mut self, label: &str) {
+        self.nodes.retain(|x| x.label() != label);
+    }
+
+    pub fn apply_render_graph_change(&mut self, render_graph_change: RenderGraphChange) {
+        match render_graph_change {
+            RenderGraphChange::AddNode(node) => self.add_node(node),
+            RenderGraphChange::RemoveNode(label) => self.remove_node(&label),
+        }
+    }
+
+    pub fn labels(&self) -> Vec<&str> {
+        self.nodes.iter().map(|x| x.label()).collect()
+    }
+
+    pub fn targets(&self) -> &RenderTargets {
+        &self.targets
+    }
+
+    /// See [`TRenderNode::reload_shaders`]
+    #[cfg(debug_assertions)]
+    pub fn reload_shaders(&mut self, logical_device: &LogicalDevice, changed_shaders: &[String]) {
+        self.nodes
+            .iter_mut()
+            .for_each(|x| x.reload_shaders(logical_device, changed_shaders));
+    }
+
+    /// Drops the surface sized targets, they get recreated in the new size
+    pub fn resize(&mut self) {
+        self.targets.release_surface_sized();
+    }
+
+    /// Order of the nodes, given their declarations
+    pub fn order(
+        &self,
+        declarations: &[RenderNodeDeclaration],
+        imported: &[RenderResource],
+    ) -> EngineResult<Vec<usize>> {
+        let label = |i: usize| self.nodes[i].label();
+
+        let mut resources: Vec<RenderResource> = declarations
+            .iter()
+            .flat_map(|x| x.reads().iter().chain(x.writes()))
+            .copied()
+            .collect();
+        resources.sort_by_key(|x| x.0);
+        resources.dedup();
+
+        let mut dependencies: Vec<Vec<usize>> = vec![vec![]; declarations.len()];
+        for resource in resources {
+            let (mut writers, mut modifiers, mut readers) = (vec![], vec![], vec![]);
+            for (i, declaration) in declarations.iter().enumerate() {
+                match (
+                    declaration.reads().contains(&resource),
+                    declaration.writes().contains(&resource),
+                ) {
+                    (false, true) => writers.push(i),
+                    (true, true) => modifiers.push(i),
+                    (true, false) => readers.push(i),
+                    (false, false) => {}
+                }
+            }
+
+            if writers.is_empty() && !imported.contains(&resource) {
+                if let Some(i) = modifiers.first().or(readers.first()) {
+                    return Err(EngineError::RenderGraphError(format!(
+                        "'{}' reads '{}', which no node writes",
+                        label(*i),
+                        resource
+                    )));
+                }
+            }
+
+            // Writers, then modifiers & then readers, each after the previous
+            let mut previous: Vec<usize> = vec![];
+            for &writer in &writers {
+                dependencies[writer].extend(&previous);
+                previous = vec![writer];
+            }
+            for &modifier in &modifiers {
+                dependencies[modifier].extend(&previous);
+                previous = vec![modifier];
+            }
+            for &reader in &readers {
+                dependencies[reader].extend(&previous);
+            }
+        }
+
+        // Earliest added node first whenever several are ready
+        let mut order = Vec::with_capacity(declarations.len());
+        let mut done = vec![false; declarations.len()];
+        while order.len() < declarations.len() {
+            let next = (0..declarations.len())
+                .find(|&i| !done[i] && dependencies[i].iter().all(|&x| done[x]))
+                .ok_or_else(|| {
+                    let remaining: Vec<&str> = (0..declarations.len())
+                        .filter(|&i| !done[i])
+                        .map(label)
+                        .collect();
+                    EngineError::RenderGraphError(format!(
+                        "Cyclic dependencies between {:?}",
+                        remaining
+                    ))
+                })?;
+
+            done[next] = true;
+            order.push(next);
+        }
+
+        Ok(order)
+    }
+
+    /// Orders, allocates & executes the nodes for the frame,
+    /// stopping at the first node failing
+    pub fn execute(
+        &mut self,
+        engine: &mut WGPURenderingEngine,
+        frame: &RenderFrame<'_>,
+        command_encoder: &mut CommandEncoder,
+        imported: Vec<(RenderResource, TextureView)>,
+        #[cfg(debug_assertions)] statistics: &mut RenderStatistics,
+    ) -> EngineResult<()> {
+        let declarations: Vec<RenderNodeDeclaration> =
+            self.nodes.iter().map(|x| x.declare(engine)).collect();
+        let imported_resources: Vec<RenderResource> = imported.iter().map(|(x, _)| *x).collect();
+        let order = self.order(&declarations, &imported_resources)?;
+
+        // Lifetime of each target, in executed nodes
+        let mut targets: HashMap<RenderResource, (RenderTargetDescriptor, usize, usize)> =
+            HashMap::new();
+        for (position, &i) in order.iter().enumerate() {
+            for (resource, descriptor) in declarations[i].targets() {
+                match targets.get(resource) {
+                    Some((existing, ..)) if existing != descriptor => {
+                        return Err(EngineError::RenderGraphError(format!(
+                            "'{}' declares '{}' differently than an earlier node",
+                            self.nodes[i].label(),
+                            resource
+                        )))
+                    }
+                    _ => {
+                        targets
+                            .entry(*resource)
+                            .or_insert((*descriptor, position, position));
+                    }
+                }
+            }
+        }
+        for (position, &i) in order.iter().enumerate() {
+            for (resource, (_, _, last)) in targets.iter_mut() {
+                if declarations[i].uses(*resource) {
+                    *last = position;
+                }
+            }
+        }
+
+        let surface_configuration = engine.surface_configuration();
+        let (width, height) = (surface_configuration.width, surface_configuration.height);
+        self.targets.allocate(
+            engine.logical_device(),
+            width,
+            height,
+            targets
+                .into_iter()
+                .map(|(resource, (descriptor, first, last))| {
+                    (resource, descriptor, first..last + 1)
+                })
+                .collect(),
+        );
+        self.targets.set_imported(imported);
+
+        let mut context = RenderNodeContext {
+            engine,
+            frame,
+            command_encoder,
+            targets: &self.targets,
+            #[cfg(debug_assertions)]
+            statistics,
+        };
+        for i in order {
+            self.nodes[i].execute(&mut context)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct TestNode(&'static str);
+
+    impl TRenderNode for TestNode {
+        fn label(&self) -> &str {
+            self.0
+        }
+
+        fn declare(&self, _engine: &WGPURenderingEngine) -> RenderNodeDeclaration {
+            RenderNodeDeclaration::new()
+        }
+
+        fn execute(&mut self, _context: &mut RenderNodeContext<'_>) -> EngineResult<()> {
+            Ok(())
+        }
+    }
+
+    const RESOURCE: RenderResource = RenderResource("resource");
+
+    fn graph(labels: &[&'static str]) -> RenderGraph {
+        labels.iter().fold(RenderGraph::new(), |graph, x| {
+            graph.with_node(Box::new(TestNode(x)))
+        })
+    }
+
+    fn error_message(result: EngineResult<Vec<usize>>) -> String {
+        match result {
+            Err(EngineError::RenderGraphError(message)) => message,
+            x => panic!("Expected a render graph error, got {:?}", x),
+        }
+    }
+
+    #[test]
+    fn writers_run_before_modifiers_before_readers() {
+        // Added in reverse
+        let declarations = [
+            RenderNodeDeclaration::new().with_read(RESOURCE),
+            RenderNodeDeclaration::new()
+                .with_read(RESOURCE)
+                .with_write(RESOURCE),
+            RenderNodeDeclaration::new().with_write(RESOURCE),
+        ];
+
+        let order = graph(&["reader", "modifier", "writer"])
+            .order(&declarations, &[])
+            .unwrap();
+
+        assert_eq!(order, vec![2, 1, 0]);
+    }
+
+    #[test]
+    fn independent_nodes_keep_their_order() {
+        let declarations = [
+            RenderNodeDeclaration::new().with_write(RenderResource::SHADOW_MAPS),
+            RenderNodeDeclaration::new().with_write(RenderResource::LIGHT_CLUSTERS),
+        ];
+
+        let order = graph(&["shadows", "clusters"])
+            .order(&declarations, &[])
+            .unwrap();
+
+        assert_eq!(order, vec![0, 1]);
+    }
+
+    #[test]
+    fn cycles_are_an_error() {
+        let other = RenderResource("other");
+        let declarations = [
+            RenderNodeDeclaration::new()
+                .with_read(RESOURCE)
+                .with_write(other),
+            RenderNodeDeclaration::new()
+                .with_read(other)
+                .with_write(RESOURCE),
+        ];
+
+        let message = error_message(graph(&["a", "b"]).order(&declarations, &[]));
+
+        assert_eq!(message, "Cyclic dependencies between [\"a\", \"b\"]");
+    }
+
+    #[test]
+    fn reading_an_unwritten_resource_is_an_error() {
+        let declarations = [RenderNodeDeclaration::new().with_read(RESOURCE)];
+        let graph = graph(&["reader"]);
+
+        let message = error_message(graph.order(&declarations, &[]));
+        assert_eq!(message, "'reader' reads 'resource', which no node writes");
+
+        // Imported resources don't need a writer
+        assert_eq!(graph.order(&declarations, &[RESOURCE]).unwrap(), vec![0]);
+    }
+}
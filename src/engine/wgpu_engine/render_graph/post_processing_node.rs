@@ -0,0 +1,33 @@
+use crate::engine::{EngineResult, WGPURenderingEngine};
+
+use super::{RenderNodeContext, RenderNodeDeclaration, RenderResource, TRenderNode};
+
+/// Bloom, tonemapping, custom passes & output onto the surface,
+/// see [`crate::engine::PostProcessor`]
+pub struct PostProcessingNode;
+
+impl PostProcessingNode {
+    pub const LABEL: &'static str = "Post-Processing";
+}
+
+impl TRenderNode for PostProcessingNode {
+    fn label(&self) -> &str {
+        Self::LABEL
+    }
+
+    fn declare(&self, _engine: &WGPURenderingEngine) -> RenderNodeDeclaration {
+        RenderNodeDeclaration::new()
+            .with_read(RenderResource::SCENE)
+            .with_write(RenderResource::SURFACE)
+    }
+
+    fn execute(&mut self, context: &mut RenderNodeContext<'_>) -> EngineResult<()> {
+        let surface_view = context.targets.required_view(RenderResource::SURFACE)?;
+
+        context
+            .engine
+            .post_process(context.command_encoder, surface_view);
+
+        Ok(())
+    }
+}
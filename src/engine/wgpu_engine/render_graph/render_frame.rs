@@ -0,0 +1,174 @@
+use cgmath::{EuclideanSpace, InnerSpace};
+use wgpu::Color;
+
+#[cfg(debug_assertions)]
+use super::RenderStatistics;
+use crate::engine::{
+    Camera, EnvironmentMap, Frustum, LightStorage, LogicalDevice, Skybox, StandardAmbientLight,
+    TMesh,
+};
+
+/// What gets rendered this frame, shared by all nodes of the [`super::RenderGraph`].
+///
+/// Creating it culls the meshes against the camera frustum:
+/// Meshes entirely outside of the view get skipped.
+/// Heavily instanced meshes get their instances culled on the GPU
+/// and are drawn indirectly, the remaining ones upload only their
+/// visible instances.
+/// Transparent meshes are always culled on the CPU, so their
+/// instances can be sorted back-to-front.
+pub struct RenderFrame<'a> {
+    camera: &'a Camera,
+    clear_color: Color,
+    frustum: Frustum,
+    meshes: Vec<&'a dyn TMesh>,
+    gpu_culled_meshes: Vec<&'a dyn TMesh>,
+    opaque_meshes: Vec<(&'a dyn TMesh, u32)>,
+    transparent_meshes: Vec<(&'a dyn TMesh, u32)>,
+    ambient_light: &'a StandardAmbientLight,
+    light_storage: &'a LightStorage,
+    skybox: Option<&'a Skybox>,
+    environment_map: &'a EnvironmentMap,
+    #[cfg(debug_assertions)]
+    culling_statistics: RenderStatistics,
+}
+
+impl<'a> RenderFrame<'a> {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        logical_device: &LogicalDevice,
+        camera: &'a Camera,
+        clear_color: Color,
+        meshes: Vec<&'a dyn TMesh>,
+        ambient_light: &'a StandardAmbientLight,
+        light_storage: &'a LightStorage,
+        skybox: Option<&'a Skybox>,
+        environment_map: &'a EnvironmentMap,
+    ) -> Self {
+        #[cfg(debug_assertions)]
+        let mut culling_statistics = RenderStatistics::default();
+
+        let frustum = camera.frustum();
+        let (gpu_culled_meshes, cpu_culled_meshes): (Vec<_>, Vec<_>) = meshes
+            .iter()
+            .copied()
+            .filter(|x| {
+                let is_visible = frustum.intersects_bounding_box(x.world_bounding_box());
+
+                #[cfg(debug_assertions)]
+                if !is_visible {
+                    culling_statistics.culled_mesh_count += 1;
+                    culling_statistics.culled_instance_count += x.instance_count();
+                }
+
+                is_visible
+            })
+            .partition(|x| {
+                x.gpu_culling_data().is_some() && !x.material().alpha_mode().is_transparent()
+            });
+
+        let (mut transparent_meshes, opaque_meshes): (Vec<_>, Vec<_>) = cpu_culled_meshes
+            .into_iter()
+            .filter_map(|x| {
+                let visible_instance_count = if x.material().alpha_mode().is_transparent() {
+                    x.cull_and_sort_instances(logical_device, &frustum, camera.position())
+                } else {
+                    x.cull_instances(logical_device, &frustum)
+                };
+
+                #[cfg(debug_assertions)]
+                {
+                    culling_statistics.culled_instance_count +=
+                        x.instance_count() - visible_instance_count;
+                    if visible_instance_count == 0 {
+                        culling_statistics.culled_mesh_count += 1;
+                    }
+                }
+
+                if visible_instance_count == 0 {
+                    None
+                } else {
+                    Some((x, visible_instance_count))
+                }
+            })
+            .partition(|(x, _)| x.material().alpha_mode().is_transparent());
+
+        // Blending requires drawing the farthest transparent mesh first
+        let view_position = camera.position().to_vec();
+        transparent_meshes.sort_by(|(a, _), (b, _)| {
+            let distance_a = (a.world_bounding_box().center() - view_position).magnitude2();
+            let distance_b = (b.world_bounding_box().center() - view_position).magnitude2();
+            distance_b.total_cmp(&distance_a)
+        });
+
+        Self {
+            camera,
+            clear_color,
+            frustum,
+            meshes,
+            gpu_culled_meshes,
+            opaque_meshes,
+            transparent_meshes,
+            ambient_light,
+            light_storage,
+            skybox,
+            environment_map,
+            #[cfg(debug_assertions)]
+            culling_statistics,
+        }
+    }
+
+    pub fn camera(&self) -> &'a Camera {
+        self.camera
+    }
+
+    pub fn clear_color(&self) -> Color {
+        self.clear_color
+    }
+
+    pub fn frustum(&self) -> &Frustum {
+        &self.frustum
+    }
+
+    /// All meshes, including the culled ones (e.g. for shadows)
+    pub fn meshes(&self) -> &[&'a dyn TMesh] {
+        &self.meshes
+    }
+
+    /// Visible meshes whose instances get culled on the GPU
+    pub fn gpu_culled_meshes(&self) -> &[&'a dyn TMesh] {
+        &self.gpu_culled_meshes
+    }
+
+    /// Visible opaque & alpha-masked meshes with their visible instance count
+    pub fn opaque_meshes(&self) -> &[(&'a dyn TMesh, u32)] {
+        &self.opaque_meshes
+    }
+
+    /// Visible transparent meshes with their visible instance count, back-to-front
+    pub fn transparent_meshes(&self) -> &[(&'a dyn TMesh, u32)] {
+        &self.transparent_meshes
+    }
+
+    pub fn ambient_light(&self) -> &'a StandardAmbientLight {
+        self.ambient_light
+    }
+
+    pub fn light_storage(&self) -> &'a LightStorage {
+        self.light_storage
+    }
+
+    pub fn skybox(&self) -> Option<&'a Skybox> {
+        self.skybox
+    }
+
+    pub fn environment_map(&self) -> &'a EnvironmentMap {
+        self.environment_map
+    }
+
+    /// Culled meshes & instances
+    #[cfg(debug_assertions)]
+    pub fn culling_statistics(&self) -> RenderStatistics {
+        self.culling_statistics
+    }
+}
@@ -0,0 +1,9 @@
+use super::BoxedRenderNode;
+
+/// Changes to the [`super::RenderGraph`]
+pub enum RenderGraphChange {
+    AddNode(BoxedRenderNode),
+
+    /// Removes all nodes with the given label
+    RemoveNode(String),
+}
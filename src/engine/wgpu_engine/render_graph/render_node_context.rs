@@ -0,0 +1,17 @@
+use wgpu::CommandEncoder;
+
+#[cfg(debug_assertions)]
+use super::RenderStatistics;
+use super::{RenderFrame, RenderTargets};
+use crate::engine::WGPURenderingEngine;
+
+/// Everything a [`super::TRenderNode`] can use while executing
+pub struct RenderNodeContext<'a> {
+    pub engine: &'a mut WGPURenderingEngine,
+    pub frame: &'a RenderFrame<'a>,
+    pub command_encoder: &'a mut CommandEncoder,
+    /// Imported & transient textures of the graph
+    pub targets: &'a RenderTargets,
+    #[cfg(debug_assertions)]
+    pub statistics: &'a mut RenderStatistics,
+}
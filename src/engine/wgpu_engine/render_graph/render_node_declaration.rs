@@ -0,0 +1,58 @@
+use super::{RenderResource, RenderTargetDescriptor};
+
+/// Resources a [`super::TRenderNode`] reads & writes.
+///
+/// The [`super::RenderGraph`] orders the nodes by them, per resource:
+/// 1. Nodes only writing it, e.g. the scene clearing the depth buffer
+/// 2. Nodes reading & writing it, e.g. an overlay drawn onto the scene,
+///    in the order they were added
+/// 3. Nodes only reading it
+#[derive(Debug, Clone, Default)]
+pub struct RenderNodeDeclaration {
+    reads: Vec<RenderResource>,
+    writes: Vec<RenderResource>,
+    targets: Vec<(RenderResource, RenderTargetDescriptor)>,
+}
+
+impl RenderNodeDeclaration {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_read(mut self, resource: RenderResource) -> Self {
+        self.reads.push(resource);
+        self
+    }
+
+    pub fn with_write(mut self, resource: RenderResource) -> Self {
+        self.writes.push(resource);
+        self
+    }
+
+    /// Writes a transient render target allocated by the graph.
+    /// Nodes using the same target have to declare the same descriptor.
+    pub fn with_target(
+        mut self,
+        resource: RenderResource,
+        descriptor: RenderTargetDescriptor,
+    ) -> Self {
+        self.targets.push((resource, descriptor));
+        self.with_write(resource)
+    }
+
+    pub fn reads(&self) -> &[RenderResource] {
+        &self.reads
+    }
+
+    pub fn writes(&self) -> &[RenderResource] {
+        &self.writes
+    }
+
+    pub fn targets(&self) -> &[(RenderResource, RenderTargetDescriptor)] {
+        &self.targets
+    }
+
+    pub fn uses(&self, resource: RenderResource) -> bool {
+        self.reads.contains(&resource) || self.writes.contains(&resource)
+    }
+}
@@ -0,0 +1,39 @@
+use std::fmt::Display;
+
+/// Name of a texture or buffer nodes of the [`super::RenderGraph`] read or write.
+///
+/// Resources are either imported (e.g. the surface), transient render targets
+/// allocated by the graph (see [`super::RenderTargetDescriptor`]) or just
+/// stand for data produced by one node and consumed by another,
+/// e.g. the shadow maps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RenderResource(pub &'static str);
+
+impl RenderResource {
+    /// Surface texture of the current frame, imported
+    pub const SURFACE: Self = Self("surface");
+
+    /// HDR texture the scene gets rendered (or resolved) into, imported from the
+    /// [`crate::engine::PostProcessor`]
+    pub const SCENE: Self = Self("scene");
+
+    /// Multisampled HDR texture resolved into [`Self::SCENE`], only with MSAA
+    pub const SCENE_MULTISAMPLED: Self = Self("scene_multisampled");
+
+    /// Depth buffer of the scene
+    pub const DEPTH: Self = Self("depth");
+
+    /// Indirect draw arguments & visible instances of GPU-culled meshes
+    pub const CULLED_INSTANCES: Self = Self("culled_instances");
+
+    /// Point & spot lights binned into clusters
+    pub const LIGHT_CLUSTERS: Self = Self("light_clusters");
+
+    pub const SHADOW_MAPS: Self = Self("shadow_maps");
+}
+
+impl Display for RenderResource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
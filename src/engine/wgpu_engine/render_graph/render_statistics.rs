@@ -0,0 +1,9 @@
+/// Counters of a frame, collected in debug builds
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RenderStatistics {
+    pub draw_calls: u32,
+    pub triangle_count: u32,
+    pub culled_mesh_count: u32,
+    pub culled_instance_count: u32,
+    pub indirect_draw_calls: u32,
+}
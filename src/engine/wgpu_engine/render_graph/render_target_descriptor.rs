@@ -0,0 +1,70 @@
+use wgpu::{Extent3d, TextureFormat, TextureUsages};
+
+/// Size of a transient render target
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RenderTargetSize {
+    /// Follows the surface, recreated on resize
+    Surface,
+    Fixed(u32, u32),
+}
+
+/// A texture allocated by the [`super::RenderGraph`] for the nodes writing it.
+///
+/// Its content is undefined until the first node writes it, as targets with
+/// compatible descriptors & non-overlapping lifetimes share the same texture.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RenderTargetDescriptor {
+    pub label: &'static str,
+    pub format: TextureFormat,
+    pub size: RenderTargetSize,
+    pub sample_count: u32,
+    pub usage: TextureUsages,
+}
+
+impl RenderTargetDescriptor {
+    pub fn new(label: &'static str, format: TextureFormat) -> Self {
+        Self {
+            label,
+            format,
+            size: RenderTargetSize::Surface,
+            sample_count: 1,
+            usage: TextureUsages::RENDER_ATTACHMENT,
+        }
+    }
+
+    pub fn with_size(mut self, size: RenderTargetSize) -> Self {
+        self.size = size;
+        self
+    }
+
+    pub fn with_sample_count(mut self, sample_count: u32) -> Self {
+        self.sample_count = sample_count;
+        self
+    }
+
+    pub fn with_usage(mut self, usage: TextureUsages) -> Self {
+        self.usage = usage;
+        self
+    }
+
+    /// Whether both can share a texture, i.e. everything but the label matches
+    pub fn is_compatible(&self, other: &Self) -> bool {
+        self.format == other.format
+            && self.size == other.size
+            && self.sample_count == other.sample_count
+            && self.usage == other.usage
+    }
+
+    pub fn extent(&self, surface_width: u32, surface_height: u32) -> Extent3d {
+        let (width, height) = match self.size {
+            RenderTargetSize::Surface => (surface_width, surface_height),
+            RenderTargetSize::Fixed(width, height) => (width, height),
+        };
+
+        Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        }
+    }
+}
@@ -0,0 +1,294 @@
+use std::{collections::HashMap, ops::Range};
+
+use wgpu::{Extent3d, Texture, TextureDescriptor, TextureDimension, TextureView};
+
+use crate::engine::{EngineError, EngineResult, LogicalDevice};
+
+use super::{RenderResource, RenderTargetDescriptor, RenderTargetSize};
+
+/// A texture of the pool, possibly shared by several transient targets
+struct PooledTarget {
+    descriptor: RenderTargetDescriptor,
+    extent: Extent3d,
+    texture: Texture,
+    view: TextureView,
+}
+
+/// A texture of the pool while assigning targets to it, see [`RenderTargets::assign_slots`]
+struct PoolSlot {
+    descriptor: RenderTargetDescriptor,
+    extent: Extent3d,
+    /// Index of the last node using it this frame
+    used_until: usize,
+}
+
+/// Textures of the [`super::RenderGraph`]: imported ones & a pool of transient targets
+#[derive(Default)]
+pub struct RenderTargets {
+    pool: Vec<PooledTarget>,
+    /// Pool index of each transient target this frame
+    transient: HashMap<RenderResource, usize>,
+    imported: HashMap<RenderResource, TextureView>,
+}
+
+impl RenderTargets {
+    pub fn view(&self, resource: RenderResource) -> Option<&TextureView> {
+        self.transient
+            .get(&resource)
+            .map(|x| &self.pool[*x].view)
+            .or_else(|| self.imported.get(&resource))
+    }
+
+    /// Like [`Self::view`], but a missing resource is an error
+    pub fn required_view(&self, resource: RenderResource) -> EngineResult<&TextureView> {
+        self.view(resource).ok_or_else(|| {
+            EngineError::RenderGraphError(format!(
+                "'{}' is neither imported nor allocated",
+                resource
+            ))
+        })
+    }
+
+    /// Transient targets only, imported resources are views
+    pub fn texture(&self, resource: RenderResource) -> Option<&Texture> {
+        self.transient
+            .get(&resource)
+            .map(|x| &self.pool[*x].texture)
+    }
+
+    pub fn is_imported(&self, resource: RenderResource) -> bool {
+        self.imported.contains_key(&resource)
+    }
+
+    /// Amount of textures backing the transient targets
+    pub fn allocated_count(&self) -> usize {
+        self.pool.len()
+    }
+
+    pub(super) fn set_imported(&mut self, imported: Vec<(RenderResource, TextureView)>) {
+        self.imported = imported.into_iter().collect();
+    }
+
+    /// Drops the targets following the surface size
+    pub(super) fn release_surface_sized(&mut self) {
+        self.pool
+            .retain(|x| x.descriptor.size != RenderTargetSize::Surface);
+        self.transient.clear();
+    }
+
+    /// Assigns a texture to each target, given with the range of nodes using it.
+    /// Targets with compatible descriptors whose ranges don't overlap share a texture.
+    /// Textures of the previous frame get reused, unused ones are dropped.
+    pub(super) fn allocate(
+        &mut self,
+        logical_device: &LogicalDevice,
+        surface_width: u32,
+        surface_height: u32,
+        mut targets: Vec<(RenderResource, RenderTargetDescriptor, Range<usize>)>,
+    ) {
+        targets.sort_by_key(|(_, _, range)| range.start);
+
+        let (slots, indices) = Self::assign_slots(
+            targets
+                .iter()
+                .map(|(_, descriptor, range)| {
+                    (
+                        *descriptor,
+                        descriptor.extent(surface_width, surface_height),
+                        range.clone(),
+                    )
+                })
+                .collect(),
+        );
+
+        let mut previous_pool = std::mem::take(&mut self.pool);
+        self.pool = slots
+            .into_iter()
+            .map(|slot| {
+                match previous_pool.iter().position(|x| {
+                    x.descriptor.is_compatible(&slot.descriptor) && x.extent == slot.extent
+                }) {
+                    Some(index) => previous_pool.swap_remove(index),
+                    None => Self::make_target(logical_device, slot.descriptor, slot.extent),
+                }
+            })
+            .collect();
+        self.transient = targets
+            .into_iter()
+            .map(|(resource, _, _)| resource)
+            .zip(indices)
+            .collect();
+    }
+
+    /// Aliasing decision of [`Self::allocate`], without touching the device:
+    /// returns the slots of the pool & the slot index of each target.
+    /// Targets have to be sorted by the start of their range.
+    fn assign_slots(
+        targets: Vec<(RenderTargetDescriptor, Extent3d, Range<usize>)>,
+    ) -> (Vec<PoolSlot>, Vec<usize>) {
+        let mut slots: Vec<PoolSlot> = Vec::new();
+        let mut indices = Vec::with_capacity(targets.len());
+
+        for (descriptor, extent, range) in targets {
+            let index = match slots.iter().position(|x| {
+                x.descriptor.is_compatible(&descriptor)
+                    && x.extent == extent
+                    && x.used_until < range.start
+            }) {
+                Some(index) => index,
+                None => {
+                    slots.push(PoolSlot {
+                        descriptor,
+                        extent,
+                        used_until: 0,
+                    });
+                    slots.len() - 1
+                }
+            };
+
+            slots[index].used_until = range.end - 1;
+            indices.push(index);
+        }
+
+        (slots, indices)
+    }
+
+    fn make_target(
+        logical_device: &LogicalDevice,
+        descriptor: RenderTargetDescriptor,
+        extent: Extent3d,
+    ) -> PooledTarget {
+        let texture = logical_device.device().create_texture(&TextureDescriptor {
+            label: Some(descriptor.label),
+            size: extent,
+            mip_level_count: 1,
+            sample_count: descriptor.sample_count,
+            dimension: TextureDimension::D2,
+            format: descriptor.format,
+            usage: descriptor.usage,
+            view_formats: &[],
+        });
+        let view = texture.create_view(&Default::default());
+
+        PooledTarget {
+            descriptor,
+            extent,
+            texture,
+            view,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use wgpu::TextureFormat;
+
+    use super::*;
+    use crate::engine::{TComputingEngine, WGPUComputingEngine};
+
+    fn extent() -> Extent3d {
+        Extent3d {
+            width: 64,
+            height: 64,
+            depth_or_array_layers: 1,
+        }
+    }
+
+    #[test]
+    fn compatible_targets_share_a_slot_unless_they_overlap() {
+        let descriptor = |label| RenderTargetDescriptor::new(label, TextureFormat::Rgba8Unorm);
+
+        let (slots, indices) = RenderTargets::assign_slots(vec![
+            (descriptor("a"), extent(), 0..2),
+            (descriptor("b"), extent(), 2..3),
+        ]);
+        assert_eq!(slots.len(), 1);
+        assert_eq!(indices, vec![0, 0]);
+
+        let (slots, indices) = RenderTargets::assign_slots(vec![
+            (descriptor("a"), extent(), 0..2),
+            (descriptor("b"), extent(), 1..3),
+        ]);
+        assert_eq!(slots.len(), 2);
+        assert_eq!(indices, vec![0, 1]);
+
+        // A freed slot gets reused by a later target
+        let (slots, indices) = RenderTargets::assign_slots(vec![
+            (descriptor("a"), extent(), 0..2),
+            (descriptor("b"), extent(), 1..3),
+            (descriptor("c"), extent(), 2..4),
+        ]);
+        assert_eq!(slots.len(), 2);
+        assert_eq!(indices, vec![0, 1, 0]);
+        assert_eq!(slots[0].used_until, 3);
+    }
+
+    #[test]
+    fn incompatible_targets_never_share_a_slot() {
+        let descriptor = RenderTargetDescriptor::new("a", TextureFormat::Rgba8Unorm);
+        let smaller = Extent3d {
+            width: 32,
+            ..extent()
+        };
+
+        let (slots, indices) = RenderTargets::assign_slots(vec![
+            (descriptor, extent(), 0..1),
+            (
+                RenderTargetDescriptor::new("b", TextureFormat::Rgba16Float),
+                extent(),
+                1..2,
+            ),
+            (descriptor, smaller, 2..3),
+            (descriptor.with_sample_count(4), extent(), 3..4),
+        ]);
+        assert_eq!(slots.len(), 4);
+        assert_eq!(indices, vec![0, 1, 2, 3]);
+    }
+
+    #[test]
+    #[ignore = "requires a GPU adapter"]
+    fn compatible_targets_share_a_texture_unless_they_overlap() {
+        let computing_engine = WGPUComputingEngine::new().unwrap();
+        let logical_device = computing_engine.logical_device();
+
+        let a = RenderResource("a");
+        let b = RenderResource("b");
+        let descriptor = |label| RenderTargetDescriptor::new(label, TextureFormat::Rgba8Unorm);
+
+        let mut render_targets = RenderTargets::default();
+
+        render_targets.allocate(
+            logical_device,
+            64,
+            64,
+            vec![(a, descriptor("a"), 0..2), (b, descriptor("b"), 2..3)],
+        );
+        assert_eq!(render_targets.allocated_count(), 1);
+        assert!(render_targets.view(a).is_some());
+        assert!(render_targets.view(b).is_some());
+
+        render_targets.allocate(
+            logical_device,
+            64,
+            64,
+            vec![(a, descriptor("a"), 0..2), (b, descriptor("b"), 1..3)],
+        );
+        assert_eq!(render_targets.allocated_count(), 2);
+
+        // Incompatible descriptors never share
+        render_targets.allocate(
+            logical_device,
+            64,
+            64,
+            vec![
+                (a, descriptor("a"), 0..2),
+                (
+                    b,
+                    RenderTargetDescriptor::new("b", TextureFormat::Rgba16Float),
+                    2..3,
+                ),
+            ],
+        );
+        assert_eq!(render_targets.allocated_count(), 2);
+    }
+}
@@ -0,0 +1,228 @@
+use wgpu::{
+    IndexFormat, LoadOp, Operations, RenderPassColorAttachment, RenderPassDepthStencilAttachment,
+    RenderPassDescriptor,
+};
+
+use crate::engine::{
+    DepthTexture, EngineError, EngineResult, PostProcessor, TAmbientLight, WGPURenderingEngine,
+};
+
+use super::{
+    RenderNodeContext, RenderNodeDeclaration, RenderResource, RenderTargetDescriptor, TRenderNode,
+};
+
+/// Renders the meshes & the skybox into the HDR scene texture:
+/// 1. Opaque & alpha-masked meshes, writing depth
+/// 2. The skybox, after all opaque geometry so covered pixels get skipped
+/// 3. Transparent meshes, back-to-front, blended over everything else
+///    without writing depth
+///
+/// With MSAA it renders into a multisampled target resolved into the scene texture.
+pub struct SceneNode;
+
+impl SceneNode {
+    pub const LABEL: &'static str = "Scene";
+}
+
+impl TRenderNode for SceneNode {
+    fn label(&self) -> &str {
+        Self::LABEL
+    }
+
+    fn declare(&self, engine: &WGPURenderingEngine) -> RenderNodeDeclaration {
+        let sample_count = engine.sample_count();
+
+        let declaration = RenderNodeDeclaration::new()
+            .with_read(RenderResource::CULLED_INSTANCES)
+            .with_read(RenderResource::LIGHT_CLUSTERS)
+            .with_read(RenderResource::SHADOW_MAPS)
+            .with_write(RenderResource::SCENE)
+            .with_target(
+                RenderResource::DEPTH,
+                RenderTargetDescriptor::new("Depth Texture", DepthTexture::TEXTURE_FORMAT)
+                    .with_sample_count(sample_count),
+            );
+
+        if sample_count > 1 {
+            declaration.with_target(
+                RenderResource::SCENE_MULTISAMPLED,
+                RenderTargetDescriptor::new(
+                    "Multisample Texture",
+                    PostProcessor::HDR_TEXTURE_FORMAT,
+                )
+                .with_sample_count(sample_count),
+            )
+        } else {
+            declaration
+        }
+    }
+
+    fn execute(&mut self, context: &mut RenderNodeContext<'_>) -> EngineResult<()> {
+        let frame = context.frame;
+        let engine = &*context.engine;
+        let targets = context.targets;
+        #[cfg(debug_assertions)]
+        let statistics = &mut *context.statistics;
+
+        let scene_view = targets.required_view(RenderResource::SCENE)?;
+        let depth_view = targets.required_view(RenderResource::DEPTH)?;
+
+        let mut render_pass = context
+            .command_encoder
+            .begin_render_pass(&RenderPassDescriptor {
+                label: Some("Render Pass"),
+                color_attachments: &[Some(
+                    match targets.view(RenderResource::SCENE_MULTISAMPLED) {
+                        // Render into the multisampled texture and resolve into the HDR scene texture
+                        Some(multisample_view) => RenderPassColorAttachment {
+                            view: multisample_view,
+                            resolve_target: Some(scene_view),
+                            ops: Operations {
+                                load: LoadOp::Clear(frame.clear_color()),
+                                // Kept for nodes drawing on top, e.g. the debug lines
+                                store: true,
+                            },
+                        },
+                        None => RenderPassColorAttachment {
+                            view: scene_view,
+                            resolve_target: None,
+                            ops: Operations {
+                                load: LoadOp::Clear(frame.clear_color()),
+                                store: true,
+                            },
+                        },
+                    },
+                )],
+                depth_stencil_attachment: Some(RenderPassDepthStencilAttachment {
+                    view: depth_view,
+                    depth_ops: Some(Operations {
+                        load: LoadOp::Clear(1.0),
+                        store: true,
+                    }),
+                    stencil_ops: None,
+                }),
+            });
+
+        // Camera
+        render_pass.set_bind_group(1, frame.camera().bind_group(), &[]);
+
+        // Ambient Light
+        render_pass.set_bind_group(2, frame.ambient_light().bind_group(), &[]);
+
+        // Point, Directional & Spot Lights
+        render_pass.set_bind_group(3, frame.light_storage().bind_group(), &[]);
+
+        // Shadow Maps
+        render_pass.set_bind_group(4, frame.light_storage().shadow_maps().bind_group(), &[]);
+
+        // Light Clusters
+        render_pass.set_bind_group(5, engine.light_clusterer().bind_group(), &[]);
+
+        // Environment Map
+        render_pass.set_bind_group(6, frame.environment_map().bind_group(), &[]);
+
+        // Opaque & alpha-masked meshes first, writing depth
+        frame
+            .opaque_meshes()
+            .iter()
+            .for_each(|(x, visible_instance_count)| {
+                // Materials whose pipeline failed to build are skipped
+                let Some(render_pipeline) =
+                    engine.material_pipeline(&x.material().pipeline_descriptor())
+                else {
+                    return;
+                };
+
+                #[cfg(debug_assertions)]
+                {
+                    statistics.draw_calls += 1;
+                    statistics.triangle_count += visible_instance_count / 3;
+                }
+
+                // Vertex & (visible) Instance Buffer
+                render_pass.set_vertex_buffer(0, x.vertex_buffer().slice(..));
+                render_pass.set_vertex_buffer(1, x.visible_instance_buffer().slice(..));
+
+                // Index Buffer
+                render_pass.set_index_buffer(x.index_buffer().slice(..), IndexFormat::Uint32);
+
+                // Texture / Material
+                render_pass.set_pipeline(render_pipeline);
+                render_pass.set_bind_group(0, x.material().bind_group(), &[]);
+
+                render_pass.draw_indexed(0..x.index_count(), 0, 0..*visible_instance_count);
+            });
+
+        for x in frame.gpu_culled_meshes() {
+            let Some(render_pipeline) =
+                engine.material_pipeline(&x.material().pipeline_descriptor())
+            else {
+                continue;
+            };
+            let gpu_culling_data = x.gpu_culling_data().ok_or_else(|| {
+                EngineError::RenderGraphError(
+                    "GPU culled mesh without GPU culling data".to_string(),
+                )
+            })?;
+
+            #[cfg(debug_assertions)]
+            {
+                statistics.draw_calls += 1;
+                statistics.indirect_draw_calls += 1;
+            }
+
+            // Vertex & (visible) Instance Buffer
+            render_pass.set_vertex_buffer(0, x.vertex_buffer().slice(..));
+            render_pass.set_vertex_buffer(1, x.visible_instance_buffer().slice(..));
+
+            // Index Buffer
+            render_pass.set_index_buffer(x.index_buffer().slice(..), IndexFormat::Uint32);
+
+            // Texture / Material
+            render_pass.set_pipeline(render_pipeline);
+            render_pass.set_bind_group(0, x.material().bind_group(), &[]);
+
+            // Instance count was written by the culling pass
+            render_pass.draw_indexed_indirect(gpu_culling_data.indirect_buffer(), 0);
+        }
+
+        // Skybox, after all opaque geometry so covered pixels get skipped
+        if let Some(skybox) = frame.skybox() {
+            engine.skybox_renderer().render(&mut render_pass, skybox);
+        }
+
+        // Transparent meshes last, back-to-front, blended over
+        // everything else without writing depth
+        frame
+            .transparent_meshes()
+            .iter()
+            .for_each(|(x, visible_instance_count)| {
+                let Some(render_pipeline) =
+                    engine.material_pipeline(&x.material().pipeline_descriptor())
+                else {
+                    return;
+                };
+
+                #[cfg(debug_assertions)]
+                {
+                    statistics.draw_calls += 1;
+                    statistics.triangle_count += visible_instance_count / 3;
+                }
+
+                // Vertex & (sorted visible) Instance Buffer
+                render_pass.set_vertex_buffer(0, x.vertex_buffer().slice(..));
+                render_pass.set_vertex_buffer(1, x.visible_instance_buffer().slice(..));
+
+                // Index Buffer
+                render_pass.set_index_buffer(x.index_buffer().slice(..), IndexFormat::Uint32);
+
+                // Texture / Material
+                render_pass.set_pipeline(render_pipeline);
+                render_pass.set_bind_group(0, x.material().bind_group(), &[]);
+
+                render_pass.draw_indexed(0..x.index_count(), 0, 0..*visible_instance_count);
+            });
+
+        Ok(())
+    }
+}
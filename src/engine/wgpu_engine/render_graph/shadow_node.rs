@@ -0,0 +1,33 @@
+use crate::engine::{EngineResult, WGPURenderingEngine};
+
+use super::{RenderNodeContext, RenderNodeDeclaration, RenderResource, TRenderNode};
+
+/// Renders the shadow maps, see [`crate::engine::ShadowRenderer`].
+///
+/// Rendered from all meshes, as meshes outside of the view can
+/// still cast shadows into it.
+pub struct ShadowNode;
+
+impl ShadowNode {
+    pub const LABEL: &'static str = "Shadows";
+}
+
+impl TRenderNode for ShadowNode {
+    fn label(&self) -> &str {
+        Self::LABEL
+    }
+
+    fn declare(&self, _engine: &WGPURenderingEngine) -> RenderNodeDeclaration {
+        RenderNodeDeclaration::new().with_write(RenderResource::SHADOW_MAPS)
+    }
+
+    fn execute(&mut self, context: &mut RenderNodeContext<'_>) -> EngineResult<()> {
+        context.engine.shadow_renderer().render(
+            context.command_encoder,
+            context.frame.light_storage().shadow_maps(),
+            context.frame.meshes(),
+        );
+
+        Ok(())
+    }
+}
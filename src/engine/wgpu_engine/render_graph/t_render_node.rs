@@ -0,0 +1,26 @@
+#[cfg(debug_assertions)]
+use crate::engine::LogicalDevice;
+use crate::engine::{EngineResult, WGPURenderingEngine};
+
+use super::{RenderNodeContext, RenderNodeDeclaration};
+
+/// A pass of the [`super::RenderGraph`]
+pub trait TRenderNode {
+    /// Identifies the node, e.g. for [`super::RenderGraph::remove_node`]
+    fn label(&self) -> &str;
+
+    /// Resources the node reads & writes this frame
+    fn declare(&self, engine: &WGPURenderingEngine) -> RenderNodeDeclaration;
+
+    /// Records the commands of the node,
+    /// an error aborts the frame (see [`super::RenderGraph::execute`])
+    fn execute(&mut self, context: &mut RenderNodeContext<'_>) -> EngineResult<()>;
+
+    /// Hot-reload: recreates the pipelines of the node whose shaders depend on
+    /// one of the changed shaders, see [`crate::engine::ShaderWatcher`].
+    /// Nodes without own pipelines don't need to implement it.
+    #[cfg(debug_assertions)]
+    fn reload_shaders(&mut self, _logical_device: &LogicalDevice, _changed_shaders: &[String]) {}
+}
+
+pub type BoxedRenderNode = Box<dyn TRenderNode>;
@@ -0,0 +1,170 @@
+use wgpu::{
+    CommandEncoder, CompareFunction, DepthBiasState, DepthStencilState, ErrorFilter, FrontFace,
+    IndexFormat, LoadOp, MultisampleState, Operations, PipelineLayoutDescriptor, PolygonMode,
+    PrimitiveState, PrimitiveTopology, RenderPassDepthStencilAttachment, RenderPassDescriptor,
+    RenderPipeline, RenderPipelineDescriptor, StencilState, VertexState,
+};
+
+use crate::engine::{
+    DepthTexture, EngineError, EngineResult, LogicalDevice, PreprocessedShader, ShaderPreprocessor,
+    ShadowMaps, StandardInstance, TInstance, TMesh, TVertex, VertexPoint,
+};
+
+/// Renders the depth of all meshes from the lights point of view into
+/// the [`ShadowMaps`].
+///
+/// Uses the same vertex and instance layouts as the main pipeline,
+/// but without a fragment stage.
+pub struct ShadowRenderer {
+    render_pipeline: RenderPipeline,
+}
+
+impl ShadowRenderer {
+    pub const SHADER_PATH: &'static str = "shadow.wgsl";
+
+    pub fn new(logical_device: &LogicalDevice) -> Self {
+        let render_pipeline = ShaderPreprocessor::new()
+            .process_file(Self::SHADER_PATH)
+            .and_then(|x| Self::make_render_pipeline(logical_device, &x))
+            .expect("Built-in shadow shader is invalid");
+
+        Self { render_pipeline }
+    }
+
+    /// Recreates the render pipeline if its shader depends on one of the changed shaders.
+    /// Keeps the old pipeline if the new one fails to compile.
+    #[cfg(debug_assertions)]
+    pub fn reload_shader(
+        &mut self,
+        logical_device: &LogicalDevice,
+        changed_shaders: &[String],
+    ) -> EngineResult<()> {
+        let shader = ShaderPreprocessor::new().process_file(Self::SHADER_PATH)?;
+        if changed_shaders.iter().any(|x| shader.depends_on(x)) {
+            self.render_pipeline = Self::make_render_pipeline(logical_device, &shader)?;
+            log::info!("Reloaded shadow pipeline");
+        }
+
+        Ok(())
+    }
+
+    fn make_render_pipeline(
+        logical_device: &LogicalDevice,
+        shader: &PreprocessedShader,
+    ) -> EngineResult<RenderPipeline> {
+        let vertex_buffers = [
+            VertexPoint::descriptor::<VertexPoint>(),
+            StandardInstance::descriptor(),
+        ];
+
+        let reflection = shader.reflect()?;
+        reflection.validate_vertex_buffers("vs_main", &vertex_buffers)?;
+        reflection.validate_bind_group_layouts(
+            &["vs_main"],
+            &[&ShadowMaps::PASS_BIND_GROUP_LAYOUT_DESCRIPTOR],
+        )?;
+        let shader = reflection.create_shader_module(logical_device, "Shadow Shader");
+
+        logical_device
+            .device()
+            .push_error_scope(ErrorFilter::Validation);
+
+        let pipeline_layout =
+            logical_device
+                .device()
+                .create_pipeline_layout(&PipelineLayoutDescriptor {
+                    label: Some("Shadow Pipeline Layout"),
+                    bind_group_layouts: &[&ShadowMaps::pass_bind_group_layout(logical_device)],
+                    push_constant_ranges: &[],
+                });
+
+        let render_pipeline =
+            logical_device
+                .device()
+                .create_render_pipeline(&RenderPipelineDescriptor {
+                    label: Some("Shadow Pipeline"),
+                    layout: Some(&pipeline_layout),
+                    vertex: VertexState {
+                        module: &shader,
+                        entry_point: "vs_main",
+                        buffers: &vertex_buffers,
+                    },
+                    // Depth only
+                    fragment: None,
+                    primitive: PrimitiveState {
+                        topology: PrimitiveTopology::TriangleList,
+                        strip_index_format: None,
+                        front_face: FrontFace::Ccw,
+                        // Single sided geometry (e.g. planes) must cast shadows too
+                        cull_mode: None,
+                        polygon_mode: PolygonMode::Fill,
+                        unclipped_depth: false,
+                        conservative: false,
+                    },
+                    depth_stencil: Some(DepthStencilState {
+                        format: DepthTexture::TEXTURE_FORMAT,
+                        depth_write_enabled: true,
+                        depth_compare: CompareFunction::LessEqual,
+                        stencil: StencilState::default(),
+                        // Biasing is done when sampling, see `ShadowSettings`
+                        bias: DepthBiasState::default(),
+                    }),
+                    multisample: MultisampleState::default(),
+                    multiview: None,
+                });
+
+        if let Some(error) = pollster::block_on(logical_device.device().pop_error_scope()) {
+            return Err(EngineError::ShaderError(format!(
+                "Shadow pipeline: {}",
+                error
+            )));
+        }
+
+        Ok(render_pipeline)
+    }
+
+    /// Records one depth pass per active shadow map layer.
+    ///
+    /// All instances are rendered, not only the ones visible to the
+    /// camera, as objects outside of the view can still cast shadows
+    /// into it.
+    pub fn render(
+        &self,
+        command_encoder: &mut CommandEncoder,
+        shadow_maps: &ShadowMaps,
+        meshes: &[&dyn TMesh],
+    ) {
+        for layer in 0..shadow_maps.active_layers() {
+            let mut render_pass = command_encoder.begin_render_pass(&RenderPassDescriptor {
+                label: Some("Shadow Pass"),
+                color_attachments: &[],
+                depth_stencil_attachment: Some(RenderPassDepthStencilAttachment {
+                    view: &shadow_maps.layer_views()[layer as usize],
+                    depth_ops: Some(Operations {
+                        load: LoadOp::Clear(1.0),
+                        store: true,
+                    }),
+                    stencil_ops: None,
+                }),
+            });
+
+            render_pass.set_pipeline(&self.render_pipeline);
+            render_pass.set_bind_group(
+                0,
+                shadow_maps.pass_bind_group(),
+                &[ShadowMaps::pass_offset(layer)],
+            );
+
+            meshes
+                .iter()
+                .filter(|x| x.instance_count() > 0)
+                .for_each(|x| {
+                    render_pass.set_vertex_buffer(0, x.vertex_buffer().slice(..));
+                    render_pass.set_vertex_buffer(1, x.instance_buffer().slice(..));
+                    render_pass.set_index_buffer(x.index_buffer().slice(..), IndexFormat::Uint32);
+
+                    render_pass.draw_indexed(0..x.index_count(), 0, 0..x.instance_count());
+                });
+        }
+    }
+}
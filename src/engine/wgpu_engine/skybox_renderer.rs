@@ -0,0 +1,134 @@
+use wgpu::{
+    BlendState, ColorTargetState, ColorWrites, CompareFunction, DepthBiasState, DepthStencilState,
+    ErrorFilter, FragmentState, MultisampleState, PipelineLayoutDescriptor, PrimitiveState,
+    RenderPass, RenderPipeline, RenderPipelineDescriptor, StencilState, VertexState,
+};
+
+use crate::engine::{
+    DepthTexture, EngineError, EngineResult, LogicalDevice, PostProcessor, PreprocessedShader,
+    ShaderPreprocessor, Skybox,
+};
+
+/// Renders a [`Skybox`] into the main render pass.
+///
+/// Must be drawn after all opaque geometry: the skybox lies on the far
+/// plane and only passes the depth test where nothing else was drawn.
+pub struct SkyboxRenderer {
+    render_pipeline: RenderPipeline,
+    /// Only needed to recompile the pipeline
+    #[cfg(debug_assertions)]
+    sample_count: u32,
+}
+
+impl SkyboxRenderer {
+    pub const SHADER_PATH: &'static str = "skybox.wgsl";
+
+    pub fn new(logical_device: &LogicalDevice, sample_count: u32) -> Self {
+        let render_pipeline = ShaderPreprocessor::new()
+            .process_file(Self::SHADER_PATH)
+            .and_then(|x| Self::make_render_pipeline(logical_device, &x, sample_count))
+            .expect("Built-in skybox shader is invalid");
+
+        Self {
+            render_pipeline,
+            #[cfg(debug_assertions)]
+            sample_count,
+        }
+    }
+
+    /// Recreates the render pipeline if its shader depends on one of the changed shaders.
+    /// Keeps the old pipeline if the new one fails to compile.
+    #[cfg(debug_assertions)]
+    pub fn reload_shader(
+        &mut self,
+        logical_device: &LogicalDevice,
+        changed_shaders: &[String],
+    ) -> EngineResult<()> {
+        let shader = ShaderPreprocessor::new().process_file(Self::SHADER_PATH)?;
+        if changed_shaders.iter().any(|x| shader.depends_on(x)) {
+            self.render_pipeline =
+                Self::make_render_pipeline(logical_device, &shader, self.sample_count)?;
+            log::info!("Reloaded skybox pipeline");
+        }
+
+        Ok(())
+    }
+
+    fn make_render_pipeline(
+        logical_device: &LogicalDevice,
+        shader: &PreprocessedShader,
+        sample_count: u32,
+    ) -> EngineResult<RenderPipeline> {
+        let reflection = shader.reflect()?;
+        reflection.validate_bind_group_layouts(
+            &["vs_main", "fs_main"],
+            &[&Skybox::BIND_GROUP_LAYOUT_DESCRIPTOR],
+        )?;
+        let shader = reflection.create_shader_module(logical_device, "Skybox Shader");
+
+        logical_device
+            .device()
+            .push_error_scope(ErrorFilter::Validation);
+
+        let pipeline_layout =
+            logical_device
+                .device()
+                .create_pipeline_layout(&PipelineLayoutDescriptor {
+                    label: Some("Skybox Pipeline Layout"),
+                    bind_group_layouts: &[&Skybox::bind_group_layout(logical_device)],
+                    push_constant_ranges: &[],
+                });
+
+        let render_pipeline =
+            logical_device
+                .device()
+                .create_render_pipeline(&RenderPipelineDescriptor {
+                    label: Some("Skybox Pipeline"),
+                    layout: Some(&pipeline_layout),
+                    vertex: VertexState {
+                        module: &shader,
+                        entry_point: "vs_main",
+                        buffers: &[],
+                    },
+                    fragment: Some(FragmentState {
+                        module: &shader,
+                        entry_point: "fs_main",
+                        targets: &[Some(ColorTargetState {
+                            format: PostProcessor::HDR_TEXTURE_FORMAT,
+                            blend: Some(BlendState::REPLACE),
+                            write_mask: ColorWrites::ALL,
+                        })],
+                    }),
+                    primitive: PrimitiveState::default(),
+                    depth_stencil: Some(DepthStencilState {
+                        format: DepthTexture::TEXTURE_FORMAT,
+                        // Passes where the depth is still cleared (1.0)
+                        depth_write_enabled: false,
+                        depth_compare: CompareFunction::LessEqual,
+                        stencil: StencilState::default(),
+                        bias: DepthBiasState::default(),
+                    }),
+                    multisample: MultisampleState {
+                        count: sample_count,
+                        mask: !0,
+                        alpha_to_coverage_enabled: false,
+                    },
+                    multiview: None,
+                });
+
+        if let Some(error) = pollster::block_on(logical_device.device().pop_error_scope()) {
+            return Err(EngineError::ShaderError(format!(
+                "Skybox pipeline: {}",
+                error
+            )));
+        }
+
+        Ok(render_pipeline)
+    }
+
+    pub fn render<'a>(&'a self, render_pass: &mut RenderPass<'a>, skybox: &'a Skybox) {
+        render_pass.set_pipeline(&self.render_pipeline);
+        render_pass.set_bind_group(0, skybox.bind_group(), &[]);
+        render_pass.draw(0..3, 0..1);
+    }
+}
@@ -129,7 +129,12 @@ impl WGPUComputingEngine {
         let (device, queue) = pollster::block_on(adapter.request_device(
             &DeviceDescriptor {
                 label: Some("Main Device"),
-                features: Features::empty(),
+                // Allows using all sample counts the adapter supports,
+                // instead of only the ones guaranteed by WebGPU (1 & 4),
+                // and wireframe materials if available
+                features: adapter.features()
+                    & (Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES
+                        | Features::POLYGON_MODE_LINE),
                 limits,
             },
             None,
@@ -0,0 +1,78 @@
+use cgmath::{InnerSpace, Matrix4, Vector3, Vector4};
+
+use crate::engine::{BoundingBox, BoundingSphere};
+
+/// View frustum, made of six planes pointing inwards.
+///
+/// Each plane is stored as `(normal.x, normal.y, normal.z, distance)` with
+/// a normalized normal. A point is inside a plane if
+/// `dot(normal, point) + distance >= 0`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Frustum {
+    planes: [Vector4<f32>; 6],
+}
+
+impl Frustum {
+    /// Extracts the frustum planes from a view-projection matrix
+    /// (Gribb & Hartmann).
+    ///
+    /// > Note: WGPU uses a depth range of `0.0..=1.0`, thus the near plane
+    /// > is the third row on its own instead of `row3 + row2`.
+    pub fn from_view_projection_matrix(matrix: &Matrix4<f32>) -> Self {
+        let row = |i: usize| Vector4::new(matrix.x[i], matrix.y[i], matrix.z[i], matrix.w[i]);
+        let (row_0, row_1, row_2, row_3) = (row(0), row(1), row(2), row(3));
+
+        let planes = [
+            // Left
+            row_3 + row_0,
+            // Right
+            row_3 - row_0,
+            // Bottom
+            row_3 + row_1,
+            // Top
+            row_3 - row_1,
+            // Near
+            row_2,
+            // Far
+            row_3 - row_2,
+        ]
+        .map(|plane| plane / plane.truncate().magnitude());
+
+        Self { planes }
+    }
+
+    pub fn contains_point(&self, point: Vector3<f32>) -> bool {
+        self.planes
+            .iter()
+            .all(|plane| plane.truncate().dot(point) + plane.w >= 0.0)
+    }
+
+    pub fn intersects_sphere(&self, sphere: &BoundingSphere) -> bool {
+        self.planes
+            .iter()
+            .all(|plane| plane.truncate().dot(sphere.center()) + plane.w >= -sphere.radius())
+    }
+
+    pub fn intersects_bounding_box(&self, bounding_box: &BoundingBox) -> bool {
+        if bounding_box.is_empty() {
+            return false;
+        }
+
+        let center = bounding_box.center();
+        let half_extents = bounding_box.half_extents();
+
+        self.planes.iter().all(|plane| {
+            let normal = plane.truncate();
+            // Projected "radius" of the box onto the plane normal
+            let radius = half_extents.x * normal.x.abs()
+                + half_extents.y * normal.y.abs()
+                + half_extents.z * normal.z.abs();
+
+            normal.dot(center) + plane.w >= -radius
+        })
+    }
+
+    pub fn planes(&self) -> &[Vector4<f32>; 6] {
+        &self.planes
+    }
+}
@@ -1,4 +1,4 @@
-use std::f32::consts::FRAC_PI_2;
+use std::{f32::consts::FRAC_PI_2, mem::size_of, num::NonZeroU64};
 
 use cgmath::{InnerSpace, Matrix4, Point3, Rad, Vector3};
 
@@ -19,6 +19,12 @@ pub use camera_change::*;
 mod projection;
 pub use projection::*;
 
+mod frustum;
+pub use frustum::*;
+
+mod u_frustum;
+pub use u_frustum::*;
+
 #[derive(Debug)]
 pub struct Camera {
     position: Point3<f32>,
@@ -53,7 +59,7 @@ impl Camera {
                 ty: BindingType::Buffer {
                     ty: BufferBindingType::Uniform,
                     has_dynamic_offset: false,
-                    min_binding_size: None,
+                    min_binding_size: NonZeroU64::new(size_of::<UCamera>() as u64),
                 },
                 count: None,
             }],
@@ -122,6 +128,14 @@ impl Camera {
         )
     }
 
+    pub fn calculate_view_projection_matrix(&self) -> Matrix4<f32> {
+        self.projection.calculate_matrix() * self.calculate_matrix()
+    }
+
+    pub fn frustum(&self) -> Frustum {
+        Frustum::from_view_projection_matrix(&self.calculate_view_projection_matrix())
+    }
+
     pub fn update_buffer(&mut self, logical_device: &LogicalDevice) {
         let uniform = UCamera::from_camera(self);
 
@@ -30,9 +30,7 @@ impl UCamera {
     pub fn from_camera(camera: &Camera) -> Self {
         Self {
             position: camera.position().to_homogeneous().into(),
-            view_projection_matrix: (camera.projection().calculate_matrix()
-                * camera.calculate_matrix())
-            .into(),
+            view_projection_matrix: camera.calculate_view_projection_matrix().into(),
         }
     }
 }
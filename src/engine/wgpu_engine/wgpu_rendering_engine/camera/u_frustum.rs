@@ -0,0 +1,24 @@
+use bytemuck::{Pod, Zeroable};
+
+use super::Frustum;
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Pod, Zeroable)]
+pub struct UFrustum {
+    /// Planes of the frustum as `(normal.x, normal.y, normal.z, distance)`
+    planes: [[f32; 4]; 6],
+}
+
+impl UFrustum {
+    pub fn new(planes: [[f32; 4]; 6]) -> Self {
+        Self { planes }
+    }
+
+    pub fn empty() -> Self {
+        Self::new([[0.0; 4]; 6])
+    }
+
+    pub fn from_frustum(frustum: &Frustum) -> Self {
+        Self::new(frustum.planes().map(|x| x.into()))
+    }
+}
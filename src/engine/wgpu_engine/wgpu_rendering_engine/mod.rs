@@ -1,18 +1,23 @@
+use std::{collections::HashMap, iter::once};
+
 use logical_device::LogicalDevice;
 use wgpu::{
-    include_wgsl, Adapter, BlendState, ColorTargetState, ColorWrites, CompareFunction,
-    DepthBiasState, DepthStencilState, Device, Extent3d, Face, FragmentState, FrontFace, Instance,
-    MultisampleState, PipelineLayoutDescriptor, PolygonMode, PrimitiveState, PrimitiveTopology,
-    RenderPipeline, RenderPipelineDescriptor, ShaderModule, StencilState, SurfaceConfiguration,
-    TextureFormat, VertexState,
+    Adapter, CommandEncoder, CommandEncoderDescriptor, Features, Instance, RenderPipeline,
+    SurfaceConfiguration, TextureFormat, TextureView,
 };
 use winit::window::Window;
 
+#[cfg(debug_assertions)]
+use crate::engine::RenderStatistics;
 use crate::engine::{
-    logical_device, DepthTexture, EngineResult, StandardAmbientLight, StandardInstance,
-    StandardMaterial, StandardPointLight, TAmbientLight, TComputingEngine, TInstance, TMaterial,
-    TPointLight, TRenderingEngine, TVertex, VertexPoint,
+    logical_device, BoxedRenderNode, DepthTexture, EngineError, EngineResult, InstanceCuller,
+    LightClusterer, MaterialPipelineDescriptor, PostProcessChange, PostProcessSettings,
+    PostProcessor, RenderFrame, RenderGraph, RenderGraphChange, RenderResource, ShadowRenderer,
+    SkyboxRenderer, StandardInstance, TComputingEngine, TInstance, TRenderingEngine, TVertex,
+    TextureHelper, VertexPoint,
 };
+#[cfg(debug_assertions)]
+use crate::engine::{DebugDraw, ShaderPreprocessor, ShaderWatcher};
 
 use super::wgpu_computing_engine::WGPUComputingEngine;
 
@@ -22,127 +27,375 @@ pub use surface::*;
 mod camera;
 pub use camera::*;
 
+mod pipeline_cache;
+pub use pipeline_cache::*;
+
 pub struct WGPURenderingEngine {
     computing_engine: WGPUComputingEngine,
     surface: Surface,
-    render_pipeline: RenderPipeline,
-    depth_texture: DepthTexture,
+    pipeline_cache: PipelineCache,
+    /// Pipeline of each distinct material pipeline descriptor for the current
+    /// sample count, `None` if the material failed to compile. The full
+    /// [`PipelineKey`] only gets built when a descriptor isn't in here yet.
+    material_pipelines: HashMap<MaterialPipelineDescriptor, Option<PipelineId>>,
+    sample_count: u32,
+    render_graph: RenderGraph,
+    instance_culler: InstanceCuller,
+    shadow_renderer: ShadowRenderer,
+    light_clusterer: LightClusterer,
+    skybox_renderer: SkyboxRenderer,
+    post_processor: PostProcessor,
+    #[cfg(debug_assertions)]
+    shader_watcher: ShaderWatcher,
+    #[cfg(debug_assertions)]
+    last_render_statistics: RenderStatistics,
 }
 
 impl WGPURenderingEngine {
     pub fn new(window: &Window) -> EngineResult<Self> {
         let (computing_engine, surface) = Surface::from_window(window)?;
 
-        let render_pipeline = Self::make_render_pipeline(
-            computing_engine.logical_device(),
-            surface.surface_texture_format(),
-        )?;
+        let sample_count = 1;
+
+        let instance_culler = InstanceCuller::new(computing_engine.logical_device());
 
-        let depth_texture = DepthTexture::from_empty(
+        let shadow_renderer = ShadowRenderer::new(computing_engine.logical_device());
+
+        let light_clusterer = LightClusterer::new(computing_engine.logical_device());
+
+        let skybox_renderer = SkyboxRenderer::new(computing_engine.logical_device(), sample_count);
+
+        let post_processor = PostProcessor::new(
             computing_engine.logical_device(),
-            Extent3d {
-                width: window.inner_size().width,
-                height: window.inner_size().height,
-                depth_or_array_layers: 1,
-            },
-            DepthTexture::TEXTURE_FORMAT,
-            &DepthTexture::SAMPLER_DESCRIPTOR,
-            Some("Depth Texture"),
+            surface.surface_texture_format(),
+            window.inner_size().width,
+            window.inner_size().height,
+            PostProcessSettings::default(),
         )?;
 
         Ok(Self {
             computing_engine,
             surface,
-            render_pipeline,
-            depth_texture,
+            pipeline_cache: PipelineCache::new(),
+            material_pipelines: HashMap::new(),
+            sample_count,
+            render_graph: RenderGraph::standard(),
+            instance_culler,
+            shadow_renderer,
+            light_clusterer,
+            skybox_renderer,
+            post_processor,
+            #[cfg(debug_assertions)]
+            shader_watcher: ShaderWatcher::new(ShaderPreprocessor::source_directories()),
+            #[cfg(debug_assertions)]
+            last_render_statistics: RenderStatistics::default(),
         })
     }
 
-    fn make_shader(device: &Device) -> ShaderModule {
-        device.create_shader_module(include_wgsl!("../../shaders/new_engine.wgsl"))
+    /// Recreates the size dependent render targets.
+    /// Must be called whenever the surface gets resized.
+    pub fn resize_render_targets(&mut self, width: u32, height: u32) -> EngineResult<()> {
+        self.render_graph.resize();
+
+        self.post_processor
+            .resize(self.computing_engine.logical_device(), width, height)
     }
 
-    fn make_render_pipeline(
-        logical_device: &LogicalDevice,
-        surface_texture_format: TextureFormat,
-    ) -> EngineResult<RenderPipeline> {
-        let main_shader = Self::make_shader(logical_device.device());
+    /// MSAA sample counts out of 1, 2, 4 & 8 supported by both
+    /// the HDR and the depth format
+    pub fn supported_sample_counts(&self) -> Vec<u32> {
+        let device_features = self.device().features();
+        let format_features = |format: TextureFormat| {
+            if device_features.contains(Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES) {
+                self.adapter().get_texture_format_features(format)
+            } else {
+                format.guaranteed_format_features(device_features)
+            }
+        };
 
-        let render_pipeline_layout =
-            logical_device
-                .device()
-                .create_pipeline_layout(&PipelineLayoutDescriptor {
-                    label: Some("Render Pipeline Layout"),
-                    bind_group_layouts: &[
-                        &StandardMaterial::bind_group_layout(logical_device),
-                        &Camera::bind_group_layout(logical_device),
-                        &StandardAmbientLight::bind_group_layout(logical_device),
-                        &StandardPointLight::bind_group_layout(logical_device),
-                    ],
-                    push_constant_ranges: &[],
-                });
+        let hdr_features = format_features(PostProcessor::HDR_TEXTURE_FORMAT);
+        let depth_features = format_features(DepthTexture::TEXTURE_FORMAT);
+
+        [1, 2, 4, 8]
+            .into_iter()
+            .filter(|x| {
+                hdr_features.flags.sample_count_supported(*x)
+                    && depth_features.flags.sample_count_supported(*x)
+            })
+            .collect()
+    }
+
+    /// Changes the MSAA sample count (1 = no MSAA).
+    /// Switches the render pipelines, the render graph
+    /// recreates its targets with the new sample count.
+    pub fn set_sample_count(&mut self, sample_count: u32) -> EngineResult<()> {
+        if !self.supported_sample_counts().contains(&sample_count) {
+            return Err(EngineError::UnsupportedSampleCount(sample_count));
+        }
+        if sample_count == self.sample_count {
+            return Ok(());
+        }
+
+        // Get resolved again with the new sample count
+        self.material_pipelines.clear();
+        self.skybox_renderer = SkyboxRenderer::new(self.logical_device(), sample_count);
+        self.sample_count = sample_count;
+
+        Ok(())
+    }
+
+    pub fn sample_count(&self) -> u32 {
+        self.sample_count
+    }
+
+    pub fn skybox_renderer(&self) -> &SkyboxRenderer {
+        &self.skybox_renderer
+    }
 
-        Ok(logical_device
+    pub fn post_processor(&self) -> &PostProcessor {
+        &self.post_processor
+    }
+
+    pub fn post_processor_mut(&mut self) -> &mut PostProcessor {
+        &mut self.post_processor
+    }
+
+    /// Applies a change to the post-processing chain
+    pub fn apply_post_process_change(
+        &mut self,
+        post_process_change: PostProcessChange,
+    ) -> EngineResult<()> {
+        self.post_processor
+            .apply_post_process_change(self.computing_engine.logical_device(), post_process_change)
+    }
+
+    /// Runs the post-processing chain on the rendered scene
+    /// and writes the result onto the surface
+    pub fn post_process(
+        &mut self,
+        command_encoder: &mut CommandEncoder,
+        surface_texture_view: &TextureView,
+    ) {
+        self.post_processor.render(
+            self.computing_engine.logical_device(),
+            command_encoder,
+            surface_texture_view,
+        );
+    }
+
+    pub fn render_graph(&self) -> &RenderGraph {
+        &self.render_graph
+    }
+
+    pub fn add_render_node(&mut self, node: BoxedRenderNode) {
+        self.render_graph.add_node(node);
+    }
+
+    /// Removes all render graph nodes with the given label
+    pub fn remove_render_node(&mut self, label: &str) {
+        self.render_graph.remove_node(label);
+    }
+
+    pub fn apply_render_graph_change(&mut self, render_graph_change: RenderGraphChange) {
+        self.render_graph
+            .apply_render_graph_change(render_graph_change);
+    }
+
+    /// Renders the frame through the [`RenderGraph`] & presents it
+    pub fn render(&mut self, frame: &RenderFrame<'_>) -> EngineResult<()> {
+        // Material pipelines, created once per material type
+        for mesh in frame.meshes() {
+            if let Err(e) = self.prepare_material_pipeline(&mesh.material().pipeline_descriptor()) {
+                log::error!("Failed to create material pipeline: {:?}", e);
+            }
+        }
+
+        let surface_texture = self.surface_texture()?;
+        let imported = vec![
+            (RenderResource::SURFACE, surface_texture.make_texture_view()),
+            (
+                RenderResource::SCENE,
+                self.post_processor
+                    .scene_texture()
+                    .create_view(&Default::default()),
+            ),
+        ];
+
+        let mut command_encoder = self
             .device()
-            .create_render_pipeline(&RenderPipelineDescriptor {
-                label: Some("Render Pipeline"),
-                layout: Some(&render_pipeline_layout),
-                // Vertex shader
-                vertex: VertexState {
-                    module: &main_shader,
-                    entry_point: "vs_main",
-                    // Vertex buffers
-                    buffers: &[
-                        VertexPoint::descriptor::<VertexPoint>(),
-                        StandardInstance::descriptor(),
-                    ],
-                },
-                // Fragment shader
-                fragment: Some(FragmentState {
-                    module: &main_shader,
-                    entry_point: "fs_main",
-                    // Store the resulting colours in a format
-                    // that is equal to the surface format
-                    targets: &[Some(ColorTargetState {
-                        // Match the surface format
-                        format: surface_texture_format,
-                        // Replace pixels
-                        blend: Some(BlendState::REPLACE),
-                        // Use all colour channels
-                        write_mask: ColorWrites::ALL,
-                    })],
-                }),
-                // How to interpret the vertices
-                primitive: PrimitiveState {
-                    // Every three vertices form a triangle
-                    topology: PrimitiveTopology::TriangleList,
-                    strip_index_format: None,
-                    // A given triangle is is facing "forward" if it's arranged counter-clockwise
-                    front_face: FrontFace::Ccw,
-                    // Cull the triangle if it's the backside
-                    cull_mode: Some(Face::Back),
-                    // Fill the triangle
-                    // Note: requires Features::NON_FILL_POLYGON_MODE if not Fill
-                    polygon_mode: PolygonMode::Fill,
-                    // Note: requires Features::DEPTH_CLIP_CONTROL
-                    unclipped_depth: false,
-                    // Note: requires Features::CONSERVATIVE_RASTERIZATION
-                    conservative: false,
-                },
-                depth_stencil: Some(DepthStencilState {
-                    format: DepthTexture::TEXTURE_FORMAT,
-                    depth_write_enabled: true,
-                    depth_compare: CompareFunction::Less,
-                    stencil: StencilState::default(),
-                    bias: DepthBiasState::default(),
-                }),
-                multisample: MultisampleState {
-                    count: 1,
-                    mask: !0,
-                    alpha_to_coverage_enabled: false,
-                },
-                multiview: None,
-            }))
+            .create_command_encoder(&CommandEncoderDescriptor {
+                label: Some("Command Encoder"),
+            });
+
+        #[cfg(debug_assertions)]
+        let mut statistics = frame.culling_statistics();
+
+        // Taken out while executing, as its nodes use the engine
+        let mut render_graph = std::mem::take(&mut self.render_graph);
+        let result = render_graph.execute(
+            self,
+            frame,
+            &mut command_encoder,
+            imported,
+            #[cfg(debug_assertions)]
+            &mut statistics,
+        );
+        self.render_graph = render_graph;
+
+        // Debug lines only last one frame, even if no node drew them
+        #[cfg(debug_assertions)]
+        DebugDraw::take_lines();
+
+        result?;
+
+        self.queue().submit(once(command_encoder.finish()));
+        surface_texture.present();
+
+        #[cfg(debug_assertions)]
+        {
+            self.last_render_statistics = statistics;
+        }
+
+        Ok(())
+    }
+
+    /// Counters of the last rendered frame
+    #[cfg(debug_assertions)]
+    pub fn last_render_statistics(&self) -> &RenderStatistics {
+        &self.last_render_statistics
+    }
+
+    pub fn instance_culler(&self) -> &InstanceCuller {
+        &self.instance_culler
+    }
+
+    pub fn shadow_renderer(&self) -> &ShadowRenderer {
+        &self.shadow_renderer
+    }
+
+    pub fn light_clusterer(&self) -> &LightClusterer {
+        &self.light_clusterer
+    }
+
+    /// Resolves the render pipeline of the material descriptor (type, defines
+    /// & render state) for the current sample count, if it isn't resolved yet.
+    /// Fails only once per descriptor, meshes of a failed descriptor
+    /// aren't drawn.
+    pub fn prepare_material_pipeline(
+        &mut self,
+        descriptor: &MaterialPipelineDescriptor,
+    ) -> EngineResult<()> {
+        if self.material_pipelines.contains_key(descriptor) {
+            return Ok(());
+        }
+
+        let key = Self::material_pipeline_key(descriptor, self.sample_count);
+        let pipeline_id = self
+            .pipeline_cache
+            .get_or_create(self.computing_engine.logical_device(), &key);
+
+        self.material_pipelines
+            .insert(descriptor.clone(), pipeline_id.as_ref().ok().copied());
+        pipeline_id.map(|_| ())
+    }
+
+    /// Pipeline of the material descriptor, see [`Self::prepare_material_pipeline`]
+    pub fn material_pipeline(
+        &self,
+        descriptor: &MaterialPipelineDescriptor,
+    ) -> Option<&RenderPipeline> {
+        self.material_pipelines
+            .get(descriptor)
+            .copied()
+            .flatten()
+            .map(|x| self.pipeline_cache.pipeline(x))
+    }
+
+    pub fn pipeline_cache(&self) -> &PipelineCache {
+        &self.pipeline_cache
+    }
+
+    /// Hot-reload: recreates the pipelines of the shaders changed on disk
+    /// (see [`ShaderWatcher`]), keeping the old ones if they fail to compile.
+    /// Material types which failed to compile get another try.
+    #[cfg(debug_assertions)]
+    pub fn reload_changed_shaders(&mut self) {
+        let changed_shaders = self.shader_watcher.poll();
+        if changed_shaders.is_empty() {
+            return;
+        }
+        log::info!("Shaders changed: {:?}", changed_shaders);
+
+        let logical_device = self.computing_engine.logical_device();
+
+        self.pipeline_cache.reload(logical_device, &changed_shaders);
+        self.material_pipelines.retain(|_, x| x.is_some());
+
+        let results = [
+            (
+                "light clustering",
+                self.light_clusterer
+                    .reload_shader(logical_device, &changed_shaders),
+            ),
+            (
+                "instance culling",
+                self.instance_culler
+                    .reload_shader(logical_device, &changed_shaders),
+            ),
+            (
+                "shadow",
+                self.shadow_renderer
+                    .reload_shader(logical_device, &changed_shaders),
+            ),
+            (
+                "skybox",
+                self.skybox_renderer
+                    .reload_shader(logical_device, &changed_shaders),
+            ),
+        ];
+        for (name, result) in results {
+            if let Err(e) = result {
+                log::error!(
+                    "Failed to reload {} pipeline, keeping the old one: {:?}",
+                    name,
+                    e
+                );
+            }
+        }
+
+        self.post_processor
+            .reload_shaders(logical_device, &changed_shaders);
+        self.render_graph
+            .reload_shaders(logical_device, &changed_shaders);
+    }
+
+    /// Material shader rendered into the HDR scene texture
+    /// with the given sample count
+    fn material_pipeline_key(
+        descriptor: &MaterialPipelineDescriptor,
+        sample_count: u32,
+    ) -> PipelineKey {
+        PipelineKey {
+            label: descriptor.id,
+            shader_path: descriptor.shader_path,
+            shader_defines: descriptor.shader_defines.clone(),
+            vertex_layouts: vec![
+                VertexPoint::descriptor::<VertexPoint>(),
+                StandardInstance::descriptor(),
+            ],
+            material_bind_group_layout: descriptor.bind_group_layout_descriptor.entries,
+            // Store the resulting colours in HDR,
+            // the post-processing maps them onto the surface
+            color_format: PostProcessor::HDR_TEXTURE_FORMAT,
+            depth_format: DepthTexture::TEXTURE_FORMAT,
+            sample_count,
+            blend: descriptor.blend,
+            cull_mode: descriptor.cull_mode,
+            polygon_mode: descriptor.polygon_mode,
+            topology: descriptor.topology,
+            depth_write_enabled: descriptor.depth_write_enabled,
+            depth_compare: descriptor.depth_compare,
+        }
     }
 }
 
@@ -182,12 +435,81 @@ impl TRenderingEngine for WGPURenderingEngine {
     fn surface_texture_format(&self) -> TextureFormat {
         self.surface.surface_texture_format()
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use wgpu::Face;
+
+    use super::*;
+    use crate::engine::{AlphaMode, StandardMaterial};
+
+    fn descriptor() -> MaterialPipelineDescriptor {
+        MaterialPipelineDescriptor::new(
+            "StandardMaterial",
+            "blinn_phong.wgsl",
+            &StandardMaterial::BIND_GROUP_LAYOUT_DESCRIPTOR,
+        )
+    }
+
+    #[test]
+    fn render_state_is_part_of_the_material_pipeline_key() {
+        let back = WGPURenderingEngine::material_pipeline_key(&descriptor(), 1);
+        let none =
+            WGPURenderingEngine::material_pipeline_key(&descriptor().with_cull_mode(None), 1);
+        let front = WGPURenderingEngine::material_pipeline_key(
+            &descriptor().with_cull_mode(Some(Face::Front)),
+            1,
+        );
+
+        assert_ne!(back, none);
+        assert_ne!(back, front);
+        assert_eq!(
+            back,
+            WGPURenderingEngine::material_pipeline_key(&descriptor(), 1)
+        );
+    }
+
+    #[test]
+    fn equal_descriptors_share_a_material_pipeline_entry() {
+        let mut material_pipelines: HashMap<_, Option<PipelineId>> = HashMap::new();
+        material_pipelines.insert(descriptor(), None);
+        material_pipelines.insert(descriptor(), None);
+        material_pipelines.insert(descriptor().with_cull_mode(None), None);
+        material_pipelines.insert(descriptor().with_alpha_mode(AlphaMode::Blend), None);
 
-    fn depth_texture(&self) -> Option<&DepthTexture> {
-        Some(&self.depth_texture)
+        assert_eq!(material_pipelines.len(), 3);
+        assert!(material_pipelines.contains_key(&descriptor()));
     }
 
-    fn render_pipeline(&self) -> &RenderPipeline {
-        &self.render_pipeline
+    #[test]
+    #[ignore = "requires a GPU adapter"]
+    fn cull_modes_resolve_to_different_pipelines() {
+        let computing_engine = WGPUComputingEngine::new().unwrap();
+        let logical_device = computing_engine.logical_device();
+        let mut pipeline_cache = PipelineCache::new();
+
+        let back = pipeline_cache
+            .get_or_create(
+                logical_device,
+                &WGPURenderingEngine::material_pipeline_key(&descriptor(), 1),
+            )
+            .unwrap();
+        let none = pipeline_cache
+            .get_or_create(
+                logical_device,
+                &WGPURenderingEngine::material_pipeline_key(&descriptor().with_cull_mode(None), 1),
+            )
+            .unwrap();
+        let back_again = pipeline_cache
+            .get_or_create(
+                logical_device,
+                &WGPURenderingEngine::material_pipeline_key(&descriptor(), 1),
+            )
+            .unwrap();
+
+        assert_ne!(back, none);
+        assert_eq!(back, back_again);
+        assert_eq!(pipeline_cache.len(), 2);
     }
 }
@@ -0,0 +1,213 @@
+use std::collections::HashMap;
+
+use wgpu::{
+    BindGroupLayout, BindGroupLayoutDescriptor, ColorTargetState, ColorWrites, DepthBiasState,
+    DepthStencilState, ErrorFilter, FragmentState, FrontFace, MultisampleState,
+    PipelineLayoutDescriptor, PrimitiveState, RenderPipeline, RenderPipelineDescriptor,
+    StencilState, VertexState,
+};
+
+use crate::engine::{
+    Camera, EngineError, EngineResult, EnvironmentMap, LightClusterer, LightStorage, LogicalDevice,
+    PreprocessedShader, ShaderPreprocessor, ShadowMaps, StandardAmbientLight, TAmbientLight,
+};
+
+mod pipeline_key;
+pub use pipeline_key::*;
+
+/// Handle of a pipeline inside a [`PipelineCache`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PipelineId(usize);
+
+/// Creates the main pass render pipelines on demand and keeps them around,
+/// so switching e.g. the sample count back and forth doesn't recompile anything
+#[derive(Debug, Default)]
+pub struct PipelineCache {
+    pipelines: Vec<RenderPipeline>,
+    ids: HashMap<PipelineKey, PipelineId>,
+}
+
+impl PipelineCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the pipeline of the key, creating it if there is none yet.
+    /// Failed pipelines aren't cached, the caller has to avoid retrying every frame.
+    pub fn get_or_create(
+        &mut self,
+        logical_device: &LogicalDevice,
+        key: &PipelineKey,
+    ) -> EngineResult<PipelineId> {
+        if let Some(id) = self.ids.get(key) {
+            return Ok(*id);
+        }
+
+        let shader = Self::preprocess_shader(key)?;
+        let render_pipeline = Self::make_render_pipeline(logical_device, key, &shader)?;
+
+        let id = PipelineId(self.pipelines.len());
+        self.pipelines.push(render_pipeline);
+        self.ids.insert(key.clone(), id);
+
+        log::debug!("Created render pipeline '{}' ({:?})", key.label, id);
+
+        Ok(id)
+    }
+
+    pub fn pipeline(&self, id: PipelineId) -> &RenderPipeline {
+        &self.pipelines[id.0]
+    }
+
+    /// Amount of cached pipelines
+    pub fn len(&self) -> usize {
+        self.pipelines.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pipelines.is_empty()
+    }
+
+    /// Recreates the pipelines whose shaders depend on one of the changed shaders.
+    /// A pipeline failing to compile keeps its old version, the error gets logged.
+    #[cfg(debug_assertions)]
+    pub fn reload(&mut self, logical_device: &LogicalDevice, changed_shaders: &[String]) {
+        for (key, id) in &self.ids {
+            let result = Self::preprocess_shader(key).and_then(|shader| {
+                if !changed_shaders.iter().any(|x| shader.depends_on(x)) {
+                    return Ok(None);
+                }
+                Self::make_render_pipeline(logical_device, key, &shader).map(Some)
+            });
+
+            match result {
+                Ok(Some(render_pipeline)) => {
+                    self.pipelines[id.0] = render_pipeline;
+                    log::info!("Reloaded render pipeline '{}' ({:?})", key.label, id);
+                }
+                Ok(None) => {}
+                Err(e) => log::error!(
+                    "Failed to reload render pipeline '{}', keeping the old one: {:?}",
+                    key.label,
+                    e
+                ),
+            }
+        }
+    }
+
+    fn preprocess_shader(key: &PipelineKey) -> EngineResult<PreprocessedShader> {
+        ShaderPreprocessor::new()
+            .with_defines(key.shader_defines.iter().copied())
+            .process_file(key.shader_path)
+    }
+
+    /// Invalid shaders or states result in an [`EngineError::ShaderError`],
+    /// shaders not matching the layouts in an [`EngineError::ShaderLayoutMismatch`],
+    /// instead of a panic
+    fn make_render_pipeline(
+        logical_device: &LogicalDevice,
+        key: &PipelineKey,
+        shader: &PreprocessedShader,
+    ) -> EngineResult<RenderPipeline> {
+        let material_label = format!("{} Bind Group Layout", key.label);
+        let bind_group_layout_descriptors = [
+            &BindGroupLayoutDescriptor {
+                label: Some(&material_label),
+                entries: key.material_bind_group_layout,
+            },
+            &Camera::BIND_GROUP_LAYOUT_DESCRIPTOR,
+            &StandardAmbientLight::BIND_GROUP_LAYOUT_DESCRIPTOR,
+            &LightStorage::BIND_GROUP_LAYOUT_DESCRIPTOR,
+            &ShadowMaps::BIND_GROUP_LAYOUT_DESCRIPTOR,
+            &LightClusterer::BIND_GROUP_LAYOUT_DESCRIPTOR,
+            &EnvironmentMap::BIND_GROUP_LAYOUT_DESCRIPTOR,
+        ];
+
+        let reflection = shader.reflect()?;
+        reflection.validate_vertex_buffers("vs_main", &key.vertex_layouts)?;
+        reflection
+            .validate_bind_group_layouts(&["vs_main", "fs_main"], &bind_group_layout_descriptors)?;
+        let shader =
+            reflection.create_shader_module(logical_device, &format!("{} Shader", key.label));
+
+        logical_device
+            .device()
+            .push_error_scope(ErrorFilter::Validation);
+
+        let bind_group_layouts: Vec<BindGroupLayout> = bind_group_layout_descriptors
+            .iter()
+            .map(|x| logical_device.device().create_bind_group_layout(x))
+            .collect();
+
+        let render_pipeline_layout =
+            logical_device
+                .device()
+                .create_pipeline_layout(&PipelineLayoutDescriptor {
+                    label: Some("Render Pipeline Layout"),
+                    bind_group_layouts: &bind_group_layouts.iter().collect::<Vec<_>>(),
+                    push_constant_ranges: &[],
+                });
+
+        let render_pipeline =
+            logical_device
+                .device()
+                .create_render_pipeline(&RenderPipelineDescriptor {
+                    label: Some(&format!("{} Render Pipeline", key.label)),
+                    layout: Some(&render_pipeline_layout),
+                    // Vertex shader
+                    vertex: VertexState {
+                        module: &shader,
+                        entry_point: "vs_main",
+                        // Vertex buffers
+                        buffers: &key.vertex_layouts,
+                    },
+                    // Fragment shader
+                    fragment: Some(FragmentState {
+                        module: &shader,
+                        entry_point: "fs_main",
+                        targets: &[Some(ColorTargetState {
+                            format: key.color_format,
+                            blend: Some(key.blend),
+                            // Use all colour channels
+                            write_mask: ColorWrites::ALL,
+                        })],
+                    }),
+                    // How to interpret the vertices
+                    primitive: PrimitiveState {
+                        topology: key.topology,
+                        strip_index_format: None,
+                        // A given triangle is is facing "forward" if it's arranged counter-clockwise
+                        front_face: FrontFace::Ccw,
+                        // Usually culls the triangle if it's the backside
+                        cull_mode: key.cull_mode,
+                        polygon_mode: key.polygon_mode,
+                        // Note: requires Features::DEPTH_CLIP_CONTROL
+                        unclipped_depth: false,
+                        // Note: requires Features::CONSERVATIVE_RASTERIZATION
+                        conservative: false,
+                    },
+                    depth_stencil: Some(DepthStencilState {
+                        format: key.depth_format,
+                        depth_write_enabled: key.depth_write_enabled,
+                        depth_compare: key.depth_compare,
+                        stencil: StencilState::default(),
+                        bias: DepthBiasState::default(),
+                    }),
+                    multisample: MultisampleState {
+                        count: key.sample_count,
+                        mask: !0,
+                        alpha_to_coverage_enabled: false,
+                    },
+                    multiview: None,
+                });
+
+        if let Some(error) = pollster::block_on(logical_device.device().pop_error_scope()) {
+            return Err(EngineError::ShaderError(format!(
+                "Pipeline '{}': {}",
+                key.label, error
+            )));
+        }
+
+        Ok(render_pipeline)
+    }
+}
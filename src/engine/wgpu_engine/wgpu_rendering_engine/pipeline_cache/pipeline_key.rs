@@ -0,0 +1,29 @@
+use wgpu::{
+    BindGroupLayoutEntry, BlendState, CompareFunction, Face, PolygonMode, PrimitiveTopology,
+    TextureFormat, VertexBufferLayout,
+};
+
+/// Everything a main pass render pipeline depends on.
+/// Equal keys result in the same pipeline, see [`super::PipelineCache`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PipelineKey {
+    /// Only used for labels and errors
+    pub label: &'static str,
+    /// WGSL shader with `vs_main` & `fs_main`, see [`crate::engine::ShaderPreprocessor`]
+    pub shader_path: &'static str,
+    /// Sorted, so the order they got added in doesn't matter
+    pub shader_defines: Vec<&'static str>,
+    pub vertex_layouts: Vec<VertexBufferLayout<'static>>,
+    /// Group 0, all other groups are the same for every pipeline
+    pub material_bind_group_layout: &'static [BindGroupLayoutEntry],
+    pub color_format: TextureFormat,
+    pub depth_format: TextureFormat,
+    pub sample_count: u32,
+    pub blend: BlendState,
+    pub cull_mode: Option<Face>,
+    /// `Line` & `Point` require `Features::POLYGON_MODE_LINE` / `POLYGON_MODE_POINT`
+    pub polygon_mode: PolygonMode,
+    pub topology: PrimitiveTopology,
+    pub depth_write_enabled: bool,
+    pub depth_compare: CompareFunction,
+}
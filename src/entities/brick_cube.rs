@@ -49,7 +49,7 @@ impl TEntity for BrickCube {
             logical_device,
             "cube/cube.gltf",
             instances,
-            MaterialLoading::Replace(material),
+            MaterialLoading::Replace(Box::new(material)),
         )?;
 
         self.mesh = Some(mesh);
@@ -60,4 +60,8 @@ impl TEntity for BrickCube {
     fn meshes(&self) -> Vec<&dyn TMesh> {
         vec![self.mesh.as_ref().unwrap()]
     }
+
+    fn meshes_mut(&mut self) -> Vec<&mut dyn TMesh> {
+        vec![self.mesh.as_mut().unwrap()]
+    }
 }
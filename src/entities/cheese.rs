@@ -56,4 +56,8 @@ impl TEntity for Cheese {
     fn meshes(&self) -> Vec<&dyn TMesh> {
         vec![self.mesh.as_ref().unwrap()]
     }
+
+    fn meshes_mut(&mut self) -> Vec<&mut dyn TMesh> {
+        vec![self.mesh.as_mut().unwrap()]
+    }
 }
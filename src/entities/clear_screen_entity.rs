@@ -2,6 +2,8 @@ use crate::app::{
     EntityAction, EntityConfiguration, InputHandler, TEntity, UpdateFrequency, World,
 };
 
+/// Sets a sky blue clear color.
+/// For an actual sky, see `WorldBuilder::with_skybox`.
 #[derive(Debug, Default)]
 pub struct ClearScreenEntity {}
 
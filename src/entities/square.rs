@@ -66,6 +66,7 @@ impl Square {
             normal_coordinates: [0.0, 0.0, 0.0],
             tangent: [0.0; 3],
             bitangent: [0.0; 3],
+            color: [1.0; 4],
         },
         // B
         VertexPoint {
@@ -74,6 +75,7 @@ impl Square {
             normal_coordinates: [0.0, 0.0, 0.0],
             tangent: [0.0; 3],
             bitangent: [0.0; 3],
+            color: [1.0; 4],
         },
         // C
         VertexPoint {
@@ -82,6 +84,7 @@ impl Square {
             normal_coordinates: [0.0, 0.0, 0.0],
             tangent: [0.0; 3],
             bitangent: [0.0; 3],
+            color: [1.0; 4],
         },
         // D
         VertexPoint {
@@ -90,6 +93,7 @@ impl Square {
             normal_coordinates: [0.0, 0.0, 0.0],
             tangent: [0.0; 3],
             bitangent: [0.0; 3],
+            color: [1.0; 4],
         },
     ];
 
@@ -181,4 +185,8 @@ impl TEntity for Square {
     fn meshes(&self) -> Vec<&dyn TMesh> {
         vec![self.mesh.as_ref().unwrap()]
     }
+
+    fn meshes_mut(&mut self) -> Vec<&mut dyn TMesh> {
+        vec![self.mesh.as_mut().unwrap()]
+    }
 }
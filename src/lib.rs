@@ -1,3 +1,6 @@
+// `#[derive(GpuType)]` refers to `::wgpu_engine`, also from inside this crate
+extern crate self as wgpu_engine;
+
 pub mod engine;
 
 pub mod app;
@@ -16,12 +16,12 @@ fn main() {
         .with_entity_tag_duplication_behaviour(EntityTagDuplicationBehaviour::WarnOnDuplication)
         // .with_ambient_light(rgb_to_f32_color(255u8, 50u8, 50u8), 0.25)
         .with_ambient_light(rgb_to_f32_color(50u8, 50u8, 50u8), 0.1)
+        .with_msaa_sample_count(4)
+        .with_light_gizmos(true)
         .with_point_light(
-            // TODO: Spawn cube for debugging at location
-            0,
             rgb_to_f32_color(255u8, 255u8, 255u8).into(),
             Vector3::new(0.0, 2.0, 0.0),
-            1.0, // TODO
+            10.0,
         )
         .with_entities(vec![
             Box::new(CameraControllingEntity::new()),
@@ -0,0 +1,127 @@
+//! Derive macros of the `wgpu_engine` crate, see `wgpu_engine::engine::TGpuType`
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Error, Fields, LitStr};
+
+/// Implements `TGpuType` for a structure with named fields,
+/// each of which has to implement `TGpuType` as well.
+///
+/// The WGSL structure is named after the Rust structure,
+/// unless overridden with `#[gpu_type(name = "PointLight")]`.
+#[proc_macro_derive(GpuType, attributes(gpu_type))]
+pub fn derive_gpu_type(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    expand_gpu_type(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+fn expand_gpu_type(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let ident = &input.ident;
+
+    let mut wgsl_name = ident.to_string();
+    for attribute in input.attrs.iter().filter(|x| x.path().is_ident("gpu_type")) {
+        attribute.parse_nested_meta(|meta| {
+            if meta.path.is_ident("name") {
+                wgsl_name = meta.value()?.parse::<LitStr>()?.value();
+                Ok(())
+            } else {
+                Err(meta.error("Expected `name = \"...\"`"))
+            }
+        })?;
+    }
+
+    if !input.generics.params.is_empty() {
+        return Err(Error::new_spanned(
+            &input.generics,
+            "GpuType can't be derived for generic structures",
+        ));
+    }
+    let Data::Struct(data) = &input.data else {
+        return Err(Error::new_spanned(
+            ident,
+            "GpuType can only be derived for structures",
+        ));
+    };
+    let Fields::Named(fields) = &data.fields else {
+        return Err(Error::new_spanned(
+            ident,
+            "GpuType can only be derived for structures with named fields",
+        ));
+    };
+    if fields.named.is_empty() {
+        return Err(Error::new_spanned(
+            ident,
+            "WGSL structures need at least one member",
+        ));
+    }
+
+    let names: Vec<_> = fields
+        .named
+        .iter()
+        .filter_map(|x| x.ident.as_ref())
+        .collect();
+    let name_strings: Vec<_> = names.iter().map(|x| x.to_string()).collect();
+    let types: Vec<_> = fields.named.iter().map(|x| &x.ty).collect();
+    let indices = 0..names.len();
+
+    let engine = quote!(::wgpu_engine::engine);
+    // Computed once per layout instead of on every call,
+    // `slice_to_bytes` asks for the size & writes every element
+    let struct_layout = quote! {
+        STRUCT_LAYOUTS.get(layout, || {
+            vec![#(#engine::GpuStructMember::of::<#types>(layout)),*]
+        })
+    };
+
+    Ok(quote! {
+        const _: () = {
+            static STRUCT_LAYOUTS: #engine::GpuStructLayoutCache =
+                #engine::GpuStructLayoutCache::new();
+
+            impl #engine::TGpuType for #ident {
+                fn wgsl_type() -> String {
+                    #wgsl_name.to_string()
+                }
+
+                fn alignment(layout: #engine::GpuLayout) -> usize {
+                    #struct_layout.alignment()
+                }
+
+                fn size(layout: #engine::GpuLayout) -> usize {
+                    #struct_layout.size()
+                }
+
+                fn is_composite() -> bool {
+                    true
+                }
+
+                fn write(&self, layout: #engine::GpuLayout, bytes: &mut [u8]) {
+                    let struct_layout = #struct_layout;
+                    #(
+                        #engine::TGpuType::write(
+                            &self.#names,
+                            layout,
+                            struct_layout.member_bytes(#indices, bytes),
+                        );
+                    )*
+                }
+
+                fn wgsl_definitions(definitions: &mut Vec<String>) {
+                    #(<#types as #engine::TGpuType>::wgsl_definitions(definitions);)*
+
+                    let definition = #engine::GpuStructLayout::wgsl_struct(
+                        #wgsl_name,
+                        &[#((#name_strings, <#types as #engine::TGpuType>::wgsl_type())),*],
+                    );
+                    if !definitions.contains(&definition) {
+                        definitions.push(definition);
+                    }
+                }
+            }
+        };
+    })
+}